@@ -1,2 +1,2 @@
 ./scripts/build.sh
-cargo run
\ No newline at end of file
+cargo run --bin ld39
\ No newline at end of file
@@ -0,0 +1,160 @@
+use std::fs;
+
+use conrod::Ui;
+use loader::get_achievements_path;
+use localization::Localization;
+use serde_json;
+use state::UiAction;
+
+// the wallet balance that unlocks Tycoon
+pub const TYCOON_MONEY: i32 = 5000;
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Achievement {
+    CleanGrid,
+    SolarPioneer,
+    TaxFree,
+    Hydrologist,
+    Tycoon,
+}
+
+impl Achievement {
+    pub fn all() -> Vec<Achievement> {
+        vec![
+            Achievement::CleanGrid,
+            Achievement::SolarPioneer,
+            Achievement::TaxFree,
+            Achievement::Hydrologist,
+            Achievement::Tycoon,
+        ]
+    }
+
+    pub fn get_title(&self, localization: &Localization) -> String {
+        localization.get(&format!("achievement.{:?}", self))
+    }
+
+    pub fn get_description(&self, localization: &Localization) -> String {
+        localization.format(
+            &format!("achievement.{:?}.description", self),
+            &[("money", localization.money(TYCOON_MONEY))],
+        )
+    }
+}
+
+// unlocked achievements persist across runs in the settings directory
+#[derive(Default, Serialize, Deserialize)]
+pub struct Achievements {
+    #[serde(default)]
+    pub unlocked: Vec<Achievement>,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains(achievement)
+    }
+
+    // returns true if this is the first time the achievement was unlocked
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(&achievement) {
+            return false;
+        }
+
+        self.unlocked.push(achievement);
+        self.save();
+        true
+    }
+
+    // an unwritable file only loses progress, so the game goes on
+    fn save(&self) {
+        let path = get_achievements_path();
+        let result = serde_json::to_string(&self)
+            .map_err(|err| err.to_string())
+            .and_then(|text| fs::write(&path, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            eprintln!("Could not write {}: {}", path.display(), err);
+        }
+    }
+}
+
+widget_ids! {
+    pub struct Ids {
+        achievements_label,
+        back_button,
+        titles[],
+        descriptions[],
+    }
+}
+
+pub fn create_ui(
+    ui: &mut Ui,
+    ids: &mut Ids,
+    achievements: &Achievements,
+    localization: &Localization,
+) -> Option<UiAction> {
+    use conrod::{color, position::Relative, widget, Colorable, Labelable, Positionable, Sizeable,
+                 Widget};
+
+    let all = Achievement::all();
+    ids.titles.resize(all.len(), &mut ui.widget_id_generator());
+    ids.descriptions
+        .resize(all.len(), &mut ui.widget_id_generator());
+
+    let ui = &mut ui.set_widgets();
+
+    if widget::Button::new()
+        .top_right_with_margin_on(ui.window, 20.0)
+        .w_h(30.0, 30.0)
+        .label("X")
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .label_x(Relative::Scalar(1.0))
+        .label_y(Relative::Scalar(2.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.back_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::Close);
+    }
+
+    widget::Text::new(&localization.format(
+        "achievements.title",
+        &[
+            ("unlocked", achievements.unlocked.len().to_string()),
+            ("total", all.len().to_string()),
+        ],
+    )).mid_top_with_margin_on(ui.window, 50.0)
+        .font_size(32)
+        .rgb(0.0, 1.0, 0.0)
+        .set(ids.achievements_label, ui);
+
+    for (i, achievement) in all.iter().enumerate() {
+        // locked achievements are dimmed out
+        let (r, g, b) = if achievements.is_unlocked(achievement) {
+            (0.0, 1.0, 0.0)
+        } else {
+            (0.3, 0.3, 0.3)
+        };
+
+        let title_text = achievement.get_title(localization);
+        let title = widget::Text::new(&title_text)
+            .font_size(24)
+            .rgb(r, g, b);
+        if i == 0 {
+            title
+                .down_from(ids.achievements_label, 40.0)
+                .x_relative_to(ui.window, -200.0)
+                .set(ids.titles[i], ui);
+        } else {
+            title
+                .down_from(ids.descriptions[i - 1], 20.0)
+                .set(ids.titles[i], ui);
+        }
+
+        widget::Text::new(&achievement.get_description(localization))
+            .down_from(ids.titles[i], 5.0)
+            .font_size(18)
+            .rgb(r * 0.8, g * 0.8, b * 0.8)
+            .set(ids.descriptions[i], ui);
+    }
+
+    None
+}
@@ -0,0 +1,426 @@
+extern crate image;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+#[path = "../spritesheet.rs"]
+mod spritesheet;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use image::{imageops, Rgba, RgbaImage};
+use spritesheet::{Frame, FrameData, Meta, Size, Spritesheet};
+
+// Packs a directory of pngs into one image and the json the game reads frames from:
+//   pack_atlas <out> <dir>... [--padding n] [--trim] [--rotate] [--pot] [--max-size n]
+// writes <out>.png and <out>.json. Frames are named by their path under the directory, such as
+// techtree/coalnode.png. Several directories can be packed together, as long as no name is in more
+// than one of them
+struct Options {
+    out: PathBuf,
+    dirs: Vec<PathBuf>,
+    // transparent pixels left between frames and around the edge of the atlas
+    padding: u32,
+    // crops the fully transparent border off each sprite, the frame keeps where it was cut from
+    trim: bool,
+    // turns sprites taller than they are wide on their side, so the shelves they go on are lower
+    rotate: bool,
+    // rounds the atlas up to a power of two on both sides
+    power_of_two: bool,
+    max_size: Option<u32>,
+}
+
+impl Options {
+    fn from_args(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            out: PathBuf::new(),
+            dirs: Vec::new(),
+            padding: 0,
+            trim: false,
+            rotate: false,
+            power_of_two: false,
+            max_size: None,
+        };
+
+        let mut paths = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            let value = args.get(i + 1);
+            match args[i].as_ref() {
+                "--padding" => {
+                    options.padding = parse_number(value, "--padding")?;
+                    i += 1;
+                }
+                "--max-size" => {
+                    options.max_size = Some(parse_number(value, "--max-size")?);
+                    i += 1;
+                }
+                "--trim" => options.trim = true,
+                "--rotate" => options.rotate = true,
+                "--pot" => options.power_of_two = true,
+                arg if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+                arg => paths.push(PathBuf::from(arg)),
+            }
+            i += 1;
+        }
+
+        if paths.len() < 2 {
+            return Err(
+                "Usage: pack_atlas <out> <dir>... [--padding n] [--trim] [--rotate] [--pot] \
+                 [--max-size n]"
+                    .to_string(),
+            );
+        }
+        options.out = paths.remove(0);
+        options.dirs = paths;
+
+        Ok(options)
+    }
+}
+
+fn parse_number(value: Option<&String>, flag: &str) -> Result<u32, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("{} needs a number", flag))
+}
+
+struct Sprite {
+    name: String,
+    // as it goes in the atlas, turned 90 degrees clockwise when rotated
+    image: RgbaImage,
+    // where the image was cut from the source png, [x, y, w, h]
+    source_rect: [u32; 4],
+    source_size: (u32, u32),
+    trimmed: bool,
+    rotated: bool,
+}
+
+// every png under dir, named by its path relative to the directory with / between folders
+fn find_pngs(dir: &Path, prefix: &str, pngs: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("Could not read {}: {}", dir.display(), err))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let file_name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => format!("{}{}", prefix, name),
+            None => return Err(format!("{} is not a valid file name", path.display())),
+        };
+        if path.is_dir() {
+            find_pngs(&path, &format!("{}/", file_name), pngs)?;
+        } else if path.extension().map(|ext| ext == "png").unwrap_or(false) {
+            pngs.push((file_name, path));
+        }
+    }
+
+    Ok(())
+}
+
+// [x, y, w, h] of the pixels that aren't fully transparent, None when there are none
+fn get_opaque_bounds(image: &RgbaImage) -> Option<[u32; 4]> {
+    let (mut min_x, mut min_y) = (image.width(), image.height());
+    let (mut max_x, mut max_y) = (0, 0);
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel.data[3] > 0 {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+
+    if min_x > max_x {
+        None
+    } else {
+        Some([min_x, min_y, max_x - min_x + 1, max_y - min_y + 1])
+    }
+}
+
+fn crop(image: &RgbaImage, rect: [u32; 4]) -> RgbaImage {
+    let mut cropped = RgbaImage::new(rect[2], rect[3]);
+    for y in 0..rect[3] {
+        for x in 0..rect[2] {
+            cropped.put_pixel(x, y, *image.get_pixel(rect[0] + x, rect[1] + y));
+        }
+    }
+    cropped
+}
+
+fn load_sprite(name: String, path: &Path, options: &Options) -> Result<Sprite, String> {
+    let image = image::open(path)
+        .map_err(|err| format!("Could not load {}: {}", path.display(), err))?
+        .to_rgba();
+    let (w, h) = image.dimensions();
+
+    // a sprite with nothing in it keeps a single pixel, frames can't be empty
+    let source_rect = if options.trim {
+        get_opaque_bounds(&image).unwrap_or([0, 0, 1, 1])
+    } else {
+        [0, 0, w, h]
+    };
+    let trimmed = source_rect != [0, 0, w, h];
+    let image = if trimmed {
+        crop(&image, source_rect)
+    } else {
+        image
+    };
+
+    let rotated = options.rotate && image.height() > image.width();
+    let image = if rotated {
+        imageops::rotate90(&image)
+    } else {
+        image
+    };
+
+    Ok(Sprite {
+        name,
+        image,
+        source_rect,
+        source_size: (w, h),
+        trimmed,
+        rotated,
+    })
+}
+
+// The game finds frames by name alone, so a name in two directories would hide one of them.
+// Names that only differ in case are reported too, they are the same file on some systems
+fn report_duplicates(pngs: &[(String, PathBuf)]) -> Result<(), String> {
+    let mut found_in: HashMap<String, Vec<String>> = HashMap::new();
+    for &(ref name, ref path) in pngs {
+        found_in
+            .entry(name.to_lowercase())
+            .or_insert_with(Vec::new)
+            .push(path.display().to_string());
+    }
+
+    let mut duplicates: Vec<String> = found_in
+        .into_iter()
+        .filter(|&(_, ref paths)| paths.len() > 1)
+        .map(|(name, paths)| format!("{} is in {}", name, paths.join(", ")))
+        .collect();
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+
+    duplicates.sort();
+    Err(format!("Duplicate names:\n{}", duplicates.join("\n")))
+}
+
+fn load_sprites(options: &Options) -> Result<Vec<Sprite>, String> {
+    let mut pngs = Vec::new();
+    for dir in &options.dirs {
+        find_pngs(dir, "", &mut pngs)?;
+    }
+
+    report_duplicates(&pngs)?;
+
+    pngs.into_iter()
+        .map(|(name, path)| load_sprite(name, &path, options))
+        .collect()
+}
+
+/**
+ * Puts the sprites on shelves, tallest first, each shelf filled left to right before the next
+ * one starts below it. Returns the position of each sprite, in the order given, and the atlas size
+ */
+fn pack(sprites: &[Sprite], padding: u32, power_of_two: bool) -> (Vec<(u32, u32)>, u32, u32) {
+    let widest = sprites.iter().map(|sprite| sprite.image.width()).max().unwrap_or(1);
+    let area: u32 = sprites
+        .iter()
+        .map(|sprite| (sprite.image.width() + padding) * (sprite.image.height() + padding))
+        .sum();
+    // roughly square, which keeps the atlas well within texture size limits
+    let mut width = ((area as f64).sqrt().ceil() as u32).max(widest + padding * 2);
+    if power_of_two {
+        width = width.next_power_of_two();
+    }
+
+    let mut order: Vec<usize> = (0..sprites.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&sprites[a], &sprites[b]);
+        b.image
+            .height()
+            .cmp(&a.image.height())
+            .then(b.image.width().cmp(&a.image.width()))
+            .then(a.name.cmp(&b.name))
+    });
+
+    let mut positions = vec![(0, 0); sprites.len()];
+    let (mut x, mut y) = (padding, padding);
+    let mut shelf_height = 0;
+    let mut used_width = 0;
+    for i in order {
+        let (w, h) = sprites[i].image.dimensions();
+        if x + w + padding > width {
+            x = padding;
+            y += shelf_height + padding;
+            shelf_height = 0;
+        }
+        positions[i] = (x, y);
+        x += w + padding;
+        used_width = used_width.max(x);
+        shelf_height = shelf_height.max(h);
+    }
+
+    let mut height = y + shelf_height + padding;
+    if power_of_two {
+        height = height.next_power_of_two();
+    } else {
+        width = used_width;
+    }
+
+    (positions, width, height)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let mut sprites = load_sprites(options)?;
+    if sprites.is_empty() {
+        return Err("There are no pngs to pack".to_string());
+    }
+    sprites.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (positions, width, height) = pack(&sprites, options.padding, options.power_of_two);
+    if let Some(max_size) = options.max_size {
+        if width > max_size || height > max_size {
+            return Err(format!(
+                "The atlas would be {}x{}, more than the max size of {}",
+                width, height, max_size
+            ));
+        }
+    }
+
+    let mut atlas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+    let mut frames = Vec::new();
+    for (sprite, &(x, y)) in sprites.iter().zip(positions.iter()) {
+        for (sprite_x, sprite_y, pixel) in sprite.image.enumerate_pixels() {
+            atlas.put_pixel(x + sprite_x, y + sprite_y, *pixel);
+        }
+
+        // the frame has the size of the sprite as drawn, not as it lies in the atlas
+        let (w, h) = if sprite.rotated {
+            (sprite.image.height(), sprite.image.width())
+        } else {
+            sprite.image.dimensions()
+        };
+        frames.push(Frame {
+            filename: sprite.name.clone(),
+            frame: FrameData {
+                x: x as usize,
+                y: y as usize,
+                w: w as usize,
+                h: h as usize,
+            },
+            rotated: sprite.rotated,
+            trimmed: sprite.trimmed,
+            sprite_source_size: FrameData {
+                x: sprite.source_rect[0] as usize,
+                y: sprite.source_rect[1] as usize,
+                w: sprite.source_rect[2] as usize,
+                h: sprite.source_rect[3] as usize,
+            },
+            source_size: Size {
+                w: sprite.source_size.0 as usize,
+                h: sprite.source_size.1 as usize,
+            },
+        });
+    }
+
+    let spritesheet = Spritesheet {
+        frames,
+        meta: Meta {
+            size: Size {
+                w: width as usize,
+                h: height as usize,
+            },
+        },
+    };
+
+    let png_path = options.out.with_extension("png");
+    let json_path = options.out.with_extension("json");
+    atlas
+        .save(&png_path)
+        .map_err(|err| format!("Could not write {}: {}", png_path.display(), err))?;
+    let json = serde_json::to_string_pretty(&spritesheet).unwrap();
+    fs::write(&json_path, json)
+        .map_err(|err| format!("Could not write {}: {}", json_path.display(), err))?;
+
+    println!(
+        "Packed {} sprites into {}x{} at {}",
+        spritesheet.frames.len(),
+        width,
+        height,
+        png_path.display()
+    );
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = Options::from_args(&args).and_then(|options| run(&options));
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite(name: &str, w: u32, h: u32) -> Sprite {
+        Sprite {
+            name: name.to_string(),
+            image: RgbaImage::new(w, h),
+            source_rect: [0, 0, w, h],
+            source_size: (w, h),
+            trimmed: false,
+            rotated: false,
+        }
+    }
+
+    #[test]
+    fn opaque_bounds_trim_transparent_edges() {
+        let mut image = RgbaImage::new(8, 6);
+        image.put_pixel(2, 1, Rgba([255, 0, 0, 255]));
+        image.put_pixel(5, 3, Rgba([0, 0, 0, 1]));
+        assert_eq!(get_opaque_bounds(&image), Some([2, 1, 4, 3]));
+    }
+
+    #[test]
+    fn opaque_bounds_of_transparent_image() {
+        assert_eq!(get_opaque_bounds(&RgbaImage::new(4, 4)), None);
+    }
+
+    #[test]
+    fn pack_puts_tallest_first() {
+        let sprites = [sprite("small", 4, 4), sprite("tall", 4, 8)];
+        let (positions, width, height) = pack(&sprites, 0, false);
+        assert_eq!(positions, vec![(0, 8), (0, 0)]);
+        assert_eq!((width, height), (4, 12));
+    }
+
+    #[test]
+    fn pack_pads_edges_and_shelves() {
+        let sprites = [sprite("a", 10, 10), sprite("b", 10, 10)];
+        let (positions, width, height) = pack(&sprites, 2, false);
+        assert_eq!(positions, vec![(2, 2), (2, 14)]);
+        assert_eq!((width, height), (14, 26));
+    }
+
+    #[test]
+    fn pack_rounds_to_power_of_two() {
+        let sprites = [sprite("a", 10, 10), sprite("b", 10, 10)];
+        let (positions, width, height) = pack(&sprites, 2, true);
+        assert_eq!(positions, vec![(2, 2), (14, 2)]);
+        assert_eq!((width, height), (32, 16));
+    }
+}
@@ -0,0 +1,99 @@
+use specs::{Component, VecStorage};
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum HorizontalAnchor {
+    Left,
+    Center,
+    Right,
+    // fills the parent's width, less the margins
+    Stretch,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum VerticalAnchor {
+    Top,
+    Center,
+    Bottom,
+    // fills the parent's height, less the margins
+    Stretch,
+}
+
+/**
+ * Positions an entity against its parent node, or against the window for children of the root.
+ * The AnchorLayout system keeps the transform's position up to date with it, and its size along
+ * stretched axes.
+ * Margins are the distance kept to the edge the entity is anchored to, centered entities are
+ * moved by left - right and top - bottom instead
+ */
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Anchor {
+    pub horizontal: HorizontalAnchor,
+    pub vertical: VerticalAnchor,
+    #[serde(default)]
+    pub left: f32,
+    #[serde(default)]
+    pub top: f32,
+    #[serde(default)]
+    pub right: f32,
+    #[serde(default)]
+    pub bottom: f32,
+}
+
+impl Anchor {
+    pub fn new(horizontal: HorizontalAnchor, vertical: VerticalAnchor) -> Anchor {
+        Anchor {
+            horizontal,
+            vertical,
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        }
+    }
+
+    pub fn fill() -> Anchor {
+        Anchor::new(HorizontalAnchor::Stretch, VerticalAnchor::Stretch)
+    }
+
+    pub fn margin(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.left = left;
+        self.top = top;
+        self.right = right;
+        self.bottom = bottom;
+        self
+    }
+
+    // [x, y, w, h] of an entity with the given size, within the parent's [x, y, w, h]
+    pub fn place(&self, parent: &[f32; 4], w: f32, h: f32) -> [f32; 4] {
+        let (x, w) = match self.horizontal {
+            HorizontalAnchor::Left => (parent[0] + self.left, w),
+            HorizontalAnchor::Center => (
+                parent[0] + (parent[2] - w) / 2.0 + self.left - self.right,
+                w,
+            ),
+            HorizontalAnchor::Right => (parent[0] + parent[2] - w - self.right, w),
+            HorizontalAnchor::Stretch => (
+                parent[0] + self.left,
+                (parent[2] - self.left - self.right).max(0.0),
+            ),
+        };
+        let (y, h) = match self.vertical {
+            VerticalAnchor::Top => (parent[1] + self.top, h),
+            VerticalAnchor::Center => (
+                parent[1] + (parent[3] - h) / 2.0 + self.top - self.bottom,
+                h,
+            ),
+            VerticalAnchor::Bottom => (parent[1] + parent[3] - h - self.bottom, h),
+            VerticalAnchor::Stretch => (
+                parent[1] + self.top,
+                (parent[3] - self.top - self.bottom).max(0.0),
+            ),
+        };
+
+        [x, y, w, h]
+    }
+}
+
+impl Component for Anchor {
+    type Storage = VecStorage<Anchor>;
+}
@@ -0,0 +1,5 @@
+use components::GathererType;
+
+// the gatherer picked with its build key, waiting on a second press to be built
+#[derive(Default)]
+pub struct ChosenGatherer(pub Option<GathererType>);
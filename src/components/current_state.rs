@@ -1,2 +1,10 @@
-#[derive(Default)]
-pub struct CurrentState(pub String);
+use components::StateId;
+
+// the state at the bottom of the stack, overlays don't change it
+pub struct CurrentState(pub StateId);
+
+impl Default for CurrentState {
+    fn default() -> Self {
+        CurrentState(StateId::Menu)
+    }
+}
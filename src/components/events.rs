@@ -0,0 +1,132 @@
+use components::{upgrade::Buff, GathererType, TutorialStep};
+
+// Requests from the ui, or from one system to another. Each is carried out once, by the system
+// that owns it
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiCommand {
+    ResumeGame,
+    CloseHighScores,
+    DisplayError(String),
+    InspectTile(i32, i32),
+    ShowTechNode(Buff),
+    // picked up by PlayState when it sets up the next map
+    RestartSameSeed(usize),
+    ShowTutorialStep(TutorialStep),
+}
+
+// Commands stay queued until the system that handles them runs, which may be in another
+// dispatcher a few frames later
+#[derive(Default)]
+pub struct UiCommands {
+    commands: Vec<UiCommand>,
+}
+
+impl UiCommands {
+    pub fn new() -> Self {
+        UiCommands {
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: UiCommand) {
+        self.commands.push(command);
+    }
+
+    // removes the first queued command the filter picks out, returning what it mapped it to
+    pub fn take<T, F>(&mut self, mut filter: F) -> Option<T>
+    where
+        F: FnMut(&UiCommand) -> Option<T>,
+    {
+        let found = self.commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| filter(command).map(|value| (i, value)))
+            .next();
+        found.map(|(i, value)| {
+            self.commands.remove(i);
+            value
+        })
+    }
+}
+
+// Things that happened in a game, for any system that cares to react to them
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    GathererBuilt {
+        gatherer_type: GathererType,
+        tile: (i32, i32),
+    },
+    GathererRemoved {
+        gatherer_type: GathererType,
+        tile: (i32, i32),
+    },
+    ResearchCompleted {
+        buff: Buff,
+        level: u32,
+    },
+    CityAdded {
+        city_count: usize,
+    },
+    EnergySold {
+        income: i32,
+        tax: i32,
+    },
+    Blackout,
+}
+
+// Keeps the events written this frame and the one before, so a reader sees them whether it
+// runs before or after the writer, or in the dispatcher of the next frame. Every reader keeps
+// its own place, so any number of systems can read the same events
+pub struct EventChannel<E> {
+    events: Vec<E>,
+    // id of the first event still kept
+    first_id: usize,
+    // events before this index were written before the current frame
+    frame_start: usize,
+}
+
+impl<E> Default for EventChannel<E> {
+    fn default() -> Self {
+        EventChannel::new()
+    }
+}
+
+impl<E> EventChannel<E> {
+    pub fn new() -> Self {
+        EventChannel {
+            events: Vec::new(),
+            first_id: 0,
+            frame_start: 0,
+        }
+    }
+
+    pub fn write(&mut self, event: E) {
+        self.events.push(event);
+    }
+
+    // the events the reader has not seen yet
+    pub fn read(&self, reader: &mut EventReader) -> &[E] {
+        let start = reader.next_id.max(self.first_id) - self.first_id;
+        reader.next_id = self.first_id + self.events.len();
+        &self.events[start.min(self.events.len())..]
+    }
+
+    // called once a frame, dropping the events from the frame before last
+    pub fn update(&mut self) {
+        self.events.drain(..self.frame_start);
+        self.first_id += self.frame_start;
+        self.frame_start = self.events.len();
+    }
+}
+
+// A reader's place in an EventChannel. Systems keep one each
+#[derive(Default)]
+pub struct EventReader {
+    next_id: usize,
+}
+
+impl EventReader {
+    pub fn new() -> Self {
+        EventReader { next_id: 0 }
+    }
+}
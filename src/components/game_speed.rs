@@ -0,0 +1,24 @@
+const SPEEDS: [f32; 3] = [1.0, 2.0, 4.0];
+
+// how many times faster than real time a run plays, scaling the delta time of the main dispatcher
+pub struct GameSpeed(pub f32);
+
+impl GameSpeed {
+    pub fn speed_up(&mut self) {
+        if let Some(speed) = SPEEDS.iter().find(|speed| **speed > self.0) {
+            self.0 = *speed;
+        }
+    }
+
+    pub fn slow_down(&mut self) {
+        if let Some(speed) = SPEEDS.iter().rev().find(|speed| **speed < self.0) {
+            self.0 = *speed;
+        }
+    }
+}
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        GameSpeed(1.0)
+    }
+}
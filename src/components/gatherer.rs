@@ -1,3 +1,4 @@
+use components::{upgrade::Buff, ResearchedBuffs};
 use specs::{Component, VecStorage};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -46,6 +47,16 @@ impl GathererType {
         }
     }
 
+    // resources gathered each tick before any research
+    pub fn get_resource_gain(&self) -> i32 {
+        match *self {
+            GathererType::Coal => 12,
+            GathererType::Oil => 16,
+            GathererType::Solar => 11,
+            GathererType::Hydro => 22,
+        }
+    }
+
     pub fn get_pollution_amount(&self) -> i32 {
         match *self {
             GathererType::Coal => 2,
@@ -54,6 +65,35 @@ impl GathererType {
             _ => 0,
         }
     }
+
+    // research that cuts the pollution of every gatherer of this type by a fifth
+    pub fn get_pollution_filter(&self) -> Option<Buff> {
+        match *self {
+            GathererType::Coal => Some(Buff::PollutionFilters),
+            GathererType::Oil => Some(Buff::FudgeTheNumbers),
+            GathererType::Hydro => Some(Buff::SalmonCannon),
+            GathererType::Solar => None,
+        }
+    }
+
+    pub fn apply_pollution_filter(&self, pollution: i32, researched_buffs: &ResearchedBuffs) -> i32 {
+        match self.get_pollution_filter() {
+            Some(buff) if researched_buffs.0.contains_key(&buff) => pollution - pollution * 20 / 100,
+            _ => pollution,
+        }
+    }
+}
+
+// what a gatherer yields each tick, with the part each researched buff adds
+pub struct GathererYield {
+    pub base: i32,
+    pub buffs: Vec<(Buff, i32)>,
+}
+
+impl GathererYield {
+    pub fn total(&self) -> i32 {
+        self.base + self.buffs.iter().map(|&(_, amount)| amount).sum::<i32>()
+    }
 }
 
 pub struct Gatherer {
@@ -70,6 +110,57 @@ impl Gatherer {
             has_adjancent_of_same_type: false,
         }
     }
+
+    // pollution added each tick, before filters, which apply to the total of each type
+    pub fn get_pollution(&self, researched_buffs: &ResearchedBuffs) -> i32 {
+        if self.pollution <= 0 {
+            return 0;
+        }
+
+        match self.gatherer_type {
+            GathererType::Coal => {
+                self.pollution + researched_buffs
+                    .0
+                    .get(&Buff::ConveyerBelts)
+                    .map(|n| *n as i32)
+                    .unwrap_or(0)
+            }
+            GathererType::Oil => {
+                self.pollution + researched_buffs
+                    .0
+                    .get(&Buff::AutomatedRefiners)
+                    .map(|n| 2 * *n as i32)
+                    .unwrap_or(0)
+            }
+            _ => self.pollution,
+        }
+    }
+
+    pub fn get_yield(&self, researched_buffs: &ResearchedBuffs) -> GathererYield {
+        let mut buffs = Vec::new();
+        if self.has_adjancent_of_same_type {
+            if let Some(level) = researched_buffs.0.get(&Buff::ResourceTrading) {
+                buffs.push((Buff::ResourceTrading, *level as i32));
+            }
+        }
+
+        let type_buffs: &[Buff] = match self.gatherer_type {
+            GathererType::Coal => &[Buff::ConveyerBelts, Buff::RoboticLoaders],
+            GathererType::Oil => &[Buff::AutomatedRefiners, Buff::Purifier],
+            GathererType::Hydro => &[Buff::ReinforcedTurbines],
+            GathererType::Solar => &[Buff::ImprovePanelTech],
+        };
+        for buff in type_buffs {
+            if let Some(level) = researched_buffs.0.get(buff) {
+                buffs.push((*buff, 2 * *level as i32));
+            }
+        }
+
+        GathererYield {
+            base: self.gatherer_type.get_resource_gain(),
+            buffs,
+        }
+    }
 }
 
 impl Component for Gatherer {
@@ -1,7 +1,7 @@
 use components::GathererType;
 
 // This resource is used to cache the current amount gathering per tick, per resource
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct GatheringRate {
     pub coal: i32,
     pub oil: i32,
@@ -1,26 +1,80 @@
 use glutin::VirtualKeyCode;
-use std::collections::HashMap;
+use key_bindings::{InputAction, KeyBindings};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Default)]
 pub struct Input {
     pub hidpi_factor: f32,
     pub pressed_keys: HashMap<VirtualKeyCode, bool>,
+    // keys that went down this frame, cleared before events are polled
+    pub just_pressed_keys: HashSet<VirtualKeyCode>,
+    pub key_bindings: KeyBindings,
+    // while rebinding, the next key press is stored in captured_key instead of acting as input
+    pub capturing_key: bool,
+    pub captured_key: Option<VirtualKeyCode>,
     pub mouse_pos: (f32, f32),
     pub mouse_pressed: bool,
+    // lines scrolled this frame, positive scrolls up
+    pub mouse_scroll: f32,
 }
 
 impl Input {
-    pub fn new(hidpi_factor: f32, keys: Vec<VirtualKeyCode>) -> Input {
-        let mut key_map: HashMap<VirtualKeyCode, bool> = HashMap::new();
-        for key in keys {
-            key_map.insert(key, false);
-        }
-
+    pub fn new(hidpi_factor: f32, key_bindings: KeyBindings) -> Input {
         Input {
             hidpi_factor: hidpi_factor,
-            pressed_keys: key_map,
+            pressed_keys: HashMap::new(),
+            just_pressed_keys: HashSet::new(),
+            key_bindings,
+            capturing_key: false,
+            captured_key: None,
             mouse_pos: (0.0, 0.0),
             mouse_pressed: false,
+            mouse_scroll: 0.0,
+        }
+    }
+
+    pub fn clear_frame(&mut self) {
+        self.just_pressed_keys.clear();
+        self.mouse_scroll = 0.0;
+    }
+
+    pub fn set_key_state(&mut self, key: VirtualKeyCode, pressed: bool) {
+        if pressed && self.capturing_key {
+            self.captured_key = Some(key);
+            self.capturing_key = false;
+            return;
+        }
+
+        // held keys repeat their press event, which should not fire actions again
+        let was_pressed = self.is_key_down(&key);
+        if pressed && !was_pressed {
+            self.just_pressed_keys.insert(key);
         }
+        self.pressed_keys.insert(key, pressed);
+    }
+
+    pub fn is_key_down(&self, key: &VirtualKeyCode) -> bool {
+        *self.pressed_keys.get(key).unwrap_or(&false)
+    }
+
+    pub fn ctrl_held(&self) -> bool {
+        self.is_key_down(&VirtualKeyCode::LControl) || self.is_key_down(&VirtualKeyCode::RControl)
+    }
+
+    pub fn action_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed_keys
+            .contains(&self.key_bindings.get_key(&action))
+            || self.key_bindings
+                .get_alternate_key(&action)
+                .map(|key| self.just_pressed_keys.contains(&key))
+                .unwrap_or(false)
+    }
+
+    pub fn action_held(&self, action: InputAction) -> bool {
+        self.is_key_down(&self.key_bindings.get_key(&action))
+            || self.key_bindings
+                .get_alternate_key(&action)
+                .map(|key| self.is_key_down(&key))
+                .unwrap_or(false)
     }
 }
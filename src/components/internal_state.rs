@@ -5,6 +5,7 @@ pub enum InternalState {
     Transition,
     Pause,
     End,
+    HighScores,
 }
 
 impl Default for InternalState {
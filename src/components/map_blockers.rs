@@ -0,0 +1,29 @@
+// The overlays that can cover the map
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MapBlocker {
+    TechTree,
+    Notifications,
+    Statistics,
+}
+
+/**
+ * The overlays currently over the map, a bit each, so closing one leaves the tiles under another
+ * blocked. systems::BlockMapTiles disables the tile buttons while any are set
+ */
+#[derive(Default)]
+pub struct MapBlockers(u8);
+
+impl MapBlockers {
+    pub fn set(&mut self, blocker: MapBlocker, blocking: bool) {
+        let bit = 1 << blocker as u8;
+        if blocking {
+            self.0 |= bit;
+        } else {
+            self.0 &= !bit;
+        }
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.0 != 0
+    }
+}
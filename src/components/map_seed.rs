@@ -0,0 +1,3 @@
+// seed used to generate the current map, so a run can be recorded and replayed
+#[derive(Default)]
+pub struct MapSeed(pub usize);
@@ -1,4 +1,4 @@
-use rand::{Rng, ThreadRng};
+use rand::Rng;
 use specs::{Component, VecStorage};
 
 const MIN_X: f32 = -280.0;
@@ -23,7 +23,7 @@ impl MenuScreen {
         }
     }
 
-    pub fn get_random_position(rng: &mut ThreadRng) -> (f32, f32) {
+    pub fn get_random_position<R: Rng>(rng: &mut R) -> (f32, f32) {
         (rng.gen_range(MIN_X, MAX_X), rng.gen_range(MIN_Y, MAX_Y))
     }
 }
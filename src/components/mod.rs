@@ -1,7 +1,8 @@
-mod actions;
+mod anchor;
 mod animation_sheet;
 mod button;
 mod camera;
+mod chosen_gatherer;
 mod city_power_state;
 mod click_sound;
 mod color;
@@ -9,17 +10,22 @@ mod current_state;
 mod delta_time;
 mod effected_by_pollution_tiles;
 mod entity_lookup;
-mod error;
+mod events;
 mod fade;
 mod floating_text;
+mod game_speed;
 mod gatherer;
 mod gatherer_positions;
 mod gathering_rate;
 mod highlight_tile;
 mod input;
 mod internal_state;
+mod map_blockers;
+mod map_seed;
 mod menu_screen;
 mod node;
+mod notifications;
+mod palette;
 mod polluted_tiles;
 mod power_bar;
 mod pulse;
@@ -27,26 +33,32 @@ mod rect;
 mod researched_buffs;
 mod researching_entities;
 mod resources;
+mod run_stats;
 mod selected_tile;
 mod shape;
 mod sprite;
 mod state_change;
+mod statistics;
 mod text;
 mod texture;
 mod tile;
+mod tile_cursor;
 mod tile_nodes;
+mod toast;
 mod transform;
 mod transition_to_state;
 mod tutorial_step;
 pub mod upgrade;
+mod viewport;
 mod wallet;
 
 pub mod ui;
 
-pub use self::actions::*;
+pub use self::anchor::*;
 pub use self::animation_sheet::*;
 pub use self::button::*;
 pub use self::camera::*;
+pub use self::chosen_gatherer::*;
 pub use self::city_power_state::*;
 pub use self::click_sound::*;
 pub use self::color::*;
@@ -54,17 +66,22 @@ pub use self::current_state::*;
 pub use self::delta_time::*;
 pub use self::effected_by_pollution_tiles::*;
 pub use self::entity_lookup::*;
-pub use self::error::*;
+pub use self::events::*;
 pub use self::fade::*;
 pub use self::floating_text::*;
+pub use self::game_speed::*;
 pub use self::gatherer::*;
 pub use self::gatherer_positions::*;
 pub use self::gathering_rate::*;
 pub use self::highlight_tile::*;
 pub use self::input::*;
 pub use self::internal_state::*;
+pub use self::map_blockers::*;
+pub use self::map_seed::*;
 pub use self::menu_screen::*;
 pub use self::node::*;
+pub use self::notifications::*;
+pub use self::palette::*;
 pub use self::power_bar::*;
 pub use self::polluted_tiles::*;
 pub use self::pulse::*;
@@ -72,15 +89,20 @@ pub use self::rect::*;
 pub use self::researched_buffs::*;
 pub use self::researching_entities::*;
 pub use self::resources::*;
+pub use self::run_stats::*;
 pub use self::selected_tile::*;
 pub use self::shape::*;
 pub use self::sprite::*;
 pub use self::state_change::*;
+pub use self::statistics::*;
 pub use self::text::*;
 pub use self::texture::*;
 pub use self::tile::*;
+pub use self::tile_cursor::*;
 pub use self::tile_nodes::*;
+pub use self::toast::*;
 pub use self::transform::*;
 pub use self::transition_to_state::*;
 pub use self::tutorial_step::*;
+pub use self::viewport::*;
 pub use self::wallet::*;
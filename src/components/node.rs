@@ -1,4 +1,4 @@
-use cgmath::Vector3;
+use cgmath::{Vector2, Vector3};
 use components::Transform;
 use specs::{Component, Entity, VecStorage, World, WriteStorage};
 use std::cmp;
@@ -21,6 +21,7 @@ impl Node {
         current_entity: &Entity,
         target_entity: &Entity,
         position: &mut Vector3<f32>,
+        scale: &mut Vector2<f32>,
         node_storage: &WriteStorage<Node>,
         transform_storage: &WriteStorage<Transform>,
     ) -> bool {
@@ -34,10 +35,14 @@ impl Node {
         if !transform.visible {
             return false;
         }
-        // increment it before confirming, so sub nodes consider the parent node position
-        position.x += transform.get_pos().x;
-        position.y += transform.get_pos().y;
+        let (last_position, last_scale) = (*position, *scale);
+        // increment it before confirming, so sub nodes consider the parent node position.
+        // The position is in the parent's units, so it is scaled by the parents' scale
+        position.x += transform.get_pos().x * scale.x;
+        position.y += transform.get_pos().y * scale.y;
         position.z += transform.get_pos().z;
+        scale.x *= transform.scale.x;
+        scale.y *= transform.scale.y;
         if *target_entity == *current_entity {
             found_entity = true;
         }
@@ -49,6 +54,7 @@ impl Node {
                         entity,
                         target_entity,
                         position,
+                        scale,
                         node_storage,
                         transform_storage,
                     ) {
@@ -59,28 +65,28 @@ impl Node {
         }
 
         if !found_entity {
-            let transform = transform_storage.get(*current_entity).unwrap();
             // undo if nothing was found
-            position.x -= transform.get_pos().x;
-            position.y -= transform.get_pos().y;
-            position.z -= transform.get_pos().z;
+            *position = last_position;
+            *scale = last_scale;
         }
 
         found_entity
     }
 
     // potential optimization is to change this into a quad tree
-    pub fn get_absolute_pos(
+    // the position in layout coordinates, along with the scale the entity is drawn at
+    pub fn get_absolute_transform(
         current_entity: &Entity,
         target_entity: &Entity,
         transform_storage: &WriteStorage<Transform>,
         node_storage: &WriteStorage<Node>,
-    ) -> Vector3<f32> {
+    ) -> (Vector3<f32>, Vector2<f32>) {
         let mut position = Vector3 {
             x: 0.0,
             y: 0.0,
             z: 0.0,
         };
+        let mut scale = Vector2 { x: 1.0, y: 1.0 };
 
         let node = node_storage.get(*current_entity).unwrap();
         for entity in &node.entities {
@@ -88,6 +94,7 @@ impl Node {
                 entity,
                 target_entity,
                 &mut position,
+                &mut scale,
                 node_storage,
                 transform_storage,
             ) {
@@ -95,7 +102,7 @@ impl Node {
             }
         }
 
-        position
+        (position, scale)
     }
 
     pub fn add(&mut self, entity: Entity) {
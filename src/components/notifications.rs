@@ -0,0 +1,91 @@
+use components::{upgrade::Buff, PaletteColor};
+
+// the history keeps this many entries, dropping the oldest
+pub const MAX_NOTIFICATIONS: usize = 100;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn get_palette_color(&self) -> PaletteColor {
+        match *self {
+            Severity::Info => PaletteColor::Info,
+            Severity::Warning => PaletteColor::Warning,
+            Severity::Error => PaletteColor::Error,
+        }
+    }
+}
+
+// where clicking the notification in the history takes the player
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NotificationTarget {
+    Tile(i32, i32),
+    TechNode(Buff),
+}
+
+pub struct Notification {
+    pub time: f32,
+    pub severity: Severity,
+    pub message: String,
+    pub target: Option<NotificationTarget>,
+}
+
+// Systems push notifications here, the NotificationCenter system shows them as toasts and keeps
+// the history for the log panel
+#[derive(Default)]
+pub struct Notifications {
+    pub history: Vec<Notification>,
+    pending: Vec<(Severity, String, Option<NotificationTarget>)>,
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Notifications {
+            history: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        severity: Severity,
+        message: String,
+        target: Option<NotificationTarget>,
+    ) {
+        self.pending.push((severity, message, target));
+    }
+
+    pub fn info(&mut self, message: String, target: Option<NotificationTarget>) {
+        self.push(Severity::Info, message, target);
+    }
+
+    pub fn warning(&mut self, message: String, target: Option<NotificationTarget>) {
+        self.push(Severity::Warning, message, target);
+    }
+
+    pub fn error(&mut self, message: String, target: Option<NotificationTarget>) {
+        self.push(Severity::Error, message, target);
+    }
+
+    // moves the pending notifications into the history, returning how many were added
+    pub fn take_pending(&mut self, time: f32) -> usize {
+        let count = self.pending.len();
+        for (severity, message, target) in self.pending.drain(..) {
+            self.history.push(Notification {
+                time,
+                severity,
+                message,
+                target,
+            });
+        }
+        if self.history.len() > MAX_NOTIFICATIONS {
+            let excess = self.history.len() - MAX_NOTIFICATIONS;
+            self.history.drain(..excess);
+        }
+        count
+    }
+}
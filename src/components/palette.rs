@@ -0,0 +1,203 @@
+use components::upgrade::Status;
+use specs::{Component, VecStorage};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    Standard,
+    // for protanopia and deuteranopia, no cue relies on telling red from green
+    RedGreen,
+    // for tritanopia, no cue relies on telling blue from green or yellow from pink
+    BlueYellow,
+    HighContrast,
+}
+
+impl Theme {
+    pub fn all() -> [Theme; 4] {
+        [
+            Theme::Standard,
+            Theme::RedGreen,
+            Theme::BlueYellow,
+            Theme::HighContrast,
+        ]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Standard
+    }
+}
+
+// the colors that carry meaning, looked up in the palette of the chosen theme
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum PaletteColor {
+    // headings and other text that only needs to stand out from the background
+    Label,
+    Coal,
+    Oil,
+    Solar,
+    Hydro,
+    Money,
+    Tax,
+    Pollution,
+    PowerSurplus,
+    PowerDeficit,
+    TechResearchable,
+    // locked, being learned or researched
+    TechUnavailable,
+    TechResearched,
+    // notifications by their severity
+    Info,
+    Warning,
+    Error,
+}
+
+pub struct Palette {
+    pub theme: Theme,
+    label: [f32; 4],
+    coal: [f32; 4],
+    oil: [f32; 4],
+    solar: [f32; 4],
+    hydro: [f32; 4],
+    money: [f32; 4],
+    tax: [f32; 4],
+    pollution: [f32; 4],
+    power_surplus: [f32; 4],
+    power_deficit: [f32; 4],
+    tech_researchable: [f32; 4],
+    tech_unavailable: [f32; 4],
+    tech_researched: [f32; 4],
+    info: [f32; 4],
+    warning: [f32; 4],
+    error: [f32; 4],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new(Theme::default())
+    }
+}
+
+impl Palette {
+    pub fn new(theme: Theme) -> Palette {
+        match theme {
+            Theme::Standard => Palette {
+                theme,
+                label: [0.0, 0.6, 0.0, 1.0],
+                coal: [0.6, 0.6, 0.6, 1.0],
+                oil: [0.8, 0.8, 0.8, 1.0],
+                solar: [1.0, 1.0, 0.6196, 1.0],
+                hydro: [0.188, 0.57647, 1.0, 1.0],
+                money: [1.0, 1.0, 0.0, 1.0],
+                tax: [0.6, 0.0, 0.0, 1.0],
+                pollution: [0.55, 0.35, 0.2, 1.0],
+                power_surplus: [0.0, 0.6, 0.0, 1.0],
+                power_deficit: [0.6, 0.0, 0.0, 1.0],
+                tech_researchable: [1.0, 1.0, 1.0, 1.0],
+                tech_unavailable: [0.3, 0.3, 0.3, 1.0],
+                tech_researched: [0.0, 1.0, 0.0, 1.0],
+                info: [1.0, 1.0, 0.0, 1.0],
+                warning: [1.0, 0.6, 0.0, 1.0],
+                error: [1.0, 0.0, 0.0, 1.0],
+            },
+            // built on the Okabe-Ito colors, blue against orange rather than green against red
+            Theme::RedGreen => Palette {
+                theme,
+                label: [0.0, 0.6, 0.0, 1.0],
+                coal: [0.6, 0.6, 0.6, 1.0],
+                oil: [0.8, 0.47, 0.65, 1.0],
+                solar: [0.94, 0.89, 0.26, 1.0],
+                hydro: [0.34, 0.71, 0.91, 1.0],
+                money: [0.9, 0.6, 0.0, 1.0],
+                tax: [0.84, 0.37, 0.0, 1.0],
+                pollution: [0.6, 0.45, 0.3, 1.0],
+                power_surplus: [0.34, 0.71, 0.91, 1.0],
+                power_deficit: [0.9, 0.6, 0.0, 1.0],
+                tech_researchable: [1.0, 1.0, 1.0, 1.0],
+                tech_unavailable: [0.3, 0.3, 0.3, 1.0],
+                tech_researched: [0.34, 0.71, 0.91, 1.0],
+                info: [0.34, 0.71, 0.91, 1.0],
+                warning: [0.94, 0.89, 0.26, 1.0],
+                error: [0.84, 0.37, 0.0, 1.0],
+            },
+            // red against teal, and solar moved off yellow
+            Theme::BlueYellow => Palette {
+                theme,
+                label: [0.0, 0.6, 0.0, 1.0],
+                coal: [0.6, 0.6, 0.6, 1.0],
+                oil: [0.85, 0.85, 0.85, 1.0],
+                solar: [1.0, 0.55, 0.2, 1.0],
+                hydro: [0.2, 0.5, 1.0, 1.0],
+                money: [1.0, 0.85, 0.85, 1.0],
+                tax: [0.9, 0.1, 0.3, 1.0],
+                pollution: [0.55, 0.35, 0.2, 1.0],
+                power_surplus: [0.0, 0.75, 0.7, 1.0],
+                power_deficit: [0.9, 0.1, 0.3, 1.0],
+                tech_researchable: [1.0, 1.0, 1.0, 1.0],
+                tech_unavailable: [0.3, 0.3, 0.3, 1.0],
+                tech_researched: [0.0, 0.75, 0.7, 1.0],
+                info: [0.0, 0.75, 0.7, 1.0],
+                warning: [1.0, 0.55, 0.2, 1.0],
+                error: [0.9, 0.1, 0.3, 1.0],
+            },
+            // everything bright against the dark background, dimmed nodes included
+            Theme::HighContrast => Palette {
+                theme,
+                label: [0.3, 1.0, 0.3, 1.0],
+                coal: [0.8, 0.8, 0.8, 1.0],
+                oil: [1.0, 1.0, 1.0, 1.0],
+                solar: [1.0, 1.0, 0.4, 1.0],
+                hydro: [0.45, 0.8, 1.0, 1.0],
+                money: [1.0, 0.9, 0.0, 1.0],
+                tax: [1.0, 0.45, 0.45, 1.0],
+                pollution: [0.9, 0.65, 0.4, 1.0],
+                power_surplus: [0.3, 1.0, 0.3, 1.0],
+                power_deficit: [1.0, 0.35, 0.35, 1.0],
+                tech_researchable: [1.0, 1.0, 1.0, 1.0],
+                tech_unavailable: [0.55, 0.55, 0.55, 1.0],
+                tech_researched: [0.3, 1.0, 0.3, 1.0],
+                info: [1.0, 1.0, 0.4, 1.0],
+                warning: [1.0, 0.7, 0.2, 1.0],
+                error: [1.0, 0.35, 0.35, 1.0],
+            },
+        }
+    }
+
+    pub fn get(&self, color: PaletteColor) -> [f32; 4] {
+        match color {
+            PaletteColor::Label => self.label,
+            PaletteColor::Coal => self.coal,
+            PaletteColor::Oil => self.oil,
+            PaletteColor::Solar => self.solar,
+            PaletteColor::Hydro => self.hydro,
+            PaletteColor::Money => self.money,
+            PaletteColor::Tax => self.tax,
+            PaletteColor::Pollution => self.pollution,
+            PaletteColor::PowerSurplus => self.power_surplus,
+            PaletteColor::PowerDeficit => self.power_deficit,
+            PaletteColor::TechResearchable => self.tech_researchable,
+            PaletteColor::TechUnavailable => self.tech_unavailable,
+            PaletteColor::TechResearched => self.tech_researched,
+            PaletteColor::Info => self.info,
+            PaletteColor::Warning => self.warning,
+            PaletteColor::Error => self.error,
+        }
+    }
+}
+
+pub fn get_color_from_status(status: &Status) -> PaletteColor {
+    match *status {
+        Status::Researchable => PaletteColor::TechResearchable,
+        _ => PaletteColor::TechUnavailable,
+    }
+}
+
+/**
+ * Gives an entity's Color from the palette, rather than a fixed one. The ApplyTheme system
+ * keeps it in step, so changing the theme recolors entities that already exist
+ */
+pub struct ThemeColor(pub PaletteColor);
+
+impl Component for ThemeColor {
+    type Storage = VecStorage<ThemeColor>;
+}
@@ -1,27 +1,26 @@
 use specs::{Component, VecStorage};
 
-const MAX_POWER: i32 = 10_000;
-pub const STARTING_TICK: i32 = 40;
-
 pub struct PowerBar {
     pub power_left: i32,
     pub power_per_tick: i32,
+    max_power: i32,
 }
 
 impl PowerBar {
-    pub fn new() -> PowerBar {
+    pub fn new(max_power: i32, power_per_tick: i32) -> PowerBar {
         PowerBar {
-            power_left: MAX_POWER,
-            power_per_tick: STARTING_TICK,
+            power_left: max_power,
+            power_per_tick,
+            max_power,
         }
     }
 
-    pub fn get_max_f32() -> f32 {
-        MAX_POWER as f32
+    pub fn get_max_f32(&self) -> f32 {
+        self.max_power as f32
     }
 
-    pub fn get_max() -> i32 {
-        MAX_POWER
+    pub fn get_max(&self) -> i32 {
+        self.max_power
     }
 
     pub fn get_max_width() -> f32 {
@@ -30,8 +29,8 @@ impl PowerBar {
 
     pub fn add_power(&mut self, power: i32) {
         self.power_left += power;
-        if self.power_left > MAX_POWER {
-            self.power_left = MAX_POWER;
+        if self.power_left > self.max_power {
+            self.power_left = self.max_power;
         }
     }
 }
@@ -0,0 +1,155 @@
+use components::{GathererType, ResourceType, Resources};
+use localization::Localization;
+
+pub struct TimelineEvent {
+    pub time: f32,
+    pub description: String,
+}
+
+// Running totals for the current game, used to evaluate achievements, record the run history
+// and explain the run on the end screen
+#[derive(Default)]
+pub struct RunStats {
+    pub total_income: i32,
+    pub total_tax: i32,
+    pub peak_pollution: i32,
+    pub time_survived: f32,
+    pub coal_built: usize,
+    pub oil_built: usize,
+    pub solar_built: usize,
+    pub hydro_built: usize,
+    pub spent_on_gatherers: i32,
+    pub spent_on_research: i32,
+    pub timeline: Vec<TimelineEvent>,
+    pub blackout_cause: String,
+    in_deficit: bool,
+    had_surplus: bool,
+    recorded_first_deficit: bool,
+    // the largest stockpile when the current deficit started, used to explain the blackout
+    deficit_reserve: Option<(ResourceType, i32)>,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        RunStats {
+            total_income: 0,
+            total_tax: 0,
+            peak_pollution: 0,
+            time_survived: 0.0,
+            coal_built: 0,
+            oil_built: 0,
+            solar_built: 0,
+            hydro_built: 0,
+            spent_on_gatherers: 0,
+            spent_on_research: 0,
+            timeline: Vec::new(),
+            blackout_cause: String::new(),
+            in_deficit: false,
+            had_surplus: false,
+            recorded_first_deficit: false,
+            deficit_reserve: None,
+        }
+    }
+
+    pub fn add_gatherer_built(&mut self, gatherer_type: &GathererType) {
+        match *gatherer_type {
+            GathererType::Coal => self.coal_built += 1,
+            GathererType::Oil => self.oil_built += 1,
+            GathererType::Solar => self.solar_built += 1,
+            GathererType::Hydro => self.hydro_built += 1,
+        }
+    }
+
+    // for builds that were undone
+    pub fn remove_gatherer_built(&mut self, gatherer_type: &GathererType) {
+        match *gatherer_type {
+            GathererType::Coal => self.coal_built -= 1,
+            GathererType::Oil => self.oil_built -= 1,
+            GathererType::Solar => self.solar_built -= 1,
+            GathererType::Hydro => self.hydro_built -= 1,
+        }
+    }
+
+    pub fn get_built_for_type(&self, gatherer_type: &GathererType) -> usize {
+        match *gatherer_type {
+            GathererType::Coal => self.coal_built,
+            GathererType::Oil => self.oil_built,
+            GathererType::Solar => self.solar_built,
+            GathererType::Hydro => self.hydro_built,
+        }
+    }
+
+    pub fn add_event(&mut self, description: String) {
+        self.timeline.push(TimelineEvent {
+            time: self.time_survived,
+            description,
+        });
+    }
+
+    pub fn update_power_balance(
+        &mut self,
+        power_balance: i32,
+        resources: &Resources,
+        localization: &Localization,
+    ) {
+        if power_balance >= 0 {
+            self.had_surplus = true;
+            self.in_deficit = false;
+            self.deficit_reserve = None;
+            return;
+        }
+
+        if !self.in_deficit {
+            self.in_deficit = true;
+            self.deficit_reserve = [
+                ResourceType::Coal,
+                ResourceType::Oil,
+                ResourceType::Solar,
+                ResourceType::Hydro,
+            ].iter()
+                .map(|r_type| (*r_type, resources.get_amount_for_type(r_type)))
+                .filter(|&(_, amount)| amount > 0)
+                .max_by_key(|&(_, amount)| amount);
+
+            // every map starts out short of power, so only count it once there has been a surplus
+            if self.had_surplus && !self.recorded_first_deficit {
+                self.recorded_first_deficit = true;
+                self.add_event(localization.format(
+                    "timeline.first_deficit",
+                    &[("amount", localization.number(-power_balance as i64))],
+                ));
+            }
+        }
+    }
+
+    pub fn set_blackout_cause(
+        &mut self,
+        production: i32,
+        demand: i32,
+        city_count: usize,
+        localization: &Localization,
+    ) {
+        let production_text = localization.number(production as i64);
+        let demand_text = localization.number(demand as i64);
+        self.blackout_cause = if production == 0 {
+            localization.get("end.cause.no_production")
+        } else if let Some((r_type, amount)) = self.deficit_reserve {
+            localization.format(
+                "end.cause.out_of_resources",
+                &[
+                    ("amount", localization.number(amount as i64)),
+                    ("resource", localization.get(&format!("resource.{:?}", r_type))),
+                    ("production", production_text),
+                    ("demand", demand_text),
+                ],
+            )
+        } else {
+            localization.plural(
+                "end.cause.demand",
+                city_count as i64,
+                &[("demand", demand_text), ("production", production_text)],
+            )
+        };
+        self.add_event(localization.get("timeline.blackout"));
+    }
+}
@@ -25,6 +25,35 @@ impl Shape {
         }
     }
 
+    // an open stroked path, for graphs and other polylines that shouldnt join back to the start
+    pub fn new_line(points: Vec<Vector2<f32>>, color: [f32; 4]) -> Self {
+        let mut path_builder = Path::builder();
+        for (i, point) in points.iter().enumerate() {
+            let p = lyon_point(point.x, point.y);
+            if i == 0 {
+                path_builder.move_to(p);
+            } else {
+                path_builder.line_to(p);
+            }
+        }
+
+        let path = path_builder.build();
+        let mut buffers = VertexBuffers::new();
+
+        let mut tessellator = StrokeTessellator::new();
+        tessellator.tessellate_path(
+            path.path_iter(),
+            &StrokeOptions::default().with_line_width(2.0),
+            &mut BuffersBuilder::new(&mut buffers, VertexCtor { color }),
+        );
+
+        Shape {
+            buffers,
+            points,
+            color,
+        }
+    }
+
     pub fn build_buffers(
         points: Vec<Vector2<f32>>,
         color: [f32; 4],
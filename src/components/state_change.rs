@@ -1,36 +1,53 @@
 use specs::{Component, HashMapStorage};
 
-#[derive(Default)]
-pub struct StateChange {
-    pub state: String,
-    pub action: String,
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum StateId {
+    Menu,
+    Play,
+    // overlays, run on top of another state
+    Pause,
+    TechTree,
+    End,
 }
 
-impl Clone for StateChange {
-    fn clone(&self) -> StateChange {
-        StateChange {
-            state: self.state.clone(),
-            action: self.action.clone(),
-        }
-    }
+// changes a state makes to itself, rather than to the stack
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StateAction {
+    StartFade,
+    ShowHighScores,
+    HideHighScores,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transition {
+    // clears the world and every state on the stack, setting the state up from scratch
+    Switch(StateId),
+    // sets the state at the bottom of the stack up again, dropping the overlays on top of it
+    Restart,
+    // runs the state on top of the current one, which keeps its world but stops updating
+    Push(StateId),
+    // drops the state on top, resuming the one below it
+    Pop,
+    // handled by the state on top of the stack
+    Custom(StateAction),
+}
+
+#[derive(Clone, Default)]
+pub struct StateChange {
+    pub transition: Option<Transition>,
 }
 
 impl StateChange {
     pub fn new() -> StateChange {
-        StateChange {
-            state: "".to_string(),
-            action: "".to_string(),
-        }
+        StateChange { transition: None }
     }
 
     pub fn reset(&mut self) {
-        self.state = "".to_string();
-        self.action = "".to_string();
+        self.transition = None;
     }
 
-    pub fn set(&mut self, state: String, action: String) {
-        self.state = state;
-        self.action = action;
+    pub fn set(&mut self, transition: Transition) {
+        self.transition = Some(transition);
     }
 }
 
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+use components::PaletteColor;
+use localization::Localization;
+
+pub const MAX_SAMPLES: usize = 40;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StatSeries {
+    Coal,
+    Oil,
+    Solar,
+    Hydro,
+    Income,
+    Tax,
+    Pollution,
+    PowerBalance,
+}
+
+impl StatSeries {
+    pub fn all() -> [StatSeries; 8] {
+        [
+            StatSeries::Coal,
+            StatSeries::Oil,
+            StatSeries::Solar,
+            StatSeries::Hydro,
+            StatSeries::Income,
+            StatSeries::Tax,
+            StatSeries::Pollution,
+            StatSeries::PowerBalance,
+        ]
+    }
+
+    // matches the colours used for the gathering rate text in the sidebar
+    pub fn get_palette_color(&self) -> PaletteColor {
+        match *self {
+            StatSeries::Coal => PaletteColor::Coal,
+            StatSeries::Oil => PaletteColor::Oil,
+            StatSeries::Solar => PaletteColor::Solar,
+            StatSeries::Hydro => PaletteColor::Hydro,
+            StatSeries::Income => PaletteColor::Money,
+            StatSeries::Tax => PaletteColor::Tax,
+            StatSeries::Pollution => PaletteColor::Pollution,
+            StatSeries::PowerBalance => PaletteColor::PowerSurplus,
+        }
+    }
+
+    pub fn get_value(&self, sample: &StatSample) -> i32 {
+        match *self {
+            StatSeries::Coal => sample.coal,
+            StatSeries::Oil => sample.oil,
+            StatSeries::Solar => sample.solar,
+            StatSeries::Hydro => sample.hydro,
+            StatSeries::Income => sample.income,
+            StatSeries::Tax => sample.tax,
+            StatSeries::Pollution => sample.pollution,
+            StatSeries::PowerBalance => sample.power_balance,
+        }
+    }
+
+    pub fn format_value(&self, value: i32, localization: &Localization) -> String {
+        match *self {
+            StatSeries::Income | StatSeries::Tax => localization.money(value),
+            _ => localization.number(value as i64),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StatSample {
+    pub coal: i32,
+    pub oil: i32,
+    pub solar: i32,
+    pub hydro: i32,
+    pub income: i32,
+    pub tax: i32,
+    pub pollution: i32,
+    pub power_balance: i32,
+}
+
+// History of the economy, one sample per sell tick. Only the last MAX_SAMPLES ticks are kept
+#[derive(Default)]
+pub struct Statistics {
+    samples: VecDeque<StatSample>,
+    total_samples: usize,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Statistics {
+            samples: VecDeque::with_capacity(MAX_SAMPLES),
+            total_samples: 0,
+        }
+    }
+
+    pub fn add_sample(&mut self, sample: StatSample) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.total_samples += 1;
+    }
+
+    pub fn get_values(&self, series: &StatSeries) -> Vec<i32> {
+        self.samples
+            .iter()
+            .map(|sample| series.get_value(sample))
+            .collect()
+    }
+
+    pub fn get_latest(&self, series: &StatSeries) -> i32 {
+        self.samples
+            .back()
+            .map(|sample| series.get_value(sample))
+            .unwrap_or(0)
+    }
+
+    // counts every sample ever added, so systems can tell when new data has arrived
+    pub fn get_total_samples(&self) -> usize {
+        self.total_samples
+    }
+}
@@ -1,6 +1,9 @@
-use rand::{Rng, ThreadRng};
+use rand::Rng;
 use specs::{Component, VecStorage};
 
+// a tile's width and height in layout pixels
+pub const TILE_SIZE: f32 = 64.0;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TileType {
     Open,
@@ -18,7 +21,7 @@ impl Tile {
         Tile { tile_type }
     }
 
-    pub fn get_sprite_frames(rng: &mut ThreadRng, tile_type: &TileType) -> [String; 2] {
+    pub fn get_sprite_frames<R: Rng>(rng: &mut R, tile_type: &TileType) -> [String; 2] {
         match *tile_type {
             TileType::Open => ["tiles.png".to_string(), "tiles_highlight.png".to_string()],
             TileType::EcoSystem => ["swamp.png".to_string(), String::new()],
@@ -44,7 +47,7 @@ impl Tile {
     }
 
     pub fn get_size() -> f32 {
-        64.0
+        TILE_SIZE
     }
 }
 
@@ -0,0 +1,8 @@
+use specs::{Component, HashMapStorage};
+
+// highlights the tile keyboard input acts on, hidden until a cursor key is pressed
+pub struct TileCursor;
+
+impl Component for TileCursor {
+    type Storage = HashMapStorage<TileCursor>;
+}
@@ -0,0 +1,16 @@
+use specs::{Component, HashMapStorage};
+
+// short lived message shown at the top of the screen, removed once tick runs out
+pub struct Toast {
+    pub tick: f32,
+}
+
+impl Toast {
+    pub fn new(tick: f32) -> Self {
+        Toast { tick }
+    }
+}
+
+impl Component for Toast {
+    type Storage = HashMapStorage<Self>;
+}
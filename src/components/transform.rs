@@ -8,6 +8,8 @@ pub struct Transform {
     pub scale: Vector2<f32>,
     pub visible: bool,
     absolute_pos: Vector3<f32>,
+    // the scale of the parent nodes and this one together, kept with absolute_pos
+    absolute_scale: Vector2<f32>,
     pub dirty_pos: bool,
 }
 
@@ -33,6 +35,10 @@ impl Transform {
             },
             visible,
             absolute_pos: Vector3 { x, y, z },
+            absolute_scale: Vector2 {
+                x: scale_x,
+                y: scale_y,
+            },
             dirty_pos: true,
         }
     }
@@ -55,8 +61,8 @@ impl Transform {
     }
 
     pub fn contains(&self, x: &f32, y: &f32) -> bool {
-        let w = self.size.x as f32;
-        let h = self.size.y as f32;
+        let w = self.size.x as f32 * self.scale.x;
+        let h = self.size.y as f32 * self.scale.y;
         self.pos.x <= *x && self.pos.x + w >= *x && self.pos.y <= *y && self.pos.y + h >= *y
     }
 
@@ -81,8 +87,13 @@ impl Transform {
         &self.absolute_pos
     }
 
-    pub fn set_absolute_pos(&mut self, pos: Vector3<f32>) {
+    pub fn get_absolute_scale(&self) -> &Vector2<f32> {
+        &self.absolute_scale
+    }
+
+    pub fn set_absolute_pos(&mut self, pos: Vector3<f32>, scale: Vector2<f32>) {
         self.absolute_pos = pos;
+        self.absolute_scale = scale;
         self.dirty_pos = false;
     }
 }
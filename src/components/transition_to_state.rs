@@ -1,11 +1,12 @@
+use components::StateId;
 use specs::{Component, HashMapStorage};
 
 pub struct TransitionToState {
-    pub state: String,
+    pub state: StateId,
 }
 
 impl TransitionToState {
-    pub fn new(state: String) -> Self {
+    pub fn new(state: StateId) -> Self {
         TransitionToState { state }
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt::{self, Display};
 
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TutorialStep {
     NotStarted,
     SelectTile,
@@ -1,7 +1,13 @@
+mod statistics_graph;
+mod tech_status_mark;
 mod tech_tree_button;
 mod tutorial_ui;
+mod ui_scaled;
 mod wallet_ui;
 
+pub use self::statistics_graph::*;
+pub use self::tech_status_mark::*;
 pub use self::tech_tree_button::*;
 pub use self::tutorial_ui::*;
+pub use self::ui_scaled::*;
 pub use self::wallet_ui::*;
@@ -0,0 +1,11 @@
+use components::StatSeries;
+use specs::{Component, HashMapStorage};
+
+// marks the line and label entities of a graph in the statistics panel
+pub struct StatisticsGraph {
+    pub series: StatSeries,
+}
+
+impl Component for StatisticsGraph {
+    type Storage = HashMapStorage<StatisticsGraph>;
+}
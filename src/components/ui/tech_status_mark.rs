@@ -0,0 +1,24 @@
+use components::{upgrade::Status, Theme};
+use specs::{Component, Entity, HashMapStorage};
+
+/**
+ * A checkmark or padlock drawn over a tech tree node, so its status reads without relying on the
+ * node's color. Remembers what was last drawn, as the shape is only rebuilt when that changes
+ */
+pub struct TechStatusMark {
+    pub upgrade: Entity,
+    pub drawn: Option<(Status, Theme)>,
+}
+
+impl TechStatusMark {
+    pub fn new(upgrade: Entity) -> TechStatusMark {
+        TechStatusMark {
+            upgrade,
+            drawn: None,
+        }
+    }
+}
+
+impl Component for TechStatusMark {
+    type Storage = HashMapStorage<TechStatusMark>;
+}
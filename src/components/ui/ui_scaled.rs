@@ -0,0 +1,12 @@
+use specs::{Component, NullStorage};
+
+/**
+ * Draws an entity and everything under it at the ui scale from the settings. AnchorLayout sets
+ * the transform's scale to it, the size stays in the unscaled units children are laid out in
+ */
+#[derive(Default)]
+pub struct UiScaled;
+
+impl Component for UiScaled {
+    type Storage = NullStorage<Self>;
+}
@@ -1,3 +1,4 @@
+use localization::Localization;
 use specs::{Component, Entity, HashMapStorage, VecStorage};
 use std::collections::HashMap;
 
@@ -30,6 +31,31 @@ pub enum Buff {
 }
 
 impl Buff {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Buff::Coal => "Coal",
+            Buff::Oil => "Oil",
+            Buff::Solar => "Solar",
+            Buff::ResourceTrading => "Resource Trading",
+            Buff::ConveyerBelts => "Conveyer Belts",
+            Buff::RoboticLoaders => "Robotic Loaders",
+            Buff::PollutionFilters => "Pollution Filters",
+            Buff::FudgeTheNumbers => "Fudge the Numbers",
+            Buff::AutomatedRefiners => "Automated Refiners",
+            Buff::Purifier => "Purifier",
+            Buff::Hydro => "Hydro",
+            Buff::SalmonCannon => "Salmon Cannon",
+            Buff::ReinforcedTurbines => "Reinforced Turbines",
+            Buff::PurchaseSolarCellCompany => "Purchase Solar Cell Company",
+            Buff::ImprovePanelTech => "Improve Panel Tech",
+            Buff::SellPanelsToConsumers => "Sell Panels to Consumers",
+        }
+    }
+
+    pub fn get_localized_name(&self, localization: &Localization) -> String {
+        localization.get_or(&format!("buff.{:?}", self), self.get_name())
+    }
+
     pub fn has_levels(&self) -> bool {
         match *self {
             Buff::ResourceTrading
@@ -64,13 +90,6 @@ impl Component for Upgrade {
     type Storage = VecStorage<Upgrade>;
 }
 
-pub fn get_color_from_status(status: &Status) -> [f32; 4] {
-    match *status {
-        Status::Researchable => [1.0, 1.0, 1.0, 1.0],
-        _ => [0.3, 0.3, 0.3, 1.0],
-    }
-}
-
 pub struct LearnProgress {
     pub buff: Buff,
 }
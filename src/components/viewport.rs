@@ -0,0 +1,90 @@
+use cgmath::Matrix4;
+use components::TILE_SIZE;
+use renderer;
+
+// the map's width and height in layout pixels
+pub const MAP_SIZE: f32 = 640.0;
+// the map is a square of this many tiles
+pub const MAP_TILES: i32 = (MAP_SIZE / TILE_SIZE) as i32;
+// the rest of the base resolution's width, to the right of the map
+pub const SIDEBAR_WIDTH: f32 = 320.0;
+
+/**
+ * How the window maps onto layout coordinates, the ones transforms are in. The base resolution
+ * from renderer::get_dimensions is scaled the same on both axes to fit the window, then grows
+ * along whichever axis has room to spare, rather than being stretched to the window's aspect ratio.
+ * Layout coordinates start at the top left of the map, which is centered in the space left of
+ * the sidebar, so the window's edges go negative once it is wider or taller than the base.
+ * A ui scale above 1 makes the base larger by the room the scaled ui needs, which leaves the map
+ * smaller in the window
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Viewport {
+    // the drawable size of the window in logical pixels
+    pub window: (f32, f32),
+    // logical pixels per layout pixel
+    pub scale: f32,
+    // the window in layout coordinates, [x, y, w, h]
+    pub bounds: [f32; 4],
+    // how much larger than its layout size the ui is drawn, see UiScaled
+    pub ui_scale: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        let dim = renderer::get_dimensions();
+        Viewport::new(dim[0], dim[1], 1.0)
+    }
+}
+
+impl Viewport {
+    pub fn new(window_w: f32, window_h: f32, ui_scale: f32) -> Viewport {
+        let dim = renderer::get_dimensions();
+        let sidebar_width = SIDEBAR_WIDTH * ui_scale;
+        let base_w = dim[0] - SIDEBAR_WIDTH + sidebar_width;
+        // the sidebar is laid out for the base height, so it needs more once scaled up
+        let base_h = dim[1] * ui_scale.max(1.0);
+        // minimized windows report a size of zero
+        let window = (window_w.max(1.0), window_h.max(1.0));
+        let scale = (window.0 / base_w).min(window.1 / base_h);
+        let w = window.0 / scale;
+        let h = window.1 / scale;
+
+        Viewport {
+            window,
+            scale,
+            bounds: [
+                -(w - sidebar_width - MAP_SIZE) / 2.0,
+                -(h - MAP_SIZE) / 2.0,
+                w,
+                h,
+            ],
+            ui_scale,
+        }
+    }
+
+    // a position in the window, in logical pixels, to layout coordinates
+    pub fn to_layout(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x / self.scale + self.bounds[0],
+            y / self.scale + self.bounds[1],
+        )
+    }
+
+    // a position in layout coordinates to logical pixels in the window
+    pub fn to_window(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.bounds[0]) * self.scale,
+            (y - self.bounds[1]) * self.scale,
+        )
+    }
+
+    pub fn get_projection(&self) -> Matrix4<f32> {
+        renderer::get_ortho_bounds(
+            self.bounds[0],
+            self.bounds[1],
+            self.bounds[2],
+            self.bounds[3],
+        )
+    }
+}
@@ -1,5 +1,3 @@
-const START_AMOUNT: i32 = 25;
-
 #[derive(Default)]
 pub struct Wallet {
     money: i32,
@@ -7,17 +5,13 @@ pub struct Wallet {
 }
 
 impl Wallet {
-    pub fn new() -> Wallet {
+    pub fn new(start_amount: i32) -> Wallet {
         Wallet {
-            money: START_AMOUNT,
-            last_credit: START_AMOUNT,
+            money: start_amount,
+            last_credit: start_amount,
         }
     }
 
-    pub fn start_amount() -> i32 {
-        START_AMOUNT
-    }
-
     pub fn add_money(&mut self, amount: i32) {
         self.money += amount;
         self.last_credit = amount;
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use conrod;
+use gfx;
+use gfx_device_gl::{CommandBuffer, Device, Factory, Resources};
+use gfx_glyph::{GlyphBrush, GlyphBrushBuilder};
+use gfx_window_glutin;
+use glutin::{self, dpi::{LogicalPosition, LogicalSize}, EventsLoop, GlContext, GlWindow};
+
+use loader;
+use mods;
+use renderer::{self, ColorFormat, DepthFormat};
+use settings::{WindowMode, WindowSettings};
+use spritesheet::Spritesheet;
+
+/**
+ * The window along with everything drawn through its gl context. Vsync can only be picked when
+ * the context is created, so toggling it builds a new display, textures and all
+ */
+pub struct Display {
+    pub window: GlWindow,
+    pub device: Device,
+    pub factory: Factory,
+    pub encoder: gfx::Encoder<Resources, CommandBuffer>,
+    pub basic: renderer::Basic<Resources>,
+    pub spritesheet: Spritesheet,
+    pub asset_texture: gfx::handle::ShaderResourceView<Resources, [f32; 4]>,
+    pub texture_map: HashMap<String, gfx::handle::ShaderResourceView<Resources, [f32; 4]>>,
+    pub screen_sizes: [(u16, u16); 4],
+    pub glyph_brush: GlyphBrush<'static, Resources, Factory>,
+    pub conrod_renderer: conrod::backend::gfx::Renderer<'static, Resources>,
+}
+
+impl Display {
+    pub fn new(events_loop: &mut EventsLoop, window_settings: &WindowSettings) -> Display {
+        let (w, h) = window_settings.size;
+        let builder = glutin::WindowBuilder::new()
+            .with_title("Energy Grid".to_string())
+            .with_dimensions(LogicalSize::new(w, h));
+        let context = glutin::ContextBuilder::new().with_vsync(window_settings.vsync);
+
+        let (window, device, mut factory, main_color, main_depth) =
+            gfx_window_glutin::init::<ColorFormat, DepthFormat>(builder, context, events_loop);
+
+        set_window_mode(&window, window_settings);
+
+        let hidpi_factor = window.get_hidpi_factor();
+
+        // macos mojave hack
+        {
+            events_loop.poll_events(|_| {});
+            let size = window.get_inner_size().unwrap();
+            window.resize(size.to_physical(hidpi_factor));
+        }
+
+        let encoder: gfx::Encoder<_, _> = factory.create_command_buffer().into();
+
+        let target = renderer::WindowTargets {
+            color: main_color,
+            depth: main_depth,
+        };
+        let basic = renderer::Basic::new(&mut factory, target);
+
+        let (spritesheet, asset_texture) = mods::load_spritesheet(&mut factory);
+
+        let mut screen_sizes = [(0u16, 0u16); 4];
+        let mut texture_map = HashMap::new();
+        for (i, name) in loader::SCREEN_IMAGES.iter().enumerate() {
+            let (texture, w, h) = loader::gfx_load_texture(
+                &format!("resources/startscreens/{}", name),
+                &mut factory,
+            );
+            screen_sizes[i] = (w, h);
+            texture_map.insert(name.to_string(), texture);
+        }
+
+        let glyph_brush = GlyphBrushBuilder::using_fonts_bytes(basic.fonts.get_font_data())
+            .build(factory.clone());
+
+        let conrod_renderer =
+            conrod::backend::gfx::Renderer::new(&mut factory, &basic.target.color, hidpi_factor)
+                .unwrap();
+
+        Display {
+            window,
+            device,
+            factory,
+            encoder,
+            basic,
+            spritesheet,
+            asset_texture,
+            texture_map,
+            screen_sizes,
+            glyph_brush,
+            conrod_renderer,
+        }
+    }
+
+    // moves the window to the mode and windowed size of the settings, vsync is left as it was
+    pub fn set_window_mode(&self, window_settings: &WindowSettings) {
+        set_window_mode(&self.window, window_settings);
+    }
+}
+
+fn set_window_mode(window: &GlWindow, window_settings: &WindowSettings) {
+    match window_settings.mode {
+        WindowMode::Windowed => {
+            window.set_fullscreen(None);
+            window.set_decorations(true);
+            let (w, h) = window_settings.size;
+            window.set_inner_size(LogicalSize::new(w, h));
+            if let Some((x, y)) = window_settings.position {
+                window.set_position(LogicalPosition::new(x, y));
+            }
+        }
+        WindowMode::Borderless => {
+            let monitor = window.get_current_monitor();
+            let hidpi_factor = monitor.get_hidpi_factor();
+            window.set_fullscreen(None);
+            window.set_decorations(false);
+            window.set_position(monitor.get_position().to_logical(hidpi_factor));
+            window.set_inner_size(monitor.get_dimensions().to_logical(hidpi_factor));
+        }
+        WindowMode::Fullscreen => {
+            let monitor = window.get_current_monitor();
+            window.set_fullscreen(Some(monitor));
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use components::{Button, Color, EffectedByPollutionTiles, GathererType, Node, Rect,
-                 ResearchedBuffs, Sprite, Text, TileType, Transform};
+use components::{Button, Color, EffectedByPollutionTiles, GathererType, Node, ResearchedBuffs,
+                 Sprite, TileType, Transform};
 use entities::{create_colored_rect, create_text, tech_tree::Buff};
 use renderer;
 use specs::{Entities, Entity, LazyUpdate, Read, WriteStorage};
@@ -11,8 +11,25 @@ const SIZE: u16 = 64;
 const CELL_WIDTH: u16 = SIZE + SPACING * 2;
 const CELL_HEIGHT: u16 = SIZE + SPACING + 30;
 
-const CONTAINER_WIDTH: u16 = 160;
-const CONTAINER_HEIGHT: u16 = 200;
+pub const CONTAINER_WIDTH: u16 = 160;
+pub const CONTAINER_HEIGHT: u16 = 200;
+
+// where the build ui for the given position ends up, kept inside the screen
+pub fn get_position(x: f32, y: f32) -> (f32, f32) {
+    let dim = renderer::get_dimensions();
+
+    let x = cmp::max(
+        0,
+        cmp::min(x as i32, dim[0] as i32 - CONTAINER_WIDTH as i32),
+    ) as f32;
+
+    let y = cmp::max(
+        0,
+        cmp::min(y as i32, dim[1] as i32 - CONTAINER_HEIGHT as i32),
+    ) as f32;
+
+    (x, y)
+}
 
 pub fn create<'a, 'b: 'a>(
     x: f32,
@@ -202,17 +219,7 @@ pub fn create<'a, 'b: 'a>(
         new_entities.push(text);
     }
 
-    let dim = renderer::get_dimensions();
-
-    let x = cmp::max(
-        0,
-        cmp::min(x as i32, dim[0] as i32 - CONTAINER_WIDTH as i32),
-    ) as f32;
-
-    let y = cmp::max(
-        0,
-        cmp::min(y as i32, dim[1] as i32 - CONTAINER_HEIGHT as i32),
-    ) as f32;
+    let (x, y) = get_position(x, y);
 
     let container_entity = create_colored_rect::create(
         x,
@@ -1,18 +1,20 @@
 use specs::{Entities, WriteStorage};
 
-use components::{Color, EntityLookup, Fade, FadeMode, Node, Rect, Transform, TransitionToState};
+use components::{Anchor, Color, EntityLookup, Fade, FadeMode, Node, Rect, StateId, Transform,
+                 TransitionToState};
 use renderer::get_dimensions;
 use systems::logic;
 
 pub fn create(
     entities: &Entities,
+    anchor_storage: &mut WriteStorage<Anchor>,
     color_storage: &mut WriteStorage<Color>,
     fade_storage: &mut WriteStorage<Fade>,
     nodes_storage: &mut WriteStorage<Node>,
     rect_storage: &mut WriteStorage<Rect>,
     transform_storage: &mut WriteStorage<Transform>,
     transition_to_state_storage: Option<&mut WriteStorage<TransitionToState>>,
-    state_to_transition_to: Option<String>,
+    state_to_transition_to: Option<StateId>,
     lookup: &EntityLookup,
     mode: FadeMode,
     duration: f32,
@@ -35,6 +37,7 @@ pub fn create(
             Transform::visible(0.0, 0.0, 50.0, dim[0] as u16, dim[1] as u16, 0.0, 1.0, 1.0),
         )
         .unwrap();
+    anchor_storage.insert(entity, Anchor::fill()).unwrap();
 
     if let Some(transition_to_state_storage) = transition_to_state_storage {
         transition_to_state_storage
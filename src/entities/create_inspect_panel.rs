@@ -0,0 +1,72 @@
+use components::{Color, Node, Tile, MAP_SIZE};
+use entities::{create_colored_rect, create_text};
+use specs::{Entities, Entity, LazyUpdate, Read, WriteStorage};
+use std::cmp;
+
+pub const PANEL_WIDTH: u16 = 250;
+pub const PANEL_HEIGHT: u16 = 240;
+const PADDING: f32 = 10.0;
+
+// x and y are the tile being inspected. The panel goes to its right, or its left near the edge
+pub fn create<'a, 'b: 'a>(
+    x: f32,
+    y: f32,
+    title: String,
+    body: String,
+    entities: &'a Entities,
+    lazy: &'a Read<LazyUpdate>,
+    node_storage: &mut WriteStorage<'b, Node>,
+) -> Entity {
+    // the panel stays over the map, clear of the sidebar
+    let x = if x + Tile::get_size() + PANEL_WIDTH as f32 <= MAP_SIZE {
+        x + Tile::get_size()
+    } else {
+        x - PANEL_WIDTH as f32
+    };
+    let y = cmp::max(0, cmp::min(y as i32, MAP_SIZE as i32 - PANEL_HEIGHT as i32)) as f32;
+
+    let title_entity = create_text::create(
+        entities,
+        lazy,
+        title,
+        20.0,
+        PADDING,
+        PADDING,
+        0.0,
+        PANEL_WIDTH - PADDING as u16 * 2,
+        24,
+        Color([0.0, 1.0, 0.0, 1.0]),
+        None,
+    );
+
+    let body_entity = create_text::create(
+        entities,
+        lazy,
+        body,
+        16.0,
+        PADDING,
+        PADDING + 30.0,
+        0.0,
+        PANEL_WIDTH - PADDING as u16 * 2,
+        PANEL_HEIGHT - 40 - PADDING as u16,
+        Color([1.0, 1.0, 1.0, 1.0]),
+        None,
+    );
+
+    let container_entity = create_colored_rect::create(
+        x,
+        y,
+        5.0,
+        PANEL_WIDTH,
+        PANEL_HEIGHT,
+        [0.0, 0.0, 0.0, 0.85],
+        entities,
+        lazy,
+    );
+
+    let mut node = Node::new();
+    node.add_many(vec![title_entity, body_entity]);
+    node_storage.insert(container_entity, node).unwrap();
+
+    container_entity
+}
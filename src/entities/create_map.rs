@@ -1,11 +1,11 @@
 use components::TileType;
-use rand::{thread_rng, Rng, ThreadRng};
+use rand::Rng;
 use specs::Entity;
 use std::collections::HashMap;
 
 type TileTypeMap = HashMap<(i32, i32), (TileType, Option<Entity>)>;
 
-fn get_random_coord_opposite_of_last(rng: &mut ThreadRng, paths: &Vec<usize>) -> usize {
+fn get_random_coord_opposite_of_last<R: Rng>(rng: &mut R, paths: &Vec<usize>) -> usize {
     if paths.len() > 0 {
         if *(paths.get(paths.len() - 1).unwrap()) == 0 {
             rng.gen_range(0, 5)
@@ -80,9 +80,7 @@ fn apply_open_tile_coords(
     insert_open_tiles(set_nodes, horizontal, pos.0, pos.1, 2, short_length);
 }
 
-pub fn create() -> TileTypeMap {
-    let mut rng = thread_rng();
-
+pub fn create<R: Rng>(rng: &mut R) -> TileTypeMap {
     let mut set_nodes = HashMap::new();
     let mut directions = [0, 0, 1, 1];
     rng.shuffle(&mut directions);
@@ -112,7 +110,7 @@ pub fn create() -> TileTypeMap {
 
         if horizontal {
             let x = rng.gen_range(0, 11 - long_length);
-            let y = get_random_coord_opposite_of_last(&mut rng, &horizontal_paths);
+            let y = get_random_coord_opposite_of_last(rng, &horizontal_paths);
 
             let positive_facing = y <= 4;
 
@@ -129,7 +127,7 @@ pub fn create() -> TileTypeMap {
             );
         } else {
             let y = rng.gen_range(0, 11 - long_length);
-            let x = get_random_coord_opposite_of_last(&mut rng, &vertical_paths);
+            let x = get_random_coord_opposite_of_last(rng, &vertical_paths);
 
             let positive_facing = x <= 4;
 
@@ -0,0 +1,27 @@
+use components::{Color, Node, Palette, PaletteColor, Rect, Text, ThemeColor, Transform};
+use specs::{Entity, World};
+
+// the rows of the history are added by the NotificationCenter system while the panel is open
+pub fn create(world: &mut World, title: String) -> Entity {
+    let mut panel_node = Node::new();
+
+    let label_color = world.read_resource::<Palette>().get(PaletteColor::Label);
+    let title = world
+        .create_entity()
+        .with(Transform::visible(20.0, 10.0, 0.0, 560, 32, 0.0, 1.0, 1.0))
+        .with(Text::new_with_text(28.0, 560, 32, title))
+        .with(Color(label_color))
+        .with(ThemeColor(PaletteColor::Label))
+        .build();
+    panel_node.add(title);
+
+    world
+        .create_entity()
+        .with(Transform::new(
+            20.0, 20.0, 3.0, 600, 600, 0.0, 1.0, 1.0, false,
+        ))
+        .with(Rect {})
+        .with(Color([16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0, 0.95]))
+        .with(panel_node)
+        .build()
+}
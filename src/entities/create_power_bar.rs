@@ -1,12 +1,13 @@
 use components::{Color, PowerBar, Rect, Transform};
+use rules::Rules;
 use specs::Entity;
 use storage_types::PowerBarStorage;
 
-pub fn create(power_bar_storage: &mut PowerBarStorage, x: f32, y: f32) -> Entity {
+pub fn create(power_bar_storage: &mut PowerBarStorage, x: f32, y: f32, rules: &Rules) -> Entity {
     let entity = power_bar_storage.entities.create();
     power_bar_storage
         .power_bar_storage
-        .insert(entity, PowerBar::new())
+        .insert(entity, PowerBar::new(rules.max_power, rules.starting_tick))
         .unwrap();
     power_bar_storage
         .transform_storage
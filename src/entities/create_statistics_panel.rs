@@ -0,0 +1,130 @@
+use cgmath::Vector2;
+use components::{ui::StatisticsGraph, Color, Node, Palette, PaletteColor, Rect, Shape, StatSeries,
+                 Text, ThemeColor, Transform};
+use localization::Localization;
+use specs::{Entity, World};
+
+pub const GRAPH_WIDTH: f32 = 270.0;
+pub const GRAPH_HEIGHT: f32 = 90.0;
+
+const CELL_WIDTH: f32 = 290.0;
+const CELL_HEIGHT: f32 = 135.0;
+
+// Turns a series of values into graph points, scaled to fit the graph. Zero is always in range
+// so that negative values, like a power deficit, read as being below the line
+pub fn get_graph_points(values: &[i32]) -> Vec<Vector2<f32>> {
+    if values.len() < 2 {
+        return vec![
+            Vector2::new(0.0, GRAPH_HEIGHT),
+            Vector2::new(GRAPH_WIDTH, GRAPH_HEIGHT),
+        ];
+    }
+
+    let min = values.iter().fold(0, |min, v| if *v < min { *v } else { min });
+    let max = values.iter().fold(0, |max, v| if *v > max { *v } else { max });
+    let range = if max > min { (max - min) as f32 } else { 1.0 };
+    let step = GRAPH_WIDTH / (values.len() - 1) as f32;
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            Vector2::new(
+                i as f32 * step,
+                GRAPH_HEIGHT - (*v - min) as f32 / range * GRAPH_HEIGHT,
+            )
+        })
+        .collect()
+}
+
+// the text above a graph, with the latest and highest values
+pub fn get_graph_label(
+    series: &StatSeries,
+    latest: i32,
+    peak: i32,
+    localization: &Localization,
+) -> String {
+    localization.format(
+        "stats.graph_label",
+        &[
+            ("series", localization.get(&format!("stats.{:?}", series))),
+            ("value", series.format_value(latest, localization)),
+            ("peak", series.format_value(peak, localization)),
+        ],
+    )
+}
+
+pub fn create(world: &mut World) -> Entity {
+    let mut panel_node = Node::new();
+
+    let title_text = world.read_resource::<Localization>().get("stats.title");
+    let label_color = world.read_resource::<Palette>().get(PaletteColor::Label);
+    let title = world
+        .create_entity()
+        .with(Transform::visible(20.0, 10.0, 0.0, 560, 32, 0.0, 1.0, 1.0))
+        .with(Text::new_with_text(28.0, 560, 32, title_text))
+        .with(Color(label_color))
+        .with(ThemeColor(PaletteColor::Label))
+        .build();
+    panel_node.add(title);
+
+    for (i, series) in StatSeries::all().iter().enumerate() {
+        let x = 20.0 + (i % 2) as f32 * CELL_WIDTH;
+        let y = 50.0 + (i / 2) as f32 * CELL_HEIGHT;
+        let color = world
+            .read_resource::<Palette>()
+            .get(series.get_palette_color());
+        let label_text = get_graph_label(series, 0, 0, &world.read_resource::<Localization>());
+
+        let label = world
+            .create_entity()
+            .with(Transform::visible(0.0, 0.0, 0.0, 270, 24, 0.0, 1.0, 1.0))
+            .with(Text::new_with_text(18.0, 270, 24, label_text))
+            .with(Color(color))
+            .with(ThemeColor(series.get_palette_color()))
+            .with(StatisticsGraph { series: *series })
+            .build();
+
+        let graph_background = world
+            .create_entity()
+            .with(Transform::visible(
+                0.0,
+                28.0,
+                0.0,
+                GRAPH_WIDTH as u16,
+                GRAPH_HEIGHT as u16,
+                0.0,
+                1.0,
+                1.0,
+            ))
+            .with(Rect {})
+            .with(Color([0.0, 0.0, 0.0, 0.5]))
+            .build();
+
+        let line = world
+            .create_entity()
+            .with(Transform::visible(0.0, 28.0, 1.0, 0, 0, 0.0, 1.0, 1.0))
+            .with(Shape::new_line(get_graph_points(&[]), color))
+            .with(StatisticsGraph { series: *series })
+            .build();
+
+        let mut cell_node = Node::new();
+        cell_node.add_many(vec![label, graph_background, line]);
+        let cell = world
+            .create_entity()
+            .with(Transform::visible(x, y, 0.0, 270, 120, 0.0, 1.0, 1.0))
+            .with(cell_node)
+            .build();
+        panel_node.add(cell);
+    }
+
+    world
+        .create_entity()
+        .with(Transform::new(
+            20.0, 20.0, 3.0, 600, 600, 0.0, 1.0, 1.0, false,
+        ))
+        .with(Rect {})
+        .with(Color([16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0, 0.95]))
+        .with(panel_node)
+        .build()
+}
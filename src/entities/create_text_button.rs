@@ -0,0 +1,47 @@
+use components::{Button, Color, Node, Rect, Text, Transform};
+use gfx_glyph::HorizontalAlign;
+use specs::{Entities, Entity, LazyUpdate, Read, WriteStorage};
+
+// A button drawn as a coloured rect with a text label, for buttons that dont have artwork in the spritesheet
+pub fn create(
+    entities: &Entities,
+    lazy: &Read<LazyUpdate>,
+    node_storage: &mut WriteStorage<Node>,
+    name: &str,
+    label: String,
+    x: f32,
+    y: f32,
+    z: f32,
+    w: u16,
+    h: u16,
+) -> Entity {
+    let label_entity = lazy
+        .create_entity(entities)
+        .with(Transform::visible(
+            w as f32 / 2.0,
+            (h as f32 - 20.0) / 2.0,
+            1.0,
+            w,
+            h,
+            0.0,
+            1.0,
+            1.0,
+        ))
+        .with(Text::new_with_text(20.0, w, h, label).align(HorizontalAlign::Center))
+        .with(Color([0.0, 1.0, 0.0, 1.0]))
+        .build();
+
+    let button_entity = lazy
+        .create_entity(entities)
+        .with(Transform::visible(x, y, z, w, h, 0.0, 1.0, 1.0))
+        .with(Button::new(name.to_string(), [String::new(), String::new()]))
+        .with(Rect {})
+        .with(Color([0.0, 0.0, 0.0, 0.8]))
+        .build();
+
+    let mut node = Node::new();
+    node.add(label_entity);
+    node_storage.insert(button_entity, node).unwrap();
+
+    button_entity
+}
@@ -2,12 +2,17 @@
 pub mod create_build_ui;
 pub mod create_colored_rect;
 pub mod create_fade;
+pub mod create_inspect_panel;
 pub mod create_map;
+pub mod create_notification_panel;
 pub mod create_power_bar;
+pub mod create_statistics_panel;
 pub mod create_text;
+pub mod create_text_button;
 pub mod create_tooltip;
 mod recursive_delete;
 pub mod tech_tree;
 pub mod tutorial;
+pub mod ui_layout;
 
 pub use self::recursive_delete::*;
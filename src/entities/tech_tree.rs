@@ -2,9 +2,10 @@ pub use components::upgrade::*;
 
 use cgmath::Vector2;
 use components::ui;
-use components::{Color, Node, Shape, Sprite, Transform};
+use components::{get_color_from_status, Color, Node, Palette, Shape, Sprite, Transform,
+                 SIDEBAR_WIDTH};
 use loader;
-use renderer;
+use mods;
 use serde_json::{self, Value};
 use specs::{Entity, World};
 
@@ -22,6 +23,8 @@ struct LastUpgrade {
 pub const SIZE: u16 = 32;
 const SIZE_F: f32 = SIZE as f32;
 const Y_INCREMENT: f32 = 64.0;
+pub const MARK_SIZE: u16 = 12;
+const MARK_SIZE_F: f32 = MARK_SIZE as f32;
 
 fn create_line(
     world: &mut World,
@@ -67,13 +70,17 @@ fn build_entity_nodes(
     let description = node["description"].as_str().unwrap().to_string();
     let upgrade: Upgrade = serde_json::from_value(node.clone()).unwrap();
     let status = upgrade.status.clone();
+    // kept up to date with the status by ApplyTheme
+    let color = world
+        .read_resource::<Palette>()
+        .get(get_color_from_status(&status));
     let entity = world
         .create_entity()
         .with(upgrade)
         .with(Transform::visible(
             x, y as f32, 1.0, SIZE, SIZE, 0.0, 1.0, 1.0,
         ))
-        .with(Color(get_color_from_status(&status)))
+        .with(Color(color))
         .with(Sprite {
             frame_name: format!("techtree/{}", node["frame_name"].as_str().unwrap()),
         })
@@ -105,6 +112,23 @@ fn build_entity_nodes(
 
     container.add(entity);
 
+    // drawn over the node's top right corner by ApplyTheme, see TechStatusMark
+    let mark_entity = world
+        .create_entity()
+        .with(ui::TechStatusMark::new(entity))
+        .with(Transform::visible(
+            x + SIZE_F - MARK_SIZE_F / 2.0,
+            y - MARK_SIZE_F / 2.0,
+            2.0,
+            MARK_SIZE,
+            MARK_SIZE,
+            0.0,
+            1.0,
+            1.0,
+        ))
+        .build();
+    container.add(mark_entity);
+
     if let Some(children) = node.get("children") {
         for child in children.as_array().unwrap().iter() {
             tech_tree_node.sub_nodes.push(build_entity_nodes(
@@ -125,6 +149,48 @@ fn build_entity_nodes(
     tech_tree_node
 }
 
+fn validate_node(node: &Value) -> Result<(), String> {
+    if let Err(err) = serde_json::from_value::<Upgrade>(node.clone()) {
+        return Err(format!("Invalid tech tree node: {}", err));
+    }
+    let buff = &node["buff"];
+    if !node["x"].is_f64() && !node["x"].is_u64() {
+        return Err(format!("Tech tree node {} needs a number for x", buff));
+    }
+    if !node["y_tier"].is_u64() {
+        return Err(format!("Tech tree node {} needs a whole number for y_tier", buff));
+    }
+    if !node["description"].is_string() || !node["frame_name"].is_string() {
+        return Err(format!(
+            "Tech tree node {} needs a description and frame_name",
+            buff
+        ));
+    }
+
+    if let Some(children) = node.get("children") {
+        match children.as_array() {
+            Some(children) => for child in children {
+                validate_node(child)?;
+            },
+            None => return Err(format!("Tech tree node {} children must be a list", buff)),
+        }
+    }
+
+    Ok(())
+}
+
+// Reads and checks tech_tree.json, so a bad edit can be reported rather than crashing
+pub fn load_tech_tree_data() -> Result<Value, String> {
+    let tech_tree_data = loader::read_text_from_file("resources/tech_tree.json")
+        .map_err(|err| format!("Could not read tech_tree.json: {}", err))?;
+    let mut tech_tree_data: Value = serde_json::from_str(tech_tree_data.as_ref())
+        .map_err(|err| format!("Could not parse tech_tree.json: {}", err))?;
+    mods::apply_tech_tree_patches(&mut tech_tree_data);
+    validate_node(&tech_tree_data)?;
+
+    Ok(tech_tree_data)
+}
+
 /**
  * This builds out the tech tree from a data source. It creates the entities to draw stuff on the screen
  * It then creates the hierarchy for dependencies, so we know when something becomes researchable upon its parent being researched.
@@ -134,12 +200,16 @@ pub fn build_tech_tree(
     container: &mut Node,
     upgrade_lines_lookup: &mut UpgradeLinesLookup,
 ) -> TechTreeNode {
-    let tech_tree_data = loader::read_text_from_file("resources/tech_tree.json").unwrap();
-    let tech_tree_data: serde_json::Value = serde_json::from_str(tech_tree_data.as_ref()).unwrap();
-
-    let dimensions = renderer::get_dimensions();
-    let width = dimensions[0] - 640.0;
+    let tech_tree_data = load_tech_tree_data().unwrap();
+    build_tech_tree_from_data(world, container, upgrade_lines_lookup, tech_tree_data)
+}
 
+pub fn build_tech_tree_from_data(
+    world: &mut World,
+    container: &mut Node,
+    upgrade_lines_lookup: &mut UpgradeLinesLookup,
+    tech_tree_data: Value,
+) -> TechTreeNode {
     let node = tech_tree_data;
     let last_upgrade = LastUpgrade {
         position: None,
@@ -150,7 +220,7 @@ pub fn build_tech_tree(
         world,
         container,
         upgrade_lines_lookup,
-        width,
+        SIDEBAR_WIDTH,
         node,
         last_upgrade,
     )
@@ -1,13 +1,16 @@
 use cgmath::Vector2;
-use components::{ui::TutorialUI, Actions, Color, EntityLookup, Node, Pulse, Rect, Shape, Text,
-                 Transform, TutorialStep};
+use components::{ui::TutorialUI, Anchor, Color, EntityLookup, Node, Pulse, Rect, Shape, Text,
+                 Transform, TutorialStep, UiCommand, UiCommands};
 use entities::{create_tooltip, recursive_delete};
 use specs::{Entities, Join, Read, ReadStorage, Write, WriteStorage};
 use std::ops::{Deref, DerefMut};
 use systems::logic;
 
+// The highlight goes into the parent node with the given anchor, so it follows the entity it
+// points at when the window is resized. x and y are within the parent
 pub fn create_step(
     entities: &Entities,
+    anchor_storage: &mut WriteStorage<Anchor>,
     color_storage: &mut WriteStorage<Color>,
     entity_lookup_storage: &Read<EntityLookup>,
     node_storage: &mut WriteStorage<Node>,
@@ -17,6 +20,8 @@ pub fn create_step(
     text_storage: &mut WriteStorage<Text>,
     tutorial_ui_storage: &mut WriteStorage<TutorialUI>,
     transform_storage: &mut WriteStorage<Transform>,
+    parent: &str,
+    anchor: Option<Anchor>,
     x: f32,
     y: f32,
     w: f32,
@@ -26,29 +31,32 @@ pub fn create_step(
     let pulse_shape = entities.create();
 
     let points = vec![
+        Vector2 { x: -2.0, y: -2.0 },
         Vector2 {
-            x: x - 2.0,
-            y: y - 2.0,
+            x: -2.0,
+            y: h + 2.0,
         },
         Vector2 {
-            x: x - 2.0,
-            y: y + h + 2.0,
+            x: w + 2.0,
+            y: h + 2.0,
         },
         Vector2 {
-            x: x + w + 2.0,
-            y: y + h + 2.0,
-        },
-        Vector2 {
-            x: x + w + 2.0,
-            y: y - 2.0,
+            x: w + 2.0,
+            y: -2.0,
         },
     ];
 
     let shape = Shape::new(points, [1.0, 1.0, 0.0, 0.0], false);
     shape_storage.insert(pulse_shape, shape).unwrap();
-    let mut transform = Transform::visible_identity();
-    transform.set_pos(0.0, 0.0, 4.0);
-    transform_storage.insert(pulse_shape, transform).unwrap();
+    transform_storage
+        .insert(
+            pulse_shape,
+            Transform::visible(x, y, 4.0, w as u16, h as u16, 0.0, 1.0, 1.0),
+        )
+        .unwrap();
+    if let Some(anchor) = anchor {
+        anchor_storage.insert(pulse_shape, anchor).unwrap();
+    }
     tutorial_ui_storage
         .insert(pulse_shape, TutorialUI {})
         .unwrap();
@@ -74,15 +82,14 @@ pub fn create_step(
     tutorial_ui_storage.insert(tooltip, TutorialUI {}).unwrap();
 
     let lookup = entity_lookup_storage.deref();
-    let node = logic::get_root(&lookup, node_storage);
-
-    node.add(pulse_shape);
-    node.add(tooltip);
+    let parent = *lookup.get(parent).unwrap();
+    node_storage.get_mut(parent).unwrap().add(pulse_shape);
+    logic::get_root(&lookup, node_storage).add(tooltip);
 }
 
 pub fn next_step(
     entities: &Entities,
-    actions_storage: &mut Write<Actions>,
+    ui_commands_storage: &mut Write<UiCommands>,
     tutorial_step_storage: &mut Write<TutorialStep>,
     tutorial_ui_storage: &ReadStorage<TutorialUI>,
     node_storage: &WriteStorage<Node>,
@@ -94,8 +101,7 @@ pub fn next_step(
         for (entity, _) in (&**entities, tutorial_ui_storage).join() {
             recursive_delete(entities, node_storage, &entity);
         }
-        let actions = actions_storage.deref_mut();
-        actions.dispatch(next_step.to_string(), String::new());
+        ui_commands_storage.push(UiCommand::ShowTutorialStep(next_step));
         *tutorial_step = next_step;
         true
     } else {
@@ -106,7 +112,7 @@ pub fn next_step(
 pub fn clear_ui(
     entities: &Entities,
     tutorial_step_storage: &Write<TutorialStep>,
-    tutorial_ui_storage: &ReadStorage<TutorialUI>,
+    tutorial_ui_storage: &WriteStorage<TutorialUI>,
     node_storage: &WriteStorage<Node>,
     current_step: TutorialStep,
 ) {
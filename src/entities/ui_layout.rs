@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+
+use components::{ui::UiScaled, Anchor, Button, Color, EntityLookup, Node, Palette, PaletteColor,
+                 Sprite, Text, ThemeColor, Transform};
+use entities::create_text_button;
+use gfx_glyph::HorizontalAlign;
+use loader;
+use localization::Localization;
+use serde_json;
+use specs::{Entity, LazyUpdate, Read, World};
+use spritesheet::Spritesheet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementType {
+    // a node holding the children, drawn only through them
+    Container,
+    Sprite,
+    Text,
+    // a rect with a text label, see create_text_button
+    TextButton,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LayoutColor {
+    // a color of the theme by name, such as "Coal", recolored when the theme changes
+    Palette(PaletteColor),
+    Rgba([f32; 4]),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TextStyle {
+    // the string to show, formatted with args. Plural forms are picked by count when it is given
+    pub key: String,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+    #[serde(default)]
+    pub count: Option<i64>,
+    #[serde(default = "default_font_size")]
+    pub size: f32,
+    #[serde(default = "default_text_color")]
+    pub color: LayoutColor,
+    #[serde(default)]
+    pub align: Option<TextAlign>,
+}
+
+fn default_font_size() -> f32 {
+    20.0
+}
+
+fn default_text_color() -> LayoutColor {
+    LayoutColor::Rgba([0.0, 1.0, 0.0, 1.0])
+}
+
+/**
+ * One entity of a ui screen, as read from resources/ui. Positions are relative to the parent
+ * element, and the root element is added under the scene's root node
+ */
+#[derive(Clone, Deserialize)]
+pub struct LayoutElement {
+    #[serde(rename = "type")]
+    pub element_type: ElementType,
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default)]
+    pub z: f32,
+    pub w: u16,
+    pub h: u16,
+    #[serde(default)]
+    pub anchor: Option<Anchor>,
+    #[serde(default)]
+    pub sprite: Option<String>,
+    // the sprite shown while the cursor is over a button, the normal one if left out
+    #[serde(default)]
+    pub hover_sprite: Option<String>,
+    // makes the element a button, this being the name ButtonHover reports it by
+    #[serde(default)]
+    pub button: Option<String>,
+    #[serde(default)]
+    pub text: Option<TextStyle>,
+    // the EntityLookup key systems find the entity by
+    #[serde(default)]
+    pub lookup: Option<String>,
+    // draws the element and its children at the ui scale from the settings
+    #[serde(default)]
+    pub ui_scaled: bool,
+    #[serde(default)]
+    pub children: Vec<LayoutElement>,
+}
+
+fn validate_element(
+    element: &LayoutElement,
+    keys: &mut HashSet<String>,
+    spritesheet: &Spritesheet,
+) -> Result<(), String> {
+    let name = element
+        .lookup
+        .clone()
+        .unwrap_or_else(|| format!("{:?}", element.element_type));
+
+    match element.element_type {
+        ElementType::Sprite if element.sprite.is_none() => {
+            return Err(format!("Sprite element {} needs a sprite", name));
+        }
+        ElementType::Text if element.text.is_none() => {
+            return Err(format!("Text element {} needs a text", name));
+        }
+        ElementType::TextButton if element.text.is_none() || element.button.is_none() => {
+            return Err(format!("Text button {} needs a text and button", name));
+        }
+        _ => {}
+    }
+
+    for sprite in element.sprite.iter().chain(element.hover_sprite.iter()) {
+        if !spritesheet
+            .frames
+            .iter()
+            .any(|frame| frame.filename == *sprite)
+        {
+            return Err(format!("{} uses {}, which is not in the spritesheet", name, sprite));
+        }
+    }
+
+    if element.element_type != ElementType::Container && !element.children.is_empty() {
+        return Err(format!("Only containers can have children, {} has some", name));
+    }
+
+    if let Some(ref key) = element.lookup {
+        if !keys.insert(key.clone()) {
+            return Err(format!("The lookup key {} is used more than once", key));
+        }
+    }
+
+    for child in &element.children {
+        validate_element(child, keys, spritesheet)?;
+    }
+
+    Ok(())
+}
+
+// Reads and checks a layout file, failing when it is missing any of the keys the game looks up or
+// uses a sprite the spritesheet doesn't have
+pub fn load_layout(
+    path: &str,
+    required_keys: &[&str],
+    spritesheet: &Spritesheet,
+) -> Result<LayoutElement, String> {
+    let layout_data = loader::read_text_from_file(path)
+        .map_err(|err| format!("Could not read {}: {}", path, err))?;
+    let layout: LayoutElement = serde_json::from_str(layout_data.as_ref())
+        .map_err(|err| format!("Could not parse {}: {}", path, err))?;
+
+    let mut keys = HashSet::new();
+    validate_element(&layout, &mut keys, spritesheet)
+        .map_err(|err| format!("{}: {}", path, err))?;
+    for key in required_keys {
+        if !keys.contains(*key) {
+            return Err(format!("{} has no element with the lookup key {}", path, key));
+        }
+    }
+
+    Ok(layout)
+}
+
+fn get_text(style: &TextStyle, args: &[(&str, String)], localization: &Localization) -> String {
+    // args from the game replace the placeholder ones in the file
+    let mut text_args: Vec<(&str, String)> = style
+        .args
+        .iter()
+        .filter(|&(key, _)| !args.iter().any(|&(arg, _)| arg == key.as_str()))
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect();
+    text_args.extend(args.iter().cloned());
+
+    match style.count {
+        Some(count) => localization.plural(&style.key, count, &text_args),
+        None => localization.format(&style.key, &text_args),
+    }
+}
+
+/**
+ * Creates the entities of a layout, returning the root element's entity. Elements with a lookup
+ * key are added to the lookup. Args fill in the placeholders of every text in the layout
+ */
+pub fn build_layout(
+    world: &mut World,
+    element: &LayoutElement,
+    args: &[(&str, String)],
+    lookup: &mut EntityLookup,
+) -> Entity {
+    let transform = Transform::visible(
+        element.x, element.y, element.z, element.w, element.h, 0.0, 1.0, 1.0,
+    );
+
+    let entity = match element.element_type {
+        ElementType::Container => {
+            let mut node = Node::new();
+            for child in &element.children {
+                node.add(build_layout(world, child, args, lookup));
+            }
+            world.create_entity().with(transform).with(node).build()
+        }
+        ElementType::Sprite => {
+            let sprite = element.sprite.clone().unwrap();
+            let mut builder = world.create_entity().with(transform).with(Sprite {
+                frame_name: sprite.clone(),
+            });
+            if let Some(ref name) = element.button {
+                let hover_sprite = element.hover_sprite.clone().unwrap_or_else(|| sprite.clone());
+                builder = builder.with(Button::new(name.clone(), [sprite, hover_sprite]));
+            }
+            builder.build()
+        }
+        ElementType::Text => {
+            let style = element.text.as_ref().unwrap();
+            let text = get_text(style, args, &world.read_resource::<Localization>());
+            let mut text = Text::new_with_text(style.size, element.w, element.h, text);
+            if let Some(align) = style.align {
+                text = text.align(match align {
+                    TextAlign::Left => HorizontalAlign::Left,
+                    TextAlign::Center => HorizontalAlign::Center,
+                    TextAlign::Right => HorizontalAlign::Right,
+                });
+            }
+            let color = match style.color {
+                LayoutColor::Palette(color) => world.read_resource::<Palette>().get(color),
+                LayoutColor::Rgba(rgba) => rgba,
+            };
+            let mut builder = world
+                .create_entity()
+                .with(transform)
+                .with(text)
+                .with(Color(color));
+            if let LayoutColor::Palette(color) = style.color {
+                builder = builder.with(ThemeColor(color));
+            }
+            builder.build()
+        }
+        ElementType::TextButton => {
+            let entities = world.entities();
+            let lazy: Read<LazyUpdate> =
+                Read::<LazyUpdate>::from(world.read_resource::<LazyUpdate>());
+            let label = get_text(
+                element.text.as_ref().unwrap(),
+                args,
+                &world.read_resource::<Localization>(),
+            );
+            create_text_button::create(
+                &entities,
+                &lazy,
+                &mut world.write_storage::<Node>(),
+                element.button.as_ref().unwrap(),
+                label,
+                element.x,
+                element.y,
+                element.z,
+                element.w,
+                element.h,
+            )
+        }
+    };
+
+    if let Some(ref anchor) = element.anchor {
+        world.write_storage::<Anchor>().insert(entity, anchor.clone()).unwrap();
+    }
+    if element.ui_scaled {
+        world.write_storage::<UiScaled>().insert(entity, UiScaled).unwrap();
+    }
+    if let Some(ref key) = element.lookup {
+        lookup.entities.insert(key.clone(), entity);
+    }
+
+    entity
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::time::{Instant, SystemTime};
+
+use specs::{Entity, Join, World, WriteStorage};
+
+use components::{ui::TechStatusMark,
+                 upgrade::{Buff, Status, Upgrade, UpgradeLinesLookup},
+                 CurrentState, EntityLookup, Node, ResearchedBuffs, Shape, Sprite, StateId,
+                 UiCommand, UiCommands};
+use entities::tech_tree::{self, TechTreeNode};
+use image::RgbaImage;
+use loader;
+use mods;
+use spritesheet::Spritesheet;
+
+pub use mods::{ASSETS_JSON, ASSETS_PNG};
+pub const TECH_TREE_JSON: &str = "resources/tech_tree.json";
+
+const POLL_INTERVAL: f32 = 0.5;
+
+// set ENERGY_GRID_DEV=1 to watch the resources directory while the game runs
+pub fn is_dev_mode() -> bool {
+    env::var("ENERGY_GRID_DEV")
+        .map(|value| value != "" && value != "0")
+        .unwrap_or(false)
+}
+
+fn get_modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(loader::get_exe_path().join(path))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Polls the modified time of data files, as there is no file watching available to us
+pub struct ResourceWatcher {
+    files: HashMap<&'static str, Option<SystemTime>>,
+    last_poll: Instant,
+    // reload errors not shown yet, only the play state displays them
+    pending_errors: Vec<String>,
+    // tech_tree.json changed while the play state, which holds the tree, wasn't active
+    tech_tree_pending: bool,
+}
+
+impl ResourceWatcher {
+    pub fn new(paths: &[&'static str]) -> Self {
+        ResourceWatcher {
+            files: paths
+                .iter()
+                .map(|path| (*path, get_modified_time(path)))
+                .collect(),
+            last_poll: Instant::now(),
+            pending_errors: Vec::new(),
+            tech_tree_pending: false,
+        }
+    }
+
+    pub fn get_changed_files(&mut self) -> Vec<&'static str> {
+        let elapsed = self.last_poll.elapsed();
+        if (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0)
+            < POLL_INTERVAL
+        {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+
+        let mut changed = Vec::new();
+        for (path, modified) in self.files.iter_mut() {
+            let current = get_modified_time(path);
+            if current != *modified {
+                *modified = current;
+                changed.push(*path);
+            }
+        }
+
+        changed
+    }
+
+    // the old data stays loaded, the error waits for the play state if another one is active
+    pub fn report_error(&mut self, error: String) {
+        eprintln!("Reload failed: {}", error);
+        self.pending_errors.push(error);
+    }
+
+    pub fn queue_tech_tree_reload(&mut self) {
+        self.tech_tree_pending = true;
+    }
+
+    // applies what waited for the play state, once it is the active one
+    pub fn apply_pending(&mut self, world: &mut World) {
+        if world.read_resource::<CurrentState>().0 != StateId::Play {
+            return;
+        }
+
+        if self.tech_tree_pending {
+            self.tech_tree_pending = false;
+            if let Err(err) = reload_tech_tree(world) {
+                self.report_error(err);
+            }
+        }
+
+        let mut ui_commands = world.write_resource::<UiCommands>();
+        for error in self.pending_errors.drain(..) {
+            ui_commands.push(UiCommand::DisplayError(error));
+        }
+    }
+}
+
+// Loads the new spritesheet along with the sprites of mods, checking that every sprite currently
+// on screen still has a frame
+pub fn load_spritesheet(world: &World) -> Result<(Spritesheet, RgbaImage), String> {
+    let (spritesheet, image) = mods::merge_spritesheet()?;
+
+    let sprite_storage = world.read_storage::<Sprite>();
+    for sprite in sprite_storage.join() {
+        if !spritesheet
+            .frames
+            .iter()
+            .any(|frame| frame.filename == sprite.frame_name)
+        {
+            return Err(format!(
+                "{} is missing frame {}",
+                ASSETS_JSON, sprite.frame_name
+            ));
+        }
+    }
+
+    Ok((spritesheet, image))
+}
+
+fn unlock_sub_nodes(
+    node: &TechTreeNode,
+    researched_entity: &Entity,
+    upgrade_storage: &mut WriteStorage<Upgrade>,
+) {
+    tech_tree::traverse_tree(node, &mut |node: &TechTreeNode| {
+        if node.entity != *researched_entity {
+            return false;
+        }
+
+        for sub_node in &node.sub_nodes {
+            let upgrade = upgrade_storage.get_mut(sub_node.entity).unwrap();
+            if upgrade.status == Status::Locked {
+                upgrade.status = Status::Researchable;
+            }
+        }
+        true
+    });
+}
+
+// Rebuilds the tech tree entities from tech_tree.json. Research is carried over by buff,
+// so upgrades that were moved or re-ordered keep their progress
+fn reload_tech_tree(world: &mut World) -> Result<(), String> {
+    let data = tech_tree::load_tech_tree_data()?;
+
+    let saved_upgrades: HashMap<Buff, (Status, f32, i32)> = {
+        let upgrade_storage = world.read_storage::<Upgrade>();
+        upgrade_storage
+            .join()
+            .map(|upgrade| {
+                (
+                    upgrade.buff,
+                    (
+                        upgrade.status,
+                        upgrade.current_research_progress,
+                        upgrade.cost,
+                    ),
+                )
+            })
+            .collect()
+    };
+
+    let container_entity = *world
+        .read_resource::<EntityLookup>()
+        .get("tech_tree_container")
+        .unwrap();
+
+    {
+        let entities = world.entities();
+        let upgrade_storage = world.read_storage::<Upgrade>();
+        for (entity, _) in (&*entities, &upgrade_storage).join() {
+            entities.delete(entity).unwrap();
+        }
+        let tech_status_mark_storage = world.read_storage::<TechStatusMark>();
+        for (entity, _) in (&*entities, &tech_status_mark_storage).join() {
+            entities.delete(entity).unwrap();
+        }
+        let upgrade_lines_lookup = world.read_resource::<UpgradeLinesLookup>();
+        for line_entities in upgrade_lines_lookup.entities.values() {
+            for line_entity in line_entities {
+                entities.delete(*line_entity).unwrap();
+            }
+        }
+    }
+    world.maintain();
+
+    let mut container = Node::new();
+    let mut upgrade_lines_lookup = UpgradeLinesLookup::new();
+    let tech_tree_node =
+        tech_tree::build_tech_tree_from_data(world, &mut container, &mut upgrade_lines_lookup, data);
+
+    let researched_entities: Vec<Entity> = {
+        let entities = world.entities();
+        let researched_buffs = world.read_resource::<ResearchedBuffs>();
+        let mut upgrade_storage = world.write_storage::<Upgrade>();
+        let mut researched_entities = Vec::new();
+        for (entity, upgrade) in (&*entities, &mut upgrade_storage).join() {
+            if let Some(&(status, progress, cost)) = saved_upgrades.get(&upgrade.buff) {
+                upgrade.status = status;
+                upgrade.current_research_progress = progress;
+                upgrade.cost = cost;
+            }
+            if upgrade.status == Status::Researched
+                || researched_buffs.0.contains_key(&upgrade.buff)
+            {
+                researched_entities.push(entity);
+            }
+        }
+
+        // upgrades newly added under researched ones become available straight away
+        for entity in &researched_entities {
+            unlock_sub_nodes(&tech_tree_node, entity, &mut upgrade_storage);
+        }
+
+        researched_entities
+    };
+
+    // lines leading out of researched upgrades are drawn as unlocked
+    {
+        let mut shape_storage = world.write_storage::<Shape>();
+        for entity in &researched_entities {
+            if let Some(line_entities) = upgrade_lines_lookup.entities.get(entity) {
+                for line_entity in line_entities {
+                    shape_storage
+                        .get_mut(*line_entity)
+                        .unwrap()
+                        .set_color([0.7, 0.7, 0.7, 1.0]);
+                }
+            }
+        }
+    }
+
+    {
+        let mut node_storage = world.write_storage::<Node>();
+        node_storage
+            .get_mut(container_entity)
+            .unwrap()
+            .add_many(container.entities);
+    }
+
+    world.add_resource(upgrade_lines_lookup);
+    world.add_resource::<TechTreeNode>(tech_tree_node);
+
+    Ok(())
+}
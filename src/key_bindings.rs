@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use conrod::Ui;
+use glutin::VirtualKeyCode;
+use localization::Localization;
+use state::UiAction;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    BuildCoal,
+    BuildOil,
+    BuildSolar,
+    BuildHydro,
+    OpenTechTree,
+    AddCity,
+    Pause,
+    SpeedUp,
+    SlowDown,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    SelectTile,
+    InspectTile,
+    // used together with ctrl
+    Undo,
+}
+
+impl InputAction {
+    pub fn all() -> [InputAction; 16] {
+        [
+            InputAction::BuildCoal,
+            InputAction::BuildOil,
+            InputAction::BuildSolar,
+            InputAction::BuildHydro,
+            InputAction::OpenTechTree,
+            InputAction::AddCity,
+            InputAction::Pause,
+            InputAction::SpeedUp,
+            InputAction::SlowDown,
+            InputAction::CursorUp,
+            InputAction::CursorDown,
+            InputAction::CursorLeft,
+            InputAction::CursorRight,
+            InputAction::SelectTile,
+            InputAction::InspectTile,
+            InputAction::Undo,
+        ]
+    }
+
+    pub fn get_default_key(&self) -> VirtualKeyCode {
+        match *self {
+            InputAction::BuildCoal => VirtualKeyCode::Key1,
+            InputAction::BuildOil => VirtualKeyCode::Key2,
+            InputAction::BuildSolar => VirtualKeyCode::Key3,
+            InputAction::BuildHydro => VirtualKeyCode::Key4,
+            InputAction::OpenTechTree => VirtualKeyCode::T,
+            InputAction::AddCity => VirtualKeyCode::C,
+            InputAction::Pause => VirtualKeyCode::Escape,
+            InputAction::SpeedUp => VirtualKeyCode::Period,
+            InputAction::SlowDown => VirtualKeyCode::Comma,
+            InputAction::CursorUp => VirtualKeyCode::Up,
+            InputAction::CursorDown => VirtualKeyCode::Down,
+            InputAction::CursorLeft => VirtualKeyCode::Left,
+            InputAction::CursorRight => VirtualKeyCode::Right,
+            InputAction::SelectTile => VirtualKeyCode::Return,
+            InputAction::InspectTile => VirtualKeyCode::I,
+            InputAction::Undo => VirtualKeyCode::Z,
+        }
+    }
+
+    // WASD moves the tile cursor as well as the arrow keys
+    fn get_alternate_key(&self) -> Option<VirtualKeyCode> {
+        match *self {
+            InputAction::CursorUp => Some(VirtualKeyCode::W),
+            InputAction::CursorDown => Some(VirtualKeyCode::S),
+            InputAction::CursorLeft => Some(VirtualKeyCode::A),
+            InputAction::CursorRight => Some(VirtualKeyCode::D),
+            _ => None,
+        }
+    }
+}
+
+// VirtualKeyCode can't be serialized, so keys are saved by name. Only these can be bound
+const BINDABLE_KEYS: [VirtualKeyCode; 62] = [
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::A,
+    VirtualKeyCode::B,
+    VirtualKeyCode::C,
+    VirtualKeyCode::D,
+    VirtualKeyCode::E,
+    VirtualKeyCode::F,
+    VirtualKeyCode::G,
+    VirtualKeyCode::H,
+    VirtualKeyCode::I,
+    VirtualKeyCode::J,
+    VirtualKeyCode::K,
+    VirtualKeyCode::L,
+    VirtualKeyCode::M,
+    VirtualKeyCode::N,
+    VirtualKeyCode::O,
+    VirtualKeyCode::P,
+    VirtualKeyCode::Q,
+    VirtualKeyCode::R,
+    VirtualKeyCode::S,
+    VirtualKeyCode::T,
+    VirtualKeyCode::U,
+    VirtualKeyCode::V,
+    VirtualKeyCode::W,
+    VirtualKeyCode::X,
+    VirtualKeyCode::Y,
+    VirtualKeyCode::Z,
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::F1,
+    VirtualKeyCode::F2,
+    VirtualKeyCode::F3,
+    VirtualKeyCode::F4,
+    VirtualKeyCode::F5,
+    VirtualKeyCode::F6,
+    VirtualKeyCode::F7,
+    VirtualKeyCode::F8,
+    VirtualKeyCode::Left,
+    VirtualKeyCode::Up,
+    VirtualKeyCode::Right,
+    VirtualKeyCode::Down,
+    VirtualKeyCode::Back,
+    VirtualKeyCode::Return,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::Tab,
+    VirtualKeyCode::Comma,
+    VirtualKeyCode::Period,
+    VirtualKeyCode::Minus,
+    VirtualKeyCode::Equals,
+    VirtualKeyCode::Add,
+    VirtualKeyCode::Subtract,
+    VirtualKeyCode::Slash,
+    VirtualKeyCode::Semicolon,
+    VirtualKeyCode::Apostrophe,
+];
+
+pub fn get_key_name(key: &VirtualKeyCode) -> String {
+    format!("{:?}", key)
+}
+
+fn get_key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .find(|key| get_key_name(key) == name)
+        .cloned()
+}
+
+pub fn is_bindable(key: &VirtualKeyCode) -> bool {
+    BINDABLE_KEYS.contains(key)
+}
+
+// the label shown to players, so Key1 reads as 1
+pub fn get_key_label(key: &VirtualKeyCode) -> String {
+    let name = get_key_name(key);
+    if name.len() == 4 && name.starts_with("Key") {
+        name[3..].to_string()
+    } else {
+        name
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    // actions missing from here use their default key, so new actions work with old settings files
+    keys: HashMap<InputAction, String>,
+}
+
+impl KeyBindings {
+    pub fn get_key(&self, action: &InputAction) -> VirtualKeyCode {
+        self.keys
+            .get(action)
+            .and_then(|name| get_key_from_name(name))
+            .unwrap_or_else(|| action.get_default_key())
+    }
+
+    // alternate keys give way when the player binds them to something else
+    pub fn get_alternate_key(&self, action: &InputAction) -> Option<VirtualKeyCode> {
+        action.get_alternate_key().and_then(|key| {
+            if InputAction::all()
+                .iter()
+                .any(|other| self.get_key(other) == key)
+            {
+                None
+            } else {
+                Some(key)
+            }
+        })
+    }
+
+    // a key can only do one thing, so an action already using it swaps to the old key
+    pub fn set_key(&mut self, action: InputAction, key: VirtualKeyCode) {
+        let old_key = self.get_key(&action);
+        for other in InputAction::all().iter() {
+            if *other != action && self.get_key(other) == key {
+                self.keys.insert(*other, get_key_name(&old_key));
+            }
+        }
+        self.keys.insert(action, get_key_name(&key));
+    }
+
+    pub fn reset(&mut self) {
+        self.keys.clear();
+    }
+}
+
+widget_ids! {
+    pub struct Ids {
+        controls_label,
+        close_button,
+        note_label,
+        reset_button,
+        action_labels[],
+        key_buttons[],
+    }
+}
+
+// waiting_for_key is the action being rebound, whose next key press is captured by the caller
+pub fn create_ui(
+    ui: &mut Ui,
+    ids: &mut Ids,
+    key_bindings: &KeyBindings,
+    localization: &Localization,
+    waiting_for_key: &mut Option<InputAction>,
+) -> Option<UiAction> {
+    use conrod::{color, position::Relative, widget, Colorable, Labelable, Positionable, Sizeable,
+                 Widget};
+
+    let actions = InputAction::all();
+    ids.action_labels
+        .resize(actions.len(), &mut ui.widget_id_generator());
+    ids.key_buttons
+        .resize(actions.len(), &mut ui.widget_id_generator());
+
+    let ui = &mut ui.set_widgets();
+
+    if widget::Button::new()
+        .top_right_with_margin_on(ui.window, 20.0)
+        .w_h(30.0, 30.0)
+        .label("X")
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .label_x(Relative::Scalar(1.0))
+        .label_y(Relative::Scalar(2.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.close_button, ui)
+        .was_clicked()
+    {
+        *waiting_for_key = None;
+        return Some(UiAction::Close);
+    }
+
+    widget::Text::new(&localization.get("controls.title"))
+        .mid_top_with_margin_on(ui.window, 30.0)
+        .font_size(32)
+        .rgb(0.0, 1.0, 0.0)
+        .set(ids.controls_label, ui);
+
+    widget::Text::new(&localization.get("controls.note"))
+        .down_from(ids.controls_label, 10.0)
+        .align_middle_x_of(ids.controls_label)
+        .font_size(16)
+        .rgb(0.8, 0.8, 0.8)
+        .set(ids.note_label, ui);
+
+    for (i, action) in actions.iter().enumerate() {
+        // two columns, so every action fits on screen
+        let column = i / 8;
+        let row = i % 8;
+
+        let action_text = localization.get(&format!("controls.{:?}", action));
+        let label = widget::Text::new(&action_text)
+            .font_size(18)
+            .color(color::rgb(0.0, 1.0, 0.0));
+        let label = if row == 0 {
+            label
+                .down_from(ids.note_label, 30.0)
+                .x_relative_to(ui.window, -400.0 + 430.0 * column as f64)
+        } else {
+            label
+                .down_from(ids.action_labels[i - 1], 36.0)
+                .align_left_of(ids.action_labels[i - 1])
+        };
+        label.set(ids.action_labels[i], ui);
+
+        let key_label = if *waiting_for_key == Some(*action) {
+            localization.get("controls.press_key")
+        } else {
+            get_key_label(&key_bindings.get_key(action))
+        };
+
+        if widget::Button::new()
+            .align_middle_y_of(ids.action_labels[i])
+            .x_relative_to(ui.window, -170.0 + 430.0 * column as f64)
+            .w_h(140.0, 35.0)
+            .label(&key_label)
+            .label_color(color::rgb(0.0, 1.0, 0.0))
+            .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+            .set(ids.key_buttons[i], ui)
+            .was_clicked()
+        {
+            *waiting_for_key = Some(*action);
+        }
+    }
+
+    if widget::Button::new()
+        .mid_bottom_with_margin_on(ui.window, 30.0)
+        .w_h(200.0, 35.0)
+        .label(&localization.get("controls.reset"))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.reset_button, ui)
+        .was_clicked()
+    {
+        *waiting_for_key = None;
+        return Some(UiAction::ResetControls);
+    }
+
+    None
+}
@@ -2,44 +2,86 @@ extern crate gfx;
 extern crate image;
 
 use std::env;
-use std::fs::{create_dir, File};
+use std::fs::{create_dir, create_dir_all, File};
 use std::io::prelude::Read;
 use std::io::BufReader;
 use std::io::Result;
 use std::path::{Path, PathBuf};
 
+use achievements::Achievements;
 use dirs;
 use gfx::texture::Mipmap;
+use mods;
 use rodio::{decoder::Decoder, Decoder as SoundDecoder};
+use run_history::RunHistory;
 use serde_json;
 use settings::Settings;
 
+// the menu backgrounds in resources/startscreens, by the name they have in the texture map
+pub const SCREEN_IMAGES: [&str; 4] = [
+    "screenone.png",
+    "screentwo.png",
+    "screenthree.png",
+    "screenfour.png",
+];
+
 pub fn gfx_load_texture<F, R>(
     path: &str,
     factory: &mut F,
 ) -> (gfx::handle::ShaderResourceView<R, [f32; 4]>, u16, u16)
+where
+    F: gfx::Factory<R>,
+    R: gfx::Resources,
+{
+    try_gfx_load_texture(path, factory).unwrap()
+}
+
+pub fn try_gfx_load_texture<F, R>(
+    path: &str,
+    factory: &mut F,
+) -> std::result::Result<(gfx::handle::ShaderResourceView<R, [f32; 4]>, u16, u16), String>
+where
+    F: gfx::Factory<R>,
+    R: gfx::Resources,
+{
+    let img = load_image(path)?;
+    let (width, height) = img.dimensions();
+    let view = gfx_create_texture(&img, factory)
+        .map_err(|err| format!("Could not create texture for {}: {}", path, err))?;
+    Ok((view, width as u16, height as u16))
+}
+
+pub fn load_image(path: &str) -> std::result::Result<image::RgbaImage, String> {
+    let full_path = get_resource_path(path);
+    image::open(full_path)
+        .map(|img| img.to_rgba())
+        .map_err(|err| format!("Could not load {}: {}", path, err))
+}
+
+pub fn gfx_create_texture<F, R>(
+    img: &image::RgbaImage,
+    factory: &mut F,
+) -> std::result::Result<gfx::handle::ShaderResourceView<R, [f32; 4]>, String>
 where
     F: gfx::Factory<R>,
     R: gfx::Resources,
 {
     use gfx::format::Srgba8;
-    let path = get_exe_path().join(path);
-    let img = image::open(path).unwrap().to_rgba();
     let (width, height) = img.dimensions();
     let kind = gfx::texture::Kind::D2(width as u16, height as u16, gfx::texture::AaMode::Single);
     let (_, view) = factory
-        .create_texture_immutable_u8::<Srgba8>(kind, Mipmap::Allocated, &[&img])
-        .unwrap();
-    (view, width as u16, height as u16)
+        .create_texture_immutable_u8::<Srgba8>(kind, Mipmap::Allocated, &[&**img])
+        .map_err(|err| format!("{:?}", err))?;
+    Ok(view)
 }
 
 pub fn create_sound(sound_file_path: &str) -> Decoder<BufReader<File>> {
-    let audio_file = File::open(&Path::new(&get_exe_path().join(sound_file_path))).unwrap();
+    let audio_file = File::open(&Path::new(&get_resource_path(sound_file_path))).unwrap();
     SoundDecoder::new(BufReader::new(audio_file)).unwrap()
 }
 
 pub fn read_text_from_file(path: &str) -> Result<String> {
-    let path = get_exe_path().join(path);
+    let path = get_resource_path(path);
     read_text_from_path(path)
 }
 
@@ -60,30 +102,78 @@ pub fn get_exe_path() -> PathBuf {
     }
 }
 
+// Enabled mods can replace any file the game ships with, the last mod in the load order wins
+pub fn get_resource_path(path: &str) -> PathBuf {
+    for mod_dir in mods::get_enabled_mod_dirs().iter().rev() {
+        let mod_path = mod_dir.join(path);
+        if mod_path.exists() {
+            return mod_path;
+        }
+    }
+    get_exe_path().join(path)
+}
+
+// Settings, saves and mods are read from here instead when set, which keeps snapshot runs away
+// from the player's own files
+pub const DATA_DIR_VAR: &str = "ENERGY_GRID_DATA_DIR";
+
+pub fn get_data_dir() -> PathBuf {
+    match env::var(DATA_DIR_VAR) {
+        Ok(ref dir) if dir != "" => {
+            let dir = PathBuf::from(dir);
+            if !dir.exists() {
+                create_dir_all(&dir).unwrap();
+            }
+            dir
+        }
+        _ => get_platform_data_dir(),
+    }
+}
+
 #[cfg(target_os = "linux")]
-pub fn get_settings_path() -> PathBuf {
+fn get_platform_data_dir() -> PathBuf {
     if let Some(home_dir) = dirs::home_dir() {
         if !home_dir.join("EnergyGrid").exists() {
             create_dir(home_dir.join("EnergyGrid")).unwrap();
         }
-        home_dir.join("EnergyGrid").join("settings.json")
+        home_dir.join("EnergyGrid")
     } else {
         panic!("Could not find $HOME");
     }
 }
 
 #[cfg(target_os = "windows")]
-pub fn get_settings_path() -> PathBuf {
-    get_settings_path_win_mac()
+fn get_platform_data_dir() -> PathBuf {
+    get_exe_path()
 }
 
 #[cfg(target_os = "macos")]
+fn get_platform_data_dir() -> PathBuf {
+    get_exe_path()
+}
+
 pub fn get_settings_path() -> PathBuf {
-    get_settings_path_win_mac()
+    get_data_dir().join("settings.json")
+}
+
+pub fn get_achievements_path() -> PathBuf {
+    get_data_dir().join("achievements.json")
+}
+
+pub fn get_run_history_path() -> PathBuf {
+    get_data_dir().join("run_history.json")
+}
+
+pub fn get_mods_dir() -> PathBuf {
+    let mods_dir = get_data_dir().join("mods");
+    if !mods_dir.exists() {
+        create_dir(&mods_dir).unwrap();
+    }
+    mods_dir
 }
 
-fn get_settings_path_win_mac() -> PathBuf {
-    get_exe_path().join("settings.json")
+pub fn get_mod_list_path() -> PathBuf {
+    get_data_dir().join("mods.json")
 }
 
 pub fn load_settings() -> Settings {
@@ -95,3 +185,32 @@ pub fn load_settings() -> Settings {
         Settings::default()
     }
 }
+
+// a corrupt file starts the player over rather than stopping the game
+pub fn load_achievements() -> Achievements {
+    let achievements_path = get_achievements_path();
+    if !achievements_path.exists() {
+        return Achievements::default();
+    }
+
+    let result = read_text_from_path(achievements_path.clone())
+        .map_err(|err| err.to_string())
+        .and_then(|text| serde_json::from_str(text.as_ref()).map_err(|err| err.to_string()));
+    match result {
+        Ok(achievements) => achievements,
+        Err(err) => {
+            eprintln!("Could not load {}: {}", achievements_path.display(), err);
+            Achievements::default()
+        }
+    }
+}
+
+pub fn load_run_history() -> RunHistory {
+    let run_history_path = get_run_history_path();
+    if run_history_path.exists() {
+        let run_history_text = read_text_from_path(run_history_path).unwrap();
+        serde_json::from_str(run_history_text.as_ref()).unwrap()
+    } else {
+        RunHistory::default()
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::fs;
+
+use loader;
+use mods;
+use serde_json;
+
+const DEFAULT_LANGUAGE: &str = "en";
+const LANG_DIR: &str = "resources/lang";
+// MunroSmall only covers latin script, other scripts are drawn with this font. It ships with the
+// game as Noto Sans, under the Apache license in resources/fonts/LICENSE-fallback.txt
+pub const FALLBACK_FONT: &str = "resources/fonts/fallback.ttf";
+
+#[derive(Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PluralRule {
+    // 1 is "one", everything else is "other"
+    OneOther,
+    // 0 and 1 are "one"
+    ZeroOneOther,
+    // "one", "few" and "many", as used by russian and ukrainian
+    EastSlavic,
+    // no plural forms, only "other"
+    Other,
+}
+
+impl Default for PluralRule {
+    fn default() -> Self {
+        PluralRule::OneOther
+    }
+}
+
+impl PluralRule {
+    fn get_category(&self, count: i64) -> &'static str {
+        let count = count.abs();
+        match *self {
+            PluralRule::OneOther => if count == 1 {
+                "one"
+            } else {
+                "other"
+            },
+            PluralRule::ZeroOneOther => if count <= 1 {
+                "one"
+            } else {
+                "other"
+            },
+            PluralRule::EastSlavic => {
+                let (last, last_two) = (count % 10, count % 100);
+                if last == 1 && last_two != 11 {
+                    "one"
+                } else if last >= 2 && last <= 4 && (last_two < 12 || last_two > 14) {
+                    "few"
+                } else {
+                    "many"
+                }
+            }
+            PluralRule::Other => "other",
+        }
+    }
+}
+
+fn default_thousands_separator() -> String {
+    ",".to_string()
+}
+
+// resources/lang/<code>.json
+#[derive(Deserialize)]
+struct StringTable {
+    name: String,
+    #[serde(default)]
+    plural_rule: PluralRule,
+    #[serde(default = "default_thousands_separator")]
+    thousands_separator: String,
+    #[serde(default)]
+    needs_fallback_font: bool,
+    #[serde(default)]
+    strings: HashMap<String, String>,
+}
+
+fn load_table(code: &str) -> Result<StringTable, String> {
+    let path = format!("{}/{}.json", LANG_DIR, code);
+    let text = loader::read_text_from_file(&path)
+        .map_err(|err| format!("Could not read {}: {}", path, err))?;
+    serde_json::from_str(text.as_ref()).map_err(|err| format!("Could not parse {}: {}", path, err))
+}
+
+// Every language installed with the game or added by an enabled mod, as (code, name)
+pub fn get_languages() -> Vec<(String, String)> {
+    let mut dirs = vec![loader::get_exe_path().join(LANG_DIR)];
+    dirs.extend(
+        mods::get_enabled_mod_dirs()
+            .into_iter()
+            .map(|dir| dir.join(LANG_DIR)),
+    );
+
+    let mut codes: Vec<String> = dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .filter_map(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|stem| stem.to_string())
+        })
+        .collect();
+    codes.sort();
+    codes.dedup();
+
+    codes
+        .into_iter()
+        .filter_map(|code| load_table(&code).ok().map(|table| (code, table.name)))
+        .collect()
+}
+
+/**
+ * The string table for the current language. Strings are looked up by id, with {name} placeholders
+ * filled from the arguments. Ids missing from a translation fall back to english.
+ */
+pub struct Localization {
+    code: String,
+    plural_rule: PluralRule,
+    thousands_separator: String,
+    needs_fallback_font: bool,
+    strings: HashMap<String, String>,
+}
+
+impl Localization {
+    pub fn load(code: &str) -> Self {
+        let mut table = load_table(DEFAULT_LANGUAGE).unwrap();
+        let mut loaded_code = DEFAULT_LANGUAGE.to_string();
+
+        if code != "" && code != DEFAULT_LANGUAGE {
+            match load_table(code) {
+                Ok(translation) => {
+                    let mut strings = table.strings;
+                    strings.extend(translation.strings);
+                    table = StringTable {
+                        strings,
+                        ..translation
+                    };
+                    loaded_code = code.to_string();
+                }
+                Err(err) => eprintln!("{}, using english", err),
+            }
+        }
+
+        Localization {
+            code: loaded_code,
+            plural_rule: table.plural_rule,
+            thousands_separator: table.thousands_separator,
+            needs_fallback_font: table.needs_fallback_font,
+            strings: table.strings,
+        }
+    }
+
+    pub fn get_code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn needs_fallback_font(&self) -> bool {
+        self.needs_fallback_font
+    }
+
+    pub fn get(&self, id: &str) -> String {
+        self.strings
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    // for text that comes from data files, which is used when a translation does not cover it
+    pub fn get_or(&self, id: &str, default: &str) -> String {
+        self.strings
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    pub fn format(&self, id: &str, args: &[(&str, String)]) -> String {
+        let mut text = self.get(id);
+        for &(name, ref value) in args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+
+    // Picks the plural form for count from "<id>.one", "<id>.few", "<id>.many" or "<id>.other".
+    // The count is available to the string as {count}
+    pub fn plural(&self, id: &str, count: i64, args: &[(&str, String)]) -> String {
+        let key = format!("{}.{}", id, self.plural_rule.get_category(count));
+        let key = if self.strings.contains_key(&key) {
+            key
+        } else {
+            format!("{}.other", id)
+        };
+
+        let mut args = args.to_vec();
+        args.push(("count", self.number(count)));
+        self.format(&key, &args)
+    }
+
+    pub fn number(&self, value: i64) -> String {
+        let digits = value.abs().to_string();
+        let mut text = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                text.push_str(&self.thousands_separator);
+            }
+            text.push(digit);
+        }
+
+        if value < 0 {
+            format!("-{}", text)
+        } else {
+            text
+        }
+    }
+
+    // the sign goes before the currency symbol, so losses read as -$5
+    pub fn money(&self, value: i32) -> String {
+        let text = self.format("money", &[("amount", self.number((value as i64).abs()))]);
+        if value < 0 {
+            format!("-{}", text)
+        } else {
+            text
+        }
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Localization::load(DEFAULT_LANGUAGE)
+    }
+}
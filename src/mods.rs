@@ -0,0 +1,514 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use conrod::{widget, Ui, UiCell};
+use gfx;
+use image::{self, GenericImage, RgbaImage};
+use loader::{self, get_mod_list_path, get_mods_dir};
+use serde_json::{self, Value};
+use spritesheet::Spritesheet;
+use state::UiAction;
+
+pub const ASSETS_JSON: &str = "resources/assets.json";
+pub const ASSETS_PNG: &str = "resources/assets.png";
+
+/**
+ * Mods live in the mods folder of the user data directory, one folder per mod:
+ * mod.json - name and description shown in the mod list
+ * sprites.json & sprites.png - frames merged into the spritesheet, replacing frames of the same name
+ * resources/ - files that replace the game's own, such as resources/click.ogg
+ * music/<name>.ogg - extra tracks for the in game playlist
+ * tech_tree_nodes.json - nodes to change or add to the tech tree
+ * rules.json - values to change in the difficulty presets
+ * Mods later in the load order win.
+ */
+
+#[derive(Default, Deserialize)]
+pub struct ModInfo {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ModEntry {
+    pub folder: String,
+    pub enabled: bool,
+    #[serde(skip)]
+    pub info: ModInfo,
+}
+
+impl ModEntry {
+    pub fn get_dir(&self) -> PathBuf {
+        get_mods_dir().join(&self.folder)
+    }
+
+    fn load_info(&self) -> ModInfo {
+        let mut info: ModInfo = loader::read_text_from_path(self.get_dir().join("mod.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str(text.as_ref()).ok())
+            .unwrap_or_default();
+        if info.name == "" {
+            info.name = self.folder.clone();
+        }
+        info
+    }
+}
+
+// The load order of installed mods, saved so it survives mods being added or removed
+#[derive(Default, Serialize, Deserialize)]
+pub struct ModList {
+    #[serde(default)]
+    pub mods: Vec<ModEntry>,
+}
+
+impl ModList {
+    pub fn load() -> Self {
+        let path = get_mod_list_path();
+        let mut mod_list: ModList = if path.exists() {
+            let text = loader::read_text_from_path(path).unwrap();
+            serde_json::from_str(text.as_ref()).unwrap_or_default()
+        } else {
+            ModList::default()
+        };
+
+        let mut folders: Vec<String> = fs::read_dir(get_mods_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        folders.sort();
+
+        // forget mods that were deleted, newly installed ones go last
+        mod_list
+            .mods
+            .retain(|entry| folders.contains(&entry.folder));
+        for folder in folders {
+            if !mod_list.mods.iter().any(|entry| entry.folder == folder) {
+                mod_list.mods.push(ModEntry {
+                    folder,
+                    enabled: true,
+                    info: ModInfo::default(),
+                });
+            }
+        }
+
+        for entry in mod_list.mods.iter_mut() {
+            entry.info = entry.load_info();
+        }
+
+        mod_list
+    }
+
+    pub fn get_enabled_dirs(&self) -> Vec<PathBuf> {
+        self.mods
+            .iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| entry.get_dir())
+            .collect()
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        self.mods[index].enabled = enabled;
+        self.save();
+    }
+
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 {
+            self.mods.swap(index, index - 1);
+            self.save();
+        }
+    }
+
+    pub fn move_down(&mut self, index: usize) {
+        if index + 1 < self.mods.len() {
+            self.mods.swap(index, index + 1);
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let text = serde_json::to_string(&self).unwrap();
+        fs::write(get_mod_list_path().to_str().unwrap(), text)
+            .expect("Unable to write mod list");
+    }
+}
+
+thread_local! {
+    // read once, as changes to the mod list only apply after a restart. Every file the game loads
+    // is looked up here, so reading the list each time would mean parsing it for every sound
+    static ENABLED_MOD_DIRS: RefCell<Option<Vec<PathBuf>>> = RefCell::new(None);
+    // what was skipped while loading mods, shown on the mod list screen
+    static SKIPPED: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+pub fn get_enabled_mod_dirs() -> Vec<PathBuf> {
+    ENABLED_MOD_DIRS.with(|dirs| {
+        dirs.borrow_mut()
+            .get_or_insert_with(|| ModList::load().get_enabled_dirs())
+            .clone()
+    })
+}
+
+// the same file is loaded again for every new game, so each message is only kept once
+fn report_skipped(message: String) {
+    eprintln!("{}", message);
+    SKIPPED.with(|skipped| {
+        let mut skipped = skipped.borrow_mut();
+        if !skipped.contains(&message) {
+            skipped.push(message);
+        }
+    });
+}
+
+pub fn get_skipped() -> Vec<String> {
+    SKIPPED.with(|skipped| skipped.borrow().clone())
+}
+
+// Objects are merged key by key, anything else in the patch replaces the base value
+pub fn merge_json(base: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !base.is_object() {
+                *base = Value::Object(Default::default());
+            }
+            let base = base.as_object_mut().unwrap();
+            for (key, value) in patch {
+                merge_json(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        patch => *base = patch,
+    }
+}
+
+fn read_mod_json(dir: &PathBuf, file_name: &str) -> Option<Value> {
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return None;
+    }
+
+    match loader::read_text_from_path(path.clone())
+        .map_err(|err| err.to_string())
+        .and_then(|text| serde_json::from_str(text.as_ref()).map_err(|err| err.to_string()))
+    {
+        Ok(value) => Some(value),
+        Err(err) => {
+            report_skipped(format!("Skipping {}: {}", path.display(), err));
+            None
+        }
+    }
+}
+
+pub fn apply_rules_patches(presets: &mut Value) {
+    for dir in get_enabled_mod_dirs() {
+        if let Some(patch) = read_mod_json(&dir, "rules.json") {
+            merge_json(presets, patch);
+        }
+    }
+}
+
+fn patch_tech_tree_node(node: &mut Value, patch: &Value) -> bool {
+    if node["buff"] == patch["buff"] {
+        let mut patch = patch.clone();
+        if let Some(patch) = patch.as_object_mut() {
+            patch.remove("parent");
+            patch.remove("children");
+        }
+        merge_json(node, patch);
+        return true;
+    }
+
+    if node["buff"] == patch["parent"] {
+        let mut child = patch.clone();
+        child.as_object_mut().unwrap().remove("parent");
+        if !node["children"].is_array() {
+            node["children"] = Value::Array(Vec::new());
+        }
+        node["children"].as_array_mut().unwrap().push(child);
+        return true;
+    }
+
+    if let Some(children) = node.get_mut("children").and_then(|c| c.as_array_mut()) {
+        for child in children {
+            if patch_tech_tree_node(child, patch) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// Each node either changes the node with the same buff, or is added under the node named by "parent".
+// Buffs are implemented by the game, so added nodes can only use buffs it already knows
+pub fn apply_tech_tree_patches(tech_tree: &mut Value) {
+    for dir in get_enabled_mod_dirs() {
+        if let Some(Value::Array(nodes)) = read_mod_json(&dir, "tech_tree_nodes.json") {
+            for node in nodes {
+                if !node.is_object() || !patch_tech_tree_node(tech_tree, &node) {
+                    report_skipped(format!(
+                        "Skipping tech tree node from {}: {}",
+                        dir.display(),
+                        node
+                    ));
+                }
+            }
+        }
+    }
+}
+
+pub fn get_music_tracks() -> Vec<String> {
+    let mut tracks = Vec::new();
+    for dir in get_enabled_mod_dirs() {
+        if let Ok(entries) = fs::read_dir(dir.join("music")) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map(|ext| ext == "ogg").unwrap_or(false))
+                .collect();
+            paths.sort();
+            tracks.extend(
+                paths
+                    .into_iter()
+                    .filter_map(|path| path.to_str().map(|path| path.to_string())),
+            );
+        }
+    }
+    tracks
+}
+
+fn load_mod_sprites(dir: &PathBuf) -> Result<(Spritesheet, RgbaImage), String> {
+    let text = loader::read_text_from_path(dir.join("sprites.json"))
+        .map_err(|err| format!("Could not read sprites.json: {}", err))?;
+    let spritesheet: Spritesheet = serde_json::from_str(text.as_ref())
+        .map_err(|err| format!("Could not parse sprites.json: {}", err))?;
+    let img = image::open(dir.join("sprites.png"))
+        .map_err(|err| format!("Could not load sprites.png: {}", err))?
+        .to_rgba();
+    Ok((spritesheet, img))
+}
+
+// Stacks each mod's sprites below the game's spritesheet, so everything still draws from one texture
+pub fn merge_spritesheet() -> Result<(Spritesheet, RgbaImage), String> {
+    let asset_data = loader::read_text_from_file(ASSETS_JSON)
+        .map_err(|err| format!("Could not read {}: {}", ASSETS_JSON, err))?;
+    let mut spritesheet: Spritesheet = serde_json::from_str(asset_data.as_ref())
+        .map_err(|err| format!("Could not parse {}: {}", ASSETS_JSON, err))?;
+    let base_image = loader::load_image(ASSETS_PNG)?;
+
+    let mut mod_sheets = Vec::new();
+    for dir in get_enabled_mod_dirs() {
+        if !dir.join("sprites.json").exists() {
+            continue;
+        }
+        match load_mod_sprites(&dir) {
+            Ok(sheet) => mod_sheets.push(sheet),
+            Err(err) => report_skipped(format!(
+                "Skipping sprites from {}: {}",
+                dir.display(),
+                err
+            )),
+        }
+    }
+
+    if mod_sheets.is_empty() {
+        return Ok((spritesheet, base_image));
+    }
+
+    let width = mod_sheets
+        .iter()
+        .fold(base_image.width(), |w, &(_, ref img)| w.max(img.width()));
+    let height = mod_sheets
+        .iter()
+        .fold(base_image.height(), |h, &(_, ref img)| h + img.height());
+
+    let mut merged = RgbaImage::new(width, height);
+    merged.copy_from(&base_image, 0, 0);
+    let mut y = base_image.height();
+    for (mod_sheet, img) in mod_sheets {
+        merged.copy_from(&img, 0, y);
+        for mut frame in mod_sheet.frames {
+            frame.frame.y += y as usize;
+            spritesheet
+                .frames
+                .retain(|existing| existing.filename != frame.filename);
+            spritesheet.frames.push(frame);
+        }
+        y += img.height();
+    }
+
+    spritesheet.meta.size.w = width as usize;
+    spritesheet.meta.size.h = height as usize;
+
+    Ok((spritesheet, merged))
+}
+
+pub fn load_spritesheet<F, R>(
+    factory: &mut F,
+) -> (Spritesheet, gfx::handle::ShaderResourceView<R, [f32; 4]>)
+where
+    F: gfx::Factory<R>,
+    R: gfx::Resources,
+{
+    let (spritesheet, image) = merge_spritesheet().unwrap();
+    let texture = loader::gfx_create_texture(&image, factory).unwrap();
+    (spritesheet, texture)
+}
+
+widget_ids! {
+    pub struct Ids {
+        mods_label,
+        close_button,
+        note_label,
+        empty_label,
+        skipped_label,
+        names[],
+        descriptions[],
+        toggles[],
+        up_buttons[],
+        down_buttons[],
+    }
+}
+
+pub fn create_ui(ui: &mut Ui, ids: &mut Ids, mod_list: &mut ModList) -> Option<UiAction> {
+    use conrod::{color, position::Relative, Colorable, Labelable, Positionable, Sizeable, Widget};
+
+    let count = mod_list.mods.len();
+    ids.names.resize(count, &mut ui.widget_id_generator());
+    ids.descriptions
+        .resize(count, &mut ui.widget_id_generator());
+    ids.toggles.resize(count, &mut ui.widget_id_generator());
+    ids.up_buttons
+        .resize(count, &mut ui.widget_id_generator());
+    ids.down_buttons
+        .resize(count, &mut ui.widget_id_generator());
+
+    let ui = &mut ui.set_widgets();
+
+    if widget::Button::new()
+        .top_right_with_margin_on(ui.window, 20.0)
+        .w_h(30.0, 30.0)
+        .label("X")
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .label_x(Relative::Scalar(1.0))
+        .label_y(Relative::Scalar(2.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.close_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::Close);
+    }
+
+    widget::Text::new("Mods")
+        .mid_top_with_margin_on(ui.window, 50.0)
+        .font_size(32)
+        .rgb(0.0, 1.0, 0.0)
+        .set(ids.mods_label, ui);
+
+    widget::Text::new("Later mods win. Restart the game to apply changes")
+        .down_from(ids.mods_label, 15.0)
+        .align_middle_x_of(ids.mods_label)
+        .font_size(18)
+        .rgb(0.8, 0.8, 0.8)
+        .set(ids.note_label, ui);
+
+    if count == 0 {
+        widget::Text::new(&format!(
+            "No mods installed. Add them to {}",
+            get_mods_dir().to_str().unwrap_or("the mods folder")
+        )).down_from(ids.note_label, 30.0)
+            .align_middle_x_of(ids.mods_label)
+            .font_size(18)
+            .rgb(0.8, 0.8, 0.8)
+            .set(ids.empty_label, ui);
+        set_skipped_label(ui, ids, ids.empty_label);
+        return None;
+    }
+
+    for i in 0..count {
+        let enabled = mod_list.mods[i].enabled;
+
+        let toggle = widget::Toggle::new(enabled)
+            .color(color::rgb(0.0, 1.0, 0.0))
+            .w_h(30.0, 30.0)
+            .label(if enabled { "X" } else { "" })
+            .label_x(Relative::Scalar(0.0))
+            .label_y(Relative::Scalar(1.0))
+            .label_color(color::rgb(0.0, 0.0, 0.0));
+        let toggle = if i == 0 {
+            toggle
+                .down_from(ids.note_label, 30.0)
+                .x_relative_to(ui.window, -320.0)
+        } else {
+            toggle.down_from(ids.toggles[i - 1], 30.0)
+        };
+        if let Some(state) = toggle.set(ids.toggles[i], ui).last() {
+            mod_list.set_enabled(i, state);
+        }
+
+        widget::Text::new(&mod_list.mods[i].info.name)
+            .right_from(ids.toggles[i], 15.0)
+            .font_size(20)
+            .rgb(0.0, 1.0, 0.0)
+            .set(ids.names[i], ui);
+
+        widget::Text::new(&mod_list.mods[i].info.description)
+            .down_from(ids.names[i], 2.0)
+            .font_size(14)
+            .rgb(0.8, 0.8, 0.8)
+            .set(ids.descriptions[i], ui);
+
+        if widget::Button::new()
+            .x_relative_to(ui.window, 280.0)
+            .y_relative_to(ids.toggles[i], 0.0)
+            .w_h(30.0, 30.0)
+            .label("^")
+            .label_color(color::rgb(0.0, 1.0, 0.0))
+            .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+            .set(ids.up_buttons[i], ui)
+            .was_clicked()
+        {
+            mod_list.move_up(i);
+        }
+
+        if widget::Button::new()
+            .right_from(ids.up_buttons[i], 5.0)
+            .w_h(30.0, 30.0)
+            .label("v")
+            .label_color(color::rgb(0.0, 1.0, 0.0))
+            .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+            .set(ids.down_buttons[i], ui)
+            .was_clicked()
+        {
+            mod_list.move_down(i);
+        }
+    }
+
+    set_skipped_label(ui, ids, ids.descriptions[count - 1]);
+
+    None
+}
+
+// lists what couldn't be loaded from the mods in use, so a broken mod doesn't fail silently
+fn set_skipped_label(ui: &mut UiCell, ids: &Ids, below: widget::Id) {
+    use conrod::{Colorable, Positionable, Sizeable, Widget};
+
+    let skipped = get_skipped();
+    if skipped.is_empty() {
+        return;
+    }
+
+    widget::Text::new(&format!("Problems loading mods:\n{}", skipped.join("\n")))
+        .down_from(below, 30.0)
+        .x_relative_to(ui.window, 0.0)
+        .w(640.0)
+        .font_size(14)
+        .rgb(1.0, 0.4, 0.4)
+        .set(ids.skipped_label, ui);
+}
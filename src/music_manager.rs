@@ -4,10 +4,12 @@ use rand::{self, Rng};
 use rodio::{Device, Sink, Source};
 
 use loader;
+use mods;
 
 pub struct MusicManager {
     tracks: HashMap<String, String>,
     current_track: String,
+    game_tracks: Vec<String>,
     thread_rng: rand::ThreadRng,
     sink: Sink,
 }
@@ -23,12 +25,19 @@ impl MusicManager {
             "resources/meloncholy.ogg".to_string(),
         );
 
+        let mut game_tracks = vec!["zen".to_string(), "meloncholy".to_string()];
+        for path in mods::get_music_tracks() {
+            game_tracks.push(path.clone());
+            tracks.insert(path.clone(), path);
+        }
+
         let mut sink = Sink::new(audio_device);
         sink.set_volume(volume);
 
         MusicManager {
             tracks,
             current_track: "title".to_string(),
+            game_tracks,
             thread_rng: rand::thread_rng(),
             sink,
         }
@@ -80,11 +89,8 @@ impl MusicManager {
     }
 
     pub fn play_random_game_track(&mut self) {
-        let track_num: usize = self.thread_rng.gen_range(0, 2);
-        if track_num == 0 {
-            self.queue_track("zen", false);
-        } else if track_num == 1 {
-            self.queue_track("meloncholy", false);
-        }
+        let track_num: usize = self.thread_rng.gen_range(0, self.game_tracks.len());
+        let track_name = self.game_tracks[track_num].clone();
+        self.queue_track(&track_name, false);
     }
 }
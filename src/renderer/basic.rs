@@ -2,16 +2,15 @@ extern crate cgmath;
 extern crate gfx;
 extern crate specs;
 
-use std::ops::Deref;
+use std::collections::HashMap;
 
-use cgmath::{Matrix4, SquareMatrix, Transform};
+use cgmath::{Matrix4, SquareMatrix};
 use components;
 use gfx::texture;
-use gfx::traits::FactoryExt;
-use gfx_glyph::{GlyphBrush, Layout, Section};
-use renderer::{ColorFormat, DepthFormat};
-use specs::World;
-use spritesheet::{Frame, Spritesheet};
+use gfx_glyph::{GlyphBrush, Layout, SectionText, VariedSection};
+use renderer::{apply_transform, get_quad, get_sprite_quad, BatchTexture, ColorFormat, DepthFormat,
+               Fonts, RenderBackend, SpriteBatch, QUAD_INDICES};
+use spritesheet::Spritesheet;
 
 gfx_defines!{
     vertex Vertex {
@@ -49,6 +48,16 @@ pub struct Basic<R: gfx::Resources> {
         gfx::handle::ShaderResourceView<R, [f32; 4]>,
         gfx::handle::Sampler<R>,
     ),
+    asset_sampler: gfx::handle::Sampler<R>,
+    texture_sampler: gfx::handle::Sampler<R>,
+    projection_cb: gfx::handle::Buffer<R, Projection>,
+    batch: SpriteBatch,
+    // grown to the largest frame so far, rather than created for every draw
+    vertex_buffer: gfx::handle::Buffer<R, Vertex>,
+    vertex_capacity: usize,
+    index_buffer: gfx::handle::Buffer<R, u32>,
+    index_capacity: usize,
+    pub fonts: Fonts,
 }
 
 impl<R> Basic<R>
@@ -83,6 +92,25 @@ where
 
         let dim = self::super::get_dimensions();
 
+        let vertex_capacity = 1024;
+        let vertex_buffer = factory
+            .create_buffer(
+                vertex_capacity,
+                gfx::buffer::Role::Vertex,
+                gfx::memory::Usage::Dynamic,
+                gfx::memory::Bind::empty(),
+            )
+            .unwrap();
+        let index_capacity = 1536;
+        let index_buffer = factory
+            .create_buffer(
+                index_capacity,
+                gfx::buffer::Role::Index,
+                gfx::memory::Usage::Dynamic,
+                gfx::memory::Bind::empty(),
+            )
+            .unwrap();
+
         Basic {
             pso: pso,
             projection: Projection {
@@ -92,258 +120,352 @@ where
             model: Matrix4::identity(),
             target,
             color_texture: (texture_view, factory.create_sampler(sinfo)),
+            asset_sampler: factory.create_sampler(texture::SamplerInfo::new(
+                texture::FilterMethod::Bilinear,
+                texture::WrapMode::Clamp,
+            )),
+            texture_sampler: factory.create_sampler(texture::SamplerInfo::new(
+                texture::FilterMethod::Scale,
+                texture::WrapMode::Clamp,
+            )),
+            projection_cb: factory.create_constant_buffer(1),
+            batch: SpriteBatch::new(),
+            vertex_buffer,
+            vertex_capacity,
+            index_buffer,
+            index_capacity,
+            fonts: Fonts::load(),
         }
     }
 
-    fn draw_verticies<F, C>(
+    // uploads the batched vertices and draws them, one call per texture run. Called before the
+    // queued text is drawn, so the text goes on top
+    pub fn flush<C, F>(
         &mut self,
-        data: Vec<Vertex>,
         encoder: &mut gfx::Encoder<R, C>,
         factory: &mut F,
-        tex: (
-            gfx::handle::ShaderResourceView<R, [f32; 4]>,
-            gfx::handle::Sampler<R>,
-        ),
         camera: &components::Camera,
+        asset_texture: &gfx::handle::ShaderResourceView<R, [f32; 4]>,
+        texture_map: &HashMap<String, gfx::handle::ShaderResourceView<R, [f32; 4]>>,
     ) where
         R: gfx::Resources,
         C: gfx::CommandBuffer<R>,
         F: gfx::Factory<R>,
     {
-        let index_data: Vec<u32> = vec![0, 1, 2, 2, 3, 0];
-        let (vbuf, slice) = factory.create_vertex_buffer_with_slice(&data, &index_data[..]);
+        if self.batch.is_empty() {
+            return;
+        }
 
-        let params = pipe::Data {
-            vbuf: vbuf,
-            projection_cb: factory.create_constant_buffer(1),
-            tex: tex,
-            out: self.target.color.clone(),
-            depth: self.target.depth.clone(),
-        };
+        let vertex_count = self.batch.vertices.len();
+        if self.vertex_capacity < vertex_count {
+            self.vertex_capacity = vertex_count.next_power_of_two();
+            self.vertex_buffer = factory
+                .create_buffer(
+                    self.vertex_capacity,
+                    gfx::buffer::Role::Vertex,
+                    gfx::memory::Usage::Dynamic,
+                    gfx::memory::Bind::empty(),
+                )
+                .unwrap();
+        }
+        let index_count = self.batch.indices.len();
+        if self.index_capacity < index_count {
+            self.index_capacity = index_count.next_power_of_two();
+            self.index_buffer = factory
+                .create_buffer(
+                    self.index_capacity,
+                    gfx::buffer::Role::Index,
+                    gfx::memory::Usage::Dynamic,
+                    gfx::memory::Bind::empty(),
+                )
+                .unwrap();
+        }
+        encoder
+            .update_buffer(&self.vertex_buffer, &self.batch.vertices, 0)
+            .unwrap();
+        encoder
+            .update_buffer(&self.index_buffer, &self.batch.indices, 0)
+            .unwrap();
 
+        // the vertices are already in world space
         self.projection.proj = (*camera).0.into();
+        self.projection.model = Matrix4::identity().into();
+        encoder.update_constant_buffer(&self.projection_cb, &self.projection);
+
+        for batch in &self.batch.batches {
+            let tex = match batch.texture {
+                BatchTexture::Color => self.color_texture.clone(),
+                BatchTexture::Asset => (asset_texture.clone(), self.asset_sampler.clone()),
+                BatchTexture::Named(ref name) => (
+                    texture_map.get(name).unwrap().clone(),
+                    self.texture_sampler.clone(),
+                ),
+            };
+            let params = pipe::Data {
+                vbuf: self.vertex_buffer.clone(),
+                projection_cb: self.projection_cb.clone(),
+                tex: tex,
+                out: self.target.color.clone(),
+                depth: self.target.depth.clone(),
+            };
+            let slice = gfx::Slice {
+                start: batch.start,
+                end: batch.end,
+                base_vertex: 0,
+                instances: None,
+                buffer: gfx::IndexBuffer::Index32(self.index_buffer.clone()),
+            };
+            encoder.draw(&slice, &self.pso, &params);
+        }
 
-        self.projection.model = self.model.into();
-
-        encoder.update_constant_buffer(&params.projection_cb, &self.projection);
-        encoder.draw(&slice, &self.pso, &params);
+        self.batch.clear();
     }
 
     pub fn reset_transform(&mut self) {
         self.model = Matrix4::identity().into();
     }
 
-    pub fn render_single_texture<C, F>(
+    pub fn render_single_texture(
         &mut self,
-        encoder: &mut gfx::Encoder<R, C>,
-        world: &World,
-        factory: &mut F,
         transform: &components::Transform,
-        texture: &gfx::handle::ShaderResourceView<R, [f32; 4]>,
+        texture_name: &str,
         color: &components::Color,
-    ) where
-        R: gfx::Resources,
-        C: gfx::CommandBuffer<R>,
-        F: gfx::Factory<R>,
-    {
-        let camera_res = world.read_resource::<components::Camera>();
-        let camera = camera_res.deref();
+    ) {
         let w = transform.size.x as f32;
         let h = transform.size.y as f32;
 
-        let tx = 0.0;
-        let ty = 0.0;
-        let tx2 = 1.0;
-        let ty2 = 1.0;
-
-        let tex = (
-            texture.clone(),
-            factory.create_sampler(texture::SamplerInfo::new(
-                texture::FilterMethod::Scale,
-                texture::WrapMode::Clamp,
-            )),
+        self.batch.add_quad(
+            BatchTexture::Named(texture_name.to_string()),
+            &self.model,
+            color.0,
+            w,
+            h,
+            [0.0, 0.0, 1.0, 1.0],
         );
-
-        let data: Vec<Vertex> = get_quad(color.0, w, h, tx, ty, tx2, ty2);
-
-        self.draw_verticies(data, encoder, factory, tex, &camera);
     }
 
-    pub fn render<C, F>(
+    pub fn render(
         &mut self,
-        encoder: &mut gfx::Encoder<R, C>,
-        world: &World,
-        factory: &mut F,
         transform: &components::Transform,
-        frame_name: Option<&String>,
+        frame_name: Option<&str>,
         spritesheet: &Spritesheet,
         color: Option<&components::Color>,
-        texture: Option<&gfx::handle::ShaderResourceView<R, [f32; 4]>>,
-    ) where
-        R: gfx::Resources,
-        C: gfx::CommandBuffer<R>,
-        F: gfx::Factory<R>,
-    {
-        let camera_res = world.read_resource::<components::Camera>();
-        let camera = camera_res.deref();
+        texture: BatchTexture,
+    ) {
         let w = transform.size.x as f32;
         let h = transform.size.y as f32;
 
-        let mut tx = 0.0;
-        let mut ty = 0.0;
-        let mut tx2 = 1.0;
-        let mut ty2 = 1.0;
-
-        if let Some(frame_name) = frame_name {
-            let region = spritesheet
-                .frames
-                .iter()
-                .filter(|frame| frame.filename == *frame_name)
-                .collect::<Vec<&Frame>>()[0];
-            let sw = spritesheet.meta.size.w as f32;
-            let sh = spritesheet.meta.size.h as f32;
-            tx = region.frame.x as f32 / sw;
-            ty = region.frame.y as f32 / sh;
-            tx2 = (region.frame.x as f32 + region.frame.w as f32) / sw;
-            ty2 = (region.frame.y as f32 + region.frame.h as f32) / sh;
-        }
-
-        let tex: (
-            gfx::handle::ShaderResourceView<R, [f32; 4]>,
-            gfx::handle::Sampler<R>,
-        ) = if let Some(texture) = texture {
-            (
-                texture.clone(),
-                factory.create_sampler(texture::SamplerInfo::new(
-                    texture::FilterMethod::Bilinear,
-                    texture::WrapMode::Clamp,
-                )),
-            )
-        } else {
-            self.color_texture.clone()
-        };
-
         let color = if let Some(color) = color {
             color.0
         } else {
             [1.0; 4]
         };
 
-        let data: Vec<Vertex> = get_quad(color, w, h, tx, ty, tx2, ty2);
+        let quad = if let Some(frame_name) = frame_name {
+            get_sprite_quad(spritesheet, frame_name, color, w, h)
+        } else {
+            get_quad(color, w, h, [0.0, 0.0, 1.0, 1.0])
+        };
 
-        self.draw_verticies(data, encoder, factory, tex, &camera);
+        self.batch.add_mesh(texture, &self.model, &quad, &QUAD_INDICES);
     }
 
-    pub fn render_shape<C, F>(
-        &mut self,
-        encoder: &mut gfx::Encoder<R, C>,
-        world: &World,
-        factory: &mut F,
-        shape: &components::Shape,
-    ) where
-        R: gfx::Resources,
-        C: gfx::CommandBuffer<R>,
-        F: gfx::Factory<R>,
-    {
-        use std::ops::Deref;
-
-        let camera_res = world.read_resource::<components::Camera>();
-        let camera = camera_res.deref();
-
+    pub fn render_shape(&mut self, shape: &components::Shape) {
         let buffers = &shape.buffers;
-        let (vbuf, slice) =
-            factory.create_vertex_buffer_with_slice(&buffers.vertices[..], &buffers.indices[..]);
-
-        let params = pipe::Data {
-            vbuf: vbuf,
-            projection_cb: factory.create_constant_buffer(1),
-            tex: self.color_texture.clone(),
-            out: self.target.color.clone(),
-            depth: self.target.depth.clone(),
-        };
-
-        self.projection.proj = (*camera).0.into();
-        self.projection.model = self.model.into();
-
-        encoder.update_constant_buffer(&params.projection_cb, &self.projection);
-        encoder.draw(&slice, &self.pso, &params);
+        self.batch.add_mesh(
+            BatchTexture::Color,
+            &self.model,
+            &buffers.vertices[..],
+            &buffers.indices[..],
+        );
     }
 
-    pub fn render_text<C, F>(
+    // queues the text in the glyph brush, it is drawn by draw_text
+    pub fn render_text<F>(
         &mut self,
-        encoder: &mut gfx::Encoder<R, C>,
         text: &components::Text,
         transform: &components::Transform,
         color: &components::Color,
         glyph_brush: &mut GlyphBrush<R, F>,
         hidpi_factor: f32,
-        scale_from_base_res: &(f32, f32),
+        viewport: &components::Viewport,
     ) where
         R: gfx::Resources,
-        C: gfx::CommandBuffer<R>,
         F: gfx::Factory<R>,
     {
         let absolute_pos = transform.get_absolute_pos();
+        let absolute_scale = transform.get_absolute_scale();
+        let (x, y) = viewport.to_window(absolute_pos.x, absolute_pos.y);
+        let pixel_scale = hidpi_factor * viewport.scale;
         let mut scale = text.scale.clone();
-        scale.x *= hidpi_factor * scale_from_base_res.0;
-        scale.y *= hidpi_factor * scale_from_base_res.1;
-        let section = Section {
-            text: text.text.as_ref(),
-            scale,
+        scale.x *= pixel_scale * absolute_scale.x;
+        scale.y *= pixel_scale * absolute_scale.y;
+        let section_text = self.fonts
+            .split_runs(&text.text)
+            .into_iter()
+            .map(|(run, font_id)| SectionText {
+                text: run,
+                scale,
+                color: color.0,
+                font_id,
+            })
+            .collect();
+        let section = VariedSection {
+            text: section_text,
             bounds: (
-                text.size.x as f32 * hidpi_factor,
-                text.size.y as f32 * hidpi_factor,
-            ),
-            screen_position: (
-                absolute_pos.x * hidpi_factor * scale_from_base_res.0,
-                absolute_pos.y * hidpi_factor * scale_from_base_res.1,
+                text.size.x as f32 * pixel_scale * absolute_scale.x,
+                text.size.y as f32 * pixel_scale * absolute_scale.y,
             ),
-            color: color.0,
+            screen_position: (x * hidpi_factor, y * hidpi_factor),
             z: 0.0,
             layout: Layout::default().h_align(text.align),
-            ..Section::default()
+            ..VariedSection::default()
         };
 
         glyph_brush.queue(section);
+    }
+
+    pub fn draw_text<C, F>(
+        &mut self,
+        encoder: &mut gfx::Encoder<R, C>,
+        glyph_brush: &mut GlyphBrush<R, F>,
+    ) where
+        R: gfx::Resources,
+        C: gfx::CommandBuffer<R>,
+        F: gfx::Factory<R>,
+    {
         glyph_brush
             .draw_queued(encoder, &self.target.color, &self.target.depth)
             .unwrap();
     }
 
     pub fn transform(&mut self, transform: &components::Transform, undo: bool) {
-        let mut transform_mat = Matrix4::from_translation(*transform.get_pos());
-        let mut scale = Matrix4::from_nonuniform_scale(transform.scale.x, transform.scale.y, 1.0);
-        if undo {
-            transform_mat = transform_mat.inverse_transform().unwrap();
-            scale = scale.inverse_transform().unwrap();
-            self.model = self.model.concat(&scale).concat(&transform_mat);
-        } else {
-            self.model = self.model.concat(&transform_mat).concat(&scale);
+        apply_transform(&mut self.model, transform, undo);
+    }
+}
+
+// The gfx renderer for one frame, along with everything it draws from
+pub struct GfxFrame<'a, 'font: 'a, R: 'a + gfx::Resources, C: 'a, F: 'a + gfx::Factory<R>> {
+    pub basic: &'a mut Basic<R>,
+    pub encoder: &'a mut gfx::Encoder<R, C>,
+    pub factory: &'a mut F,
+    pub glyph_brush: &'a mut GlyphBrush<'font, R, F>,
+    pub camera: &'a components::Camera,
+    pub spritesheet: &'a Spritesheet,
+    pub asset_texture: &'a gfx::handle::ShaderResourceView<R, [f32; 4]>,
+    pub texture_map: &'a HashMap<String, gfx::handle::ShaderResourceView<R, [f32; 4]>>,
+    pub hidpi_factor: f32,
+    pub viewport: &'a components::Viewport,
+    // the absolute z of the node being drawn
+    pub z: f32,
+    // the highest absolute z of the text queued since the last flush
+    pub text_z: Option<f32>,
+}
+
+impl<'a, 'font, R, C, F> GfxFrame<'a, 'font, R, C, F>
+where
+    R: gfx::Resources,
+    C: gfx::CommandBuffer<R>,
+    F: gfx::Factory<R>,
+{
+    /**
+     * Text is queued rather than drawn straight away, so it doesn't split the batch at every label.
+     * Queued text goes on top of everything batched at its z or below, and only once something
+     * is drawn above it are both flushed, keeping it under that
+     */
+    fn before_batched(&mut self) {
+        if let Some(text_z) = self.text_z {
+            if self.z > text_z {
+                self.finish();
+            }
         }
     }
 }
 
-fn get_quad(color: [f32; 4], w: f32, h: f32, tx: f32, ty: f32, tx2: f32, ty2: f32) -> Vec<Vertex> {
-    vec![
-        Vertex {
-            pos: [0.0, 0.0, 0.0],
-            uv: [tx, ty],
-            color: color,
-        },
-        Vertex {
-            pos: [w, 0.0, 0.0],
-            uv: [tx2, ty],
-            color: color,
-        },
-        Vertex {
-            pos: [w, h, 0.0],
-            uv: [tx2, ty2],
-            color: color,
-        },
-        Vertex {
-            pos: [0.0, h, 0.0],
-            uv: [tx, ty2],
-            color: color,
-        },
-    ]
+impl<'a, 'font, R, C, F> RenderBackend for GfxFrame<'a, 'font, R, C, F>
+where
+    R: gfx::Resources,
+    C: gfx::CommandBuffer<R>,
+    F: gfx::Factory<R>,
+{
+    fn transform(&mut self, transform: &components::Transform, undo: bool) {
+        if !undo {
+            self.z = transform.get_absolute_pos().z;
+        }
+        self.basic.transform(transform, undo);
+    }
+
+    fn render_sprite(
+        &mut self,
+        transform: &components::Transform,
+        frame_name: &str,
+        color: Option<&components::Color>,
+    ) {
+        self.before_batched();
+        self.basic.render(
+            transform,
+            Some(frame_name),
+            self.spritesheet,
+            color,
+            BatchTexture::Asset,
+        );
+    }
+
+    fn render_texture(
+        &mut self,
+        transform: &components::Transform,
+        texture_name: &str,
+        color: &components::Color,
+    ) {
+        self.before_batched();
+        self.basic
+            .render_single_texture(transform, texture_name, color);
+    }
+
+    fn render_rect(&mut self, transform: &components::Transform, color: &components::Color) {
+        self.before_batched();
+        self.basic.render(
+            transform,
+            None,
+            self.spritesheet,
+            Some(color),
+            BatchTexture::Color,
+        );
+    }
+
+    fn render_shape(&mut self, shape: &components::Shape) {
+        self.before_batched();
+        self.basic.render_shape(shape);
+    }
+
+    fn render_text(
+        &mut self,
+        text: &components::Text,
+        transform: &components::Transform,
+        color: &components::Color,
+    ) {
+        let z = transform.get_absolute_pos().z;
+        self.text_z = Some(self.text_z.map_or(z, |text_z| text_z.max(z)));
+        self.basic.render_text(
+            text,
+            transform,
+            color,
+            self.glyph_brush,
+            self.hidpi_factor,
+            self.viewport,
+        );
+    }
+
+    fn finish(&mut self) {
+        self.basic.flush(
+            self.encoder,
+            self.factory,
+            self.camera,
+            self.asset_texture,
+            self.texture_map,
+        );
+        if self.text_z.take().is_some() {
+            self.basic.draw_text(self.encoder, self.glyph_brush);
+        }
+    }
 }
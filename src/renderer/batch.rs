@@ -0,0 +1,370 @@
+use cgmath::{Matrix4, Transform, Vector4};
+use components;
+use renderer::Vertex;
+use spritesheet::{Frame, Spritesheet};
+
+// The texture a batch samples from, only resolved to a gpu handle when the batch is drawn
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchTexture {
+    // plain white, for rects and shapes that only use their vertex color
+    Color,
+    // the spritesheet
+    Asset,
+    // a standalone image, by its name in the texture map
+    Named(String),
+}
+
+// A run of indices drawn with one texture
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    pub texture: BatchTexture,
+    pub start: u32,
+    pub end: u32,
+}
+
+// Collects the quads and meshes of a frame in draw order, with their vertices already moved
+// into world space, so consecutive ones sharing a texture go to the gpu in a single draw call
+#[derive(Default)]
+pub struct SpriteBatch {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub batches: Vec<Batch>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        SpriteBatch {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.batches.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    // uv is [left, top, right, bottom]
+    pub fn add_quad(
+        &mut self,
+        texture: BatchTexture,
+        model: &Matrix4<f32>,
+        color: [f32; 4],
+        w: f32,
+        h: f32,
+        uv: [f32; 4],
+    ) {
+        let quad = get_quad(color, w, h, uv);
+        self.add_mesh(texture, model, &quad, &QUAD_INDICES);
+    }
+
+    pub fn add_mesh(
+        &mut self,
+        texture: BatchTexture,
+        model: &Matrix4<f32>,
+        vertices: &[Vertex],
+        indices: &[u16],
+    ) {
+        let base = self.vertices.len() as u32;
+        self.vertices
+            .extend(vertices.iter().map(|vertex| transform_vertex(model, vertex)));
+        self.indices
+            .extend(indices.iter().map(|index| base + *index as u32));
+
+        let end = self.indices.len() as u32;
+        if let Some(batch) = self.batches.last_mut() {
+            if batch.texture == texture {
+                batch.end = end;
+                return;
+            }
+        }
+        self.batches.push(Batch {
+            texture,
+            start: end - indices.len() as u32,
+            end,
+        });
+    }
+}
+
+// the two triangles of a quad from get_quad or get_sprite_quad
+pub const QUAD_INDICES: [u16; 6] = [0, 1, 2, 2, 3, 0];
+
+pub fn get_quad(color: [f32; 4], w: f32, h: f32, uv: [f32; 4]) -> [Vertex; 4] {
+    [
+        Vertex {
+            pos: [0.0, 0.0, 0.0],
+            uv: [uv[0], uv[1]],
+            color: color,
+        },
+        Vertex {
+            pos: [w, 0.0, 0.0],
+            uv: [uv[2], uv[1]],
+            color: color,
+        },
+        Vertex {
+            pos: [w, h, 0.0],
+            uv: [uv[2], uv[3]],
+            color: color,
+        },
+        Vertex {
+            pos: [0.0, h, 0.0],
+            uv: [uv[0], uv[3]],
+            color: color,
+        },
+    ]
+}
+
+pub fn transform_vertex(model: &Matrix4<f32>, vertex: &Vertex) -> Vertex {
+    let pos = model * Vector4::new(vertex.pos[0], vertex.pos[1], vertex.pos[2], 1.0);
+    Vertex {
+        pos: [pos.x, pos.y, pos.z],
+        uv: vertex.uv,
+        color: vertex.color,
+    }
+}
+
+/**
+ * The quad drawing a frame of the spritesheet at w by h, the size of the untrimmed sprite.
+ * Trimmed frames only cover the part of it that was kept, at the offset it was cut from.
+ * Rotated frames are stored turned 90 degrees clockwise in the sheet, taking up frame.h by
+ * frame.w there, so each corner samples the corner a quarter turn on from it
+ */
+pub fn get_sprite_quad(
+    spritesheet: &Spritesheet,
+    frame_name: &str,
+    color: [f32; 4],
+    w: f32,
+    h: f32,
+) -> [Vertex; 4] {
+    let region = spritesheet
+        .frames
+        .iter()
+        .filter(|frame| frame.filename == frame_name)
+        .collect::<Vec<&Frame>>()[0];
+
+    let scale_x = w / region.source_size.w as f32;
+    let scale_y = h / region.source_size.h as f32;
+    let trim = &region.sprite_source_size;
+    let left = trim.x as f32 * scale_x;
+    let top = trim.y as f32 * scale_y;
+    let right = left + trim.w as f32 * scale_x;
+    let bottom = top + trim.h as f32 * scale_y;
+
+    let (frame_w, frame_h) = if region.rotated {
+        (region.frame.h as f32, region.frame.w as f32)
+    } else {
+        (region.frame.w as f32, region.frame.h as f32)
+    };
+    let sw = spritesheet.meta.size.w as f32;
+    let sh = spritesheet.meta.size.h as f32;
+    let uv_left = region.frame.x as f32 / sw;
+    let uv_top = region.frame.y as f32 / sh;
+    let uv_right = (region.frame.x as f32 + frame_w) / sw;
+    let uv_bottom = (region.frame.y as f32 + frame_h) / sh;
+
+    // top left, top right, bottom right, bottom left
+    let uvs = if region.rotated {
+        [
+            [uv_right, uv_top],
+            [uv_right, uv_bottom],
+            [uv_left, uv_bottom],
+            [uv_left, uv_top],
+        ]
+    } else {
+        [
+            [uv_left, uv_top],
+            [uv_right, uv_top],
+            [uv_right, uv_bottom],
+            [uv_left, uv_bottom],
+        ]
+    };
+    [
+        Vertex {
+            pos: [left, top, 0.0],
+            uv: uvs[0],
+            color: color,
+        },
+        Vertex {
+            pos: [right, top, 0.0],
+            uv: uvs[1],
+            color: color,
+        },
+        Vertex {
+            pos: [right, bottom, 0.0],
+            uv: uvs[2],
+            color: color,
+        },
+        Vertex {
+            pos: [left, bottom, 0.0],
+            uv: uvs[3],
+            color: color,
+        },
+    ]
+}
+
+// moves the model matrix into a node, or back out of it once its children are drawn
+pub fn apply_transform(model: &mut Matrix4<f32>, transform: &components::Transform, undo: bool) {
+    let mut transform_mat = Matrix4::from_translation(*transform.get_pos());
+    let mut scale = Matrix4::from_nonuniform_scale(transform.scale.x, transform.scale.y, 1.0);
+    if undo {
+        transform_mat = transform_mat.inverse_transform().unwrap();
+        scale = scale.inverse_transform().unwrap();
+        *model = model.concat(&scale).concat(&transform_mat);
+    } else {
+        *model = model.concat(&transform_mat).concat(&scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector3;
+    use spritesheet::{FrameData, Meta, Size};
+
+    const WHITE: [f32; 4] = [1.0; 4];
+
+    // a 100 by 50 sheet holding a single 16 by 12 frame at 10, 20
+    fn get_spritesheet(rotated: bool, trim: FrameData, source_size: Size) -> Spritesheet {
+        Spritesheet {
+            frames: vec![Frame {
+                filename: "sprite.png".to_string(),
+                frame: FrameData {
+                    x: 10,
+                    y: 20,
+                    w: 16,
+                    h: 12,
+                },
+                rotated,
+                trimmed: trim.w != source_size.w || trim.h != source_size.h,
+                sprite_source_size: trim,
+                source_size,
+            }],
+            meta: Meta {
+                size: Size { w: 100, h: 50 },
+            },
+        }
+    }
+
+    #[test]
+    fn quad_corners_and_uvs() {
+        let quad = get_quad(WHITE, 32.0, 16.0, [0.25, 0.5, 0.75, 1.0]);
+        let corners: Vec<[f32; 3]> = quad.iter().map(|vertex| vertex.pos).collect();
+        let uvs: Vec<[f32; 2]> = quad.iter().map(|vertex| vertex.uv).collect();
+
+        assert_eq!(
+            corners,
+            vec![
+                [0.0, 0.0, 0.0],
+                [32.0, 0.0, 0.0],
+                [32.0, 16.0, 0.0],
+                [0.0, 16.0, 0.0],
+            ]
+        );
+        assert_eq!(uvs, vec![[0.25, 0.5], [0.75, 0.5], [0.75, 1.0], [0.25, 1.0]]);
+        assert!(quad.iter().all(|vertex| vertex.color == WHITE));
+    }
+
+    #[test]
+    fn transform_vertex_translates_and_scales() {
+        let model = Matrix4::from_translation(Vector3::new(10.0, 20.0, 1.0))
+            * Matrix4::from_nonuniform_scale(2.0, 3.0, 1.0);
+        let vertex = Vertex {
+            pos: [4.0, 5.0, 0.0],
+            uv: [0.5, 0.5],
+            color: WHITE,
+        };
+
+        let moved = transform_vertex(&model, &vertex);
+        assert_eq!(moved.pos, [18.0, 35.0, 1.0]);
+        assert_eq!(moved.uv, vertex.uv);
+        assert_eq!(moved.color, vertex.color);
+    }
+
+    #[test]
+    fn same_texture_quads_share_a_batch() {
+        let mut batch = SpriteBatch::new();
+        let model = Matrix4::from_scale(1.0);
+        batch.add_quad(BatchTexture::Asset, &model, WHITE, 8.0, 8.0, [0.0, 0.0, 1.0, 1.0]);
+        batch.add_quad(BatchTexture::Asset, &model, WHITE, 8.0, 8.0, [0.0, 0.0, 1.0, 1.0]);
+        batch.add_quad(BatchTexture::Color, &model, WHITE, 8.0, 8.0, [0.0, 0.0, 1.0, 1.0]);
+
+        assert_eq!(batch.vertices.len(), 12);
+        assert_eq!(
+            batch.indices,
+            vec![0, 1, 2, 2, 3, 0, 4, 5, 6, 6, 7, 4, 8, 9, 10, 10, 11, 8]
+        );
+        assert_eq!(
+            batch.batches,
+            vec![
+                Batch {
+                    texture: BatchTexture::Asset,
+                    start: 0,
+                    end: 12,
+                },
+                Batch {
+                    texture: BatchTexture::Color,
+                    start: 12,
+                    end: 18,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn trimmed_sprite_quad_is_offset_and_scaled() {
+        let trim = FrameData {
+            x: 4,
+            y: 8,
+            w: 16,
+            h: 12,
+        };
+        let spritesheet = get_spritesheet(false, trim, Size { w: 32, h: 32 });
+        // drawn at twice the source size
+        let quad = get_sprite_quad(&spritesheet, "sprite.png", WHITE, 64.0, 64.0);
+        let corners: Vec<[f32; 3]> = quad.iter().map(|vertex| vertex.pos).collect();
+        let uvs: Vec<[f32; 2]> = quad.iter().map(|vertex| vertex.uv).collect();
+
+        assert_eq!(
+            corners,
+            vec![
+                [8.0, 16.0, 0.0],
+                [40.0, 16.0, 0.0],
+                [40.0, 40.0, 0.0],
+                [8.0, 40.0, 0.0],
+            ]
+        );
+        assert_eq!(uvs, vec![[0.1, 0.4], [0.26, 0.4], [0.26, 0.64], [0.1, 0.64]]);
+    }
+
+    #[test]
+    fn rotated_sprite_quad_turns_uvs() {
+        let trim = FrameData {
+            x: 0,
+            y: 0,
+            w: 16,
+            h: 12,
+        };
+        let spritesheet = get_spritesheet(true, trim, Size { w: 16, h: 12 });
+        let quad = get_sprite_quad(&spritesheet, "sprite.png", WHITE, 16.0, 12.0);
+        let corners: Vec<[f32; 3]> = quad.iter().map(|vertex| vertex.pos).collect();
+        let uvs: Vec<[f32; 2]> = quad.iter().map(|vertex| vertex.uv).collect();
+
+        assert_eq!(
+            corners,
+            vec![
+                [0.0, 0.0, 0.0],
+                [16.0, 0.0, 0.0],
+                [16.0, 12.0, 0.0],
+                [0.0, 12.0, 0.0],
+            ]
+        );
+        // the frame takes up 12 by 16 in the sheet, each corner sampling the one a quarter turn on
+        assert_eq!(uvs, vec![[0.22, 0.4], [0.22, 0.72], [0.1, 0.72], [0.1, 0.4]]);
+    }
+}
@@ -0,0 +1,98 @@
+use gfx_glyph::FontId;
+use loader;
+use localization;
+use rusttype::Font;
+use std::fs::File;
+use std::io::Read;
+
+const MAIN_FONT: &'static [u8] = include_bytes!("../../resources/MunroSmall.ttf");
+const MAIN_FONT_ID: FontId = FontId(0);
+const FALLBACK_FONT_ID: FontId = FontId(1);
+
+/**
+ * MunroSmall only has glyphs for latin script. When a fallback font is installed,
+ * runs of text it cannot draw are drawn with the fallback instead.
+ */
+pub struct Fonts {
+    main_font: Font<'static>,
+    fallback_font_data: Option<Vec<u8>>,
+}
+
+impl Fonts {
+    pub fn load() -> Self {
+        let fallback_path = loader::get_resource_path(localization::FALLBACK_FONT);
+        let fallback_font_data = File::open(&fallback_path)
+            .ok()
+            .and_then(|mut file| {
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).ok().map(|_| data)
+            })
+            .and_then(|data| match Font::from_bytes(data.clone()) {
+                Ok(_) => Some(data),
+                Err(err) => {
+                    eprintln!("Could not load {}: {}", localization::FALLBACK_FONT, err);
+                    None
+                }
+            });
+
+        Fonts {
+            main_font: Font::from_bytes(MAIN_FONT).unwrap(),
+            fallback_font_data,
+        }
+    }
+
+    // font data for the glyph brush, in FontId order
+    pub fn get_font_data(&self) -> Vec<Vec<u8>> {
+        let mut font_data = vec![MAIN_FONT.to_vec()];
+        if let Some(ref fallback_font_data) = self.fallback_font_data {
+            font_data.push(fallback_font_data.clone());
+        }
+        font_data
+    }
+
+    // the same fonts, in the same order, for drawing glyphs without the glyph brush
+    pub fn create_fonts(&self) -> Vec<Font<'static>> {
+        self.get_font_data()
+            .into_iter()
+            .filter_map(|data| Font::from_bytes(data).ok())
+            .collect()
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        // missing characters give the font's notdef glyph
+        self.main_font.glyph(c).id().0 != 0
+    }
+
+    pub fn split_runs<'a>(&self, text: &'a str) -> Vec<(&'a str, FontId)> {
+        if self.fallback_font_data.is_none() {
+            return vec![(text, MAIN_FONT_ID)];
+        }
+
+        let mut runs = Vec::new();
+        let mut start = 0;
+        let mut current = MAIN_FONT_ID;
+        for (i, c) in text.char_indices() {
+            // spaces and line breaks stay with the run they are in
+            let font_id = if c.is_whitespace() {
+                current
+            } else if self.has_glyph(c) {
+                MAIN_FONT_ID
+            } else {
+                FALLBACK_FONT_ID
+            };
+
+            if font_id != current {
+                if i > start {
+                    runs.push((&text[start..i], current));
+                }
+                start = i;
+                current = font_id;
+            }
+        }
+
+        if start < text.len() {
+            runs.push((&text[start..], current));
+        }
+        runs
+    }
+}
@@ -1,19 +1,33 @@
 use cgmath::{ortho, Matrix4};
 use gfx;
 mod basic;
+mod batch;
+mod fonts;
+mod scene;
+mod software;
 
 pub type ColorFormat = gfx::format::Srgba8;
 pub type DepthFormat = gfx::format::Depth;
 
 pub use self::basic::*;
+pub use self::batch::*;
+pub use self::fonts::Fonts;
+pub use self::scene::*;
+pub use self::software::SoftwareRenderer;
 
 pub fn get_ortho(w: f32, h: f32) -> Matrix4<f32> {
-    let mut m = ortho(0.0, w, h, 0.0, 100.0, 0.0);
+    get_ortho_bounds(0.0, 0.0, w, h)
+}
+
+// the projection for a view whose top left corner is at x, y
+pub fn get_ortho_bounds(x: f32, y: f32, w: f32, h: f32) -> Matrix4<f32> {
+    let mut m = ortho(x, x + w, y + h, y, 100.0, 0.0);
 
     m.z.z *= -1.0;
     m
 }
 
+// the base resolution screens are laid out for, anchored to the middle of larger windows
 pub fn get_dimensions() -> [f32; 2] {
     [960.0, 640.0]
 }
@@ -0,0 +1,142 @@
+use components::{AnimationSheet, Color, EntityLookup, Node, Rect, Shape, Sprite, Text, Texture,
+                 Transform};
+use specs::{Entity, ReadStorage, World, WriteStorage};
+
+// What the node tree is drawn through. The window uses the gfx renderer, headless snapshots use
+// the software one
+pub trait RenderBackend {
+    // moves into a node, or back out of it once its children are drawn
+    fn transform(&mut self, transform: &Transform, undo: bool);
+    fn render_sprite(&mut self, transform: &Transform, frame_name: &str, color: Option<&Color>);
+    fn render_texture(&mut self, transform: &Transform, texture_name: &str, color: &Color);
+    fn render_rect(&mut self, transform: &Transform, color: &Color);
+    fn render_shape(&mut self, shape: &Shape);
+    fn render_text(&mut self, text: &Text, transform: &Transform, color: &Color);
+    // draws anything still queued
+    fn finish(&mut self);
+}
+
+struct SceneStorages<'a, 'b: 'a> {
+    sprites: &'a ReadStorage<'b, Sprite>,
+    transforms: &'a mut WriteStorage<'b, Transform>,
+    animation_sheets: &'a ReadStorage<'b, AnimationSheet>,
+    colors: &'a ReadStorage<'b, Color>,
+    texts: &'a ReadStorage<'b, Text>,
+    rects: &'a ReadStorage<'b, Rect>,
+    shapes: &'a ReadStorage<'b, Shape>,
+    nodes: &'a mut WriteStorage<'b, Node>,
+    textures: &'a ReadStorage<'b, Texture>,
+}
+
+// Draws every visible entity under the root node, children in the order of their z
+pub fn render_scene<B: RenderBackend>(backend: &mut B, world: &World) {
+    let root_node = {
+        let lookup = world.read_resource::<EntityLookup>();
+        lookup.entities.get("root").unwrap().clone()
+    };
+
+    let sprites = world.read_storage::<Sprite>();
+    let mut transforms = world.write_storage::<Transform>();
+    let animation_sheets = world.read_storage::<AnimationSheet>();
+    let colors = world.read_storage::<Color>();
+    let texts = world.read_storage::<Text>();
+    let rects = world.read_storage::<Rect>();
+    let shapes = world.read_storage::<Shape>();
+    let mut nodes = world.write_storage::<Node>();
+    let textures = world.read_storage::<Texture>();
+
+    let mut storages = SceneStorages {
+        sprites: &sprites,
+        transforms: &mut transforms,
+        animation_sheets: &animation_sheets,
+        colors: &colors,
+        texts: &texts,
+        rects: &rects,
+        shapes: &shapes,
+        nodes: &mut nodes,
+        textures: &textures,
+    };
+
+    let mut entities = Vec::new();
+    {
+        let node = storages.nodes.get_mut(root_node).unwrap();
+        node.sort_children(world, storages.transforms);
+        entities.append(&mut node.entities.iter().cloned().collect());
+    }
+
+    for entity in &entities {
+        render_node(backend, *entity, world, &mut storages);
+    }
+
+    backend.finish();
+}
+
+fn render_entity<B: RenderBackend>(backend: &mut B, entity: Entity, storages: &SceneStorages) {
+    let transform = match storages.transforms.get(entity) {
+        Some(transform) if transform.visible => transform,
+        _ => return,
+    };
+
+    if let Some(sprite) = storages.sprites.get(entity) {
+        backend.render_sprite(transform, &sprite.frame_name, storages.colors.get(entity));
+    }
+
+    if let Some(texture) = storages.textures.get(entity) {
+        backend.render_texture(
+            transform,
+            &texture.name,
+            storages.colors.get(entity).unwrap(),
+        );
+    }
+
+    if let Some(animation) = storages.animation_sheets.get(entity) {
+        backend.render_sprite(
+            transform,
+            animation.get_current_frame(),
+            storages.colors.get(entity),
+        );
+    }
+
+    if let (Some(color), Some(_)) = (storages.colors.get(entity), storages.rects.get(entity)) {
+        backend.render_rect(transform, color);
+    }
+
+    if let (Some(color), Some(text)) = (storages.colors.get(entity), storages.texts.get(entity)) {
+        if text.text != "" && text.visible {
+            backend.render_text(text, transform, color);
+        }
+    }
+
+    if let Some(shape) = storages.shapes.get(entity) {
+        backend.render_shape(shape);
+    }
+}
+
+fn render_node<B: RenderBackend>(
+    backend: &mut B,
+    entity: Entity,
+    world: &World,
+    storages: &mut SceneStorages,
+) {
+    if let Some(transform) = storages.transforms.get(entity) {
+        if !transform.visible {
+            return;
+        }
+        backend.transform(transform, false);
+    }
+    render_entity(backend, entity, storages);
+
+    let mut entities = Vec::new();
+    if let Some(node) = storages.nodes.get_mut(entity) {
+        node.sort_children(world, storages.transforms);
+        entities.append(&mut node.entities.iter().cloned().collect());
+    }
+
+    for entity in &entities {
+        render_node(backend, *entity, world, storages);
+    }
+
+    if let Some(transform) = storages.transforms.get(entity) {
+        backend.transform(transform, true);
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use cgmath::{Matrix4, Vector3};
+use components;
+use gfx_glyph::HorizontalAlign;
+use image::{ImageBuffer, Rgba, RgbaImage};
+use renderer::{apply_transform, get_quad, get_sprite_quad, transform_vertex, BatchTexture, Fonts,
+               RenderBackend, Vertex};
+use rusttype::{point, Font, Scale};
+use spritesheet::Spritesheet;
+
+const CLEAR_COLOR: [f32; 4] = [16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0, 1.0];
+
+/**
+ * Draws the node tree into an image in memory, for snapshots of the game without a gpu.
+ * Like the gfx pipeline, textures are decoded from srgb and blended in linear space, then
+ * encoded back when the image is taken. Drawing happens in node order rather than by depth
+ */
+pub struct SoftwareRenderer {
+    canvas: Canvas,
+    viewport: components::Viewport,
+    // layout coordinates to pixels of the canvas, what the model starts out as
+    view: Matrix4<f32>,
+    model: Matrix4<f32>,
+    spritesheet: Spritesheet,
+    asset_image: RgbaImage,
+    texture_map: HashMap<String, RgbaImage>,
+    fonts: Fonts,
+    font_faces: Vec<Font<'static>>,
+}
+
+impl SoftwareRenderer {
+    pub fn new(
+        spritesheet: Spritesheet,
+        asset_image: RgbaImage,
+        texture_map: HashMap<String, RgbaImage>,
+        viewport: components::Viewport,
+    ) -> Self {
+        let fonts = Fonts::load();
+        let font_faces = fonts.create_fonts();
+        let origin = Vector3::new(-viewport.bounds[0], -viewport.bounds[1], 0.0);
+        let view = Matrix4::from_scale(viewport.scale) * Matrix4::from_translation(origin);
+
+        SoftwareRenderer {
+            canvas: Canvas::new(
+                viewport.window.0.round() as u32,
+                viewport.window.1.round() as u32,
+            ),
+            viewport,
+            view,
+            model: view,
+            spritesheet,
+            asset_image,
+            texture_map,
+            fonts,
+            font_faces,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.model = self.view;
+        for pixel in self.canvas.pixels.iter_mut() {
+            *pixel = CLEAR_COLOR;
+        }
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        let canvas = &self.canvas;
+        ImageBuffer::from_fn(canvas.width, canvas.height, |x, y| {
+            let pixel = canvas.pixels[(y * canvas.width + x) as usize];
+            Rgba {
+                data: [
+                    encode_srgb(pixel[0]),
+                    encode_srgb(pixel[1]),
+                    encode_srgb(pixel[2]),
+                    (pixel[3].max(0.0).min(1.0) * 255.0).round() as u8,
+                ],
+            }
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        self.to_image()
+            .save(path)
+            .map_err(|err| format!("Could not write {:?}: {}", path, err))
+    }
+
+    fn fill_quad(&mut self, texture: BatchTexture, quad: [Vertex; 4]) {
+        let vertices: Vec<Vertex> = quad.iter()
+            .map(|vertex| transform_vertex(&self.model, vertex))
+            .collect();
+        let image = match texture {
+            BatchTexture::Color => None,
+            BatchTexture::Asset => Some(&self.asset_image),
+            BatchTexture::Named(ref name) => self.texture_map.get(name),
+        };
+        self.canvas
+            .fill_triangle(&vertices[0], &vertices[1], &vertices[2], image);
+        self.canvas
+            .fill_triangle(&vertices[2], &vertices[3], &vertices[0], image);
+    }
+}
+
+impl RenderBackend for SoftwareRenderer {
+    fn transform(&mut self, transform: &components::Transform, undo: bool) {
+        apply_transform(&mut self.model, transform, undo);
+    }
+
+    fn render_sprite(
+        &mut self,
+        transform: &components::Transform,
+        frame_name: &str,
+        color: Option<&components::Color>,
+    ) {
+        let color = color.map(|color| color.0).unwrap_or([1.0; 4]);
+        let (w, h) = (transform.size.x as f32, transform.size.y as f32);
+        let quad = get_sprite_quad(&self.spritesheet, frame_name, color, w, h);
+        self.fill_quad(BatchTexture::Asset, quad);
+    }
+
+    fn render_texture(
+        &mut self,
+        transform: &components::Transform,
+        texture_name: &str,
+        color: &components::Color,
+    ) {
+        let (w, h) = (transform.size.x as f32, transform.size.y as f32);
+        self.fill_quad(
+            BatchTexture::Named(texture_name.to_string()),
+            get_quad(color.0, w, h, [0.0, 0.0, 1.0, 1.0]),
+        );
+    }
+
+    fn render_rect(&mut self, transform: &components::Transform, color: &components::Color) {
+        let (w, h) = (transform.size.x as f32, transform.size.y as f32);
+        self.fill_quad(
+            BatchTexture::Color,
+            get_quad(color.0, w, h, [0.0, 0.0, 1.0, 1.0]),
+        );
+    }
+
+    fn render_shape(&mut self, shape: &components::Shape) {
+        let vertices: Vec<Vertex> = shape
+            .buffers
+            .vertices
+            .iter()
+            .map(|vertex| transform_vertex(&self.model, vertex))
+            .collect();
+        for triangle in shape.buffers.indices.chunks(3) {
+            if triangle.len() == 3 {
+                self.canvas.fill_triangle(
+                    &vertices[triangle[0] as usize],
+                    &vertices[triangle[1] as usize],
+                    &vertices[triangle[2] as usize],
+                    None,
+                );
+            }
+        }
+    }
+
+    fn render_text(
+        &mut self,
+        text: &components::Text,
+        transform: &components::Transform,
+        color: &components::Color,
+    ) {
+        if self.font_faces.is_empty() {
+            return;
+        }
+
+        let pos = transform.get_absolute_pos();
+        let absolute_scale = transform.get_absolute_scale();
+        let (x, y) = self.viewport.to_window(pos.x, pos.y);
+        let scale = Scale {
+            x: text.scale.x * self.viewport.scale * absolute_scale.x,
+            y: text.scale.y * self.viewport.scale * absolute_scale.y,
+        };
+        let lines = layout_text(
+            &self.fonts,
+            &self.font_faces,
+            &text.text,
+            scale,
+            text.size.x as f32 * self.viewport.scale * absolute_scale.x,
+        );
+        let v_metrics = self.font_faces[0].v_metrics(scale);
+        let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+
+        let canvas = &mut self.canvas;
+        for (i, line) in lines.iter().enumerate() {
+            let x = match text.align {
+                HorizontalAlign::Left => x,
+                HorizontalAlign::Center => x - line.width / 2.0,
+                HorizontalAlign::Right => x - line.width,
+            };
+            let baseline = y + v_metrics.ascent + line_height * i as f32;
+            for laid_out in &line.glyphs {
+                let glyph = self.font_faces[laid_out.font].glyph(laid_out.c);
+                // the notdef glyph of characters the font lacks
+                if glyph.id().0 == 0 {
+                    continue;
+                }
+                let glyph = glyph
+                    .scaled(scale)
+                    .positioned(point(x + laid_out.x, baseline));
+                if let Some(bounds) = glyph.pixel_bounding_box() {
+                    glyph.draw(|gx, gy, coverage| {
+                        canvas.blend(
+                            bounds.min.x + gx as i32,
+                            bounds.min.y + gy as i32,
+                            [color.0[0], color.0[1], color.0[2], color.0[3] * coverage],
+                        );
+                    });
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self) {}
+}
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    // linear rgba
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![CLEAR_COLOR; (width * height) as usize],
+        }
+    }
+
+    // the same blending as gfx::preset::blend::ALPHA
+    fn blend(&mut self, x: i32, y: i32, src: [f32; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let dst = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+        let a = src[3];
+        dst[0] = src[0] * a + dst[0] * (1.0 - a);
+        dst[1] = src[1] * a + dst[1] * (1.0 - a);
+        dst[2] = src[2] * a + dst[2] * (1.0 - a);
+        dst[3] = a + dst[3] * (1.0 - a);
+    }
+
+    // pixel centers on an edge belong to its triangle only for top and left edges, so quads
+    // split along a diagonal don't blend twice where their triangles meet
+    fn fill_triangle(&mut self, a: &Vertex, b: &Vertex, c: &Vertex, texture: Option<&RgbaImage>) {
+        let (a, b, c) = if edge(a, b, c.pos[0], c.pos[1]) < 0.0 {
+            (a, c, b)
+        } else {
+            (a, b, c)
+        };
+        let area = edge(a, b, c.pos[0], c.pos[1]);
+        if area <= 0.0 {
+            return;
+        }
+
+        let min_x = a.pos[0].min(b.pos[0]).min(c.pos[0]).floor().max(0.0) as i32;
+        let min_y = a.pos[1].min(b.pos[1]).min(c.pos[1]).floor().max(0.0) as i32;
+        let max_x = a.pos[0]
+            .max(b.pos[0])
+            .max(c.pos[0])
+            .ceil()
+            .min(self.width as f32) as i32;
+        let max_y = a.pos[1]
+            .max(b.pos[1])
+            .max(c.pos[1])
+            .ceil()
+            .min(self.height as f32) as i32;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge(b, c, px, py);
+                let w1 = edge(c, a, px, py);
+                let w2 = edge(a, b, px, py);
+                if !covers(w0, b, c) || !covers(w1, c, a) || !covers(w2, a, b) {
+                    continue;
+                }
+                let (w0, w1, w2) = (w0 / area, w1 / area, w2 / area);
+
+                let interpolate = |i: usize| a.color[i] * w0 + b.color[i] * w1 + c.color[i] * w2;
+                let color = [interpolate(0), interpolate(1), interpolate(2), interpolate(3)];
+                let texel = if let Some(texture) = texture {
+                    let u = a.uv[0] * w0 + b.uv[0] * w1 + c.uv[0] * w2;
+                    let v = a.uv[1] * w0 + b.uv[1] * w1 + c.uv[1] * w2;
+                    sample(texture, u, v)
+                } else {
+                    [1.0; 4]
+                };
+                self.blend(
+                    x,
+                    y,
+                    [
+                        color[0] * texel[0],
+                        color[1] * texel[1],
+                        color[2] * texel[2],
+                        color[3] * texel[3],
+                    ],
+                );
+            }
+        }
+    }
+}
+
+struct LaidOutGlyph {
+    c: char,
+    font: usize,
+    x: f32,
+}
+
+struct Line {
+    glyphs: Vec<LaidOutGlyph>,
+    width: f32,
+}
+
+impl Line {
+    fn new() -> Self {
+        Line {
+            glyphs: Vec::new(),
+            width: 0.0,
+        }
+    }
+
+    fn push(&mut self, c: char, font: usize, advance: f32) {
+        self.glyphs.push(LaidOutGlyph {
+            c,
+            font,
+            x: self.width,
+        });
+        self.width += advance;
+    }
+
+    fn trim_end(&mut self) {
+        while self.glyphs.last().map(|glyph| glyph.c == ' ').unwrap_or(false) {
+            self.width = self.glyphs.pop().unwrap().x;
+        }
+    }
+}
+
+// breaks text into lines at spaces so they fit the width, the way the glyph brush wraps it
+fn layout_text(
+    fonts: &Fonts,
+    font_faces: &[Font<'static>],
+    text: &str,
+    scale: Scale,
+    max_width: f32,
+) -> Vec<Line> {
+    let advance = |font: usize, c: char| {
+        let glyph = font_faces[font].glyph(c);
+        if glyph.id().0 == 0 {
+            0.0
+        } else {
+            glyph.scaled(scale).h_metrics().advance_width
+        }
+    };
+
+    let mut lines = vec![Line::new()];
+    let mut word = Vec::new();
+    for (run, font_id) in fonts.split_runs(text) {
+        let font = font_id.0.min(font_faces.len() - 1);
+        for c in run.chars() {
+            if c == '\n' {
+                place_word(&mut lines, &mut word, max_width);
+                lines.push(Line::new());
+            } else if c == ' ' {
+                place_word(&mut lines, &mut word, max_width);
+                lines.last_mut().unwrap().push(c, font, advance(font, c));
+            } else {
+                word.push((c, font, advance(font, c)));
+            }
+        }
+    }
+    place_word(&mut lines, &mut word, max_width);
+
+    lines
+}
+
+fn place_word(lines: &mut Vec<Line>, word: &mut Vec<(char, usize, f32)>, max_width: f32) {
+    let word_width: f32 = word.iter().map(|&(_, _, advance)| advance).sum();
+    let wraps = {
+        let line = lines.last().unwrap();
+        !line.glyphs.is_empty() && line.width + word_width > max_width
+    };
+    if wraps {
+        lines.last_mut().unwrap().trim_end();
+        lines.push(Line::new());
+    }
+
+    let line = lines.last_mut().unwrap();
+    for (c, font, advance) in word.drain(..) {
+        line.push(c, font, advance);
+    }
+}
+
+fn edge(a: &Vertex, b: &Vertex, px: f32, py: f32) -> f32 {
+    (b.pos[0] - a.pos[0]) * (py - a.pos[1]) - (b.pos[1] - a.pos[1]) * (px - a.pos[0])
+}
+
+fn covers(w: f32, from: &Vertex, to: &Vertex) -> bool {
+    let dx = to.pos[0] - from.pos[0];
+    let dy = to.pos[1] - from.pos[1];
+    w > 0.0 || (w == 0.0 && ((dy == 0.0 && dx > 0.0) || dy < 0.0))
+}
+
+// nearest texel, decoded to linear
+fn sample(texture: &RgbaImage, u: f32, v: f32) -> [f32; 4] {
+    let (w, h) = texture.dimensions();
+    let x = ((u * w as f32) as i32).max(0).min(w as i32 - 1) as u32;
+    let y = ((v * h as f32) as i32).max(0).min(h as i32 - 1) as u32;
+    let texel = texture.get_pixel(x, y).data;
+    [
+        decode_srgb(texel[0]),
+        decode_srgb(texel[1]),
+        decode_srgb(texel[2]),
+        texel[3] as f32 / 255.0,
+    ]
+}
+
+fn decode_srgb(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn encode_srgb(value: f32) -> u8 {
+    let c = value.max(0.0).min(1.0);
+    let encoded = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
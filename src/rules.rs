@@ -0,0 +1,281 @@
+use conrod::Ui;
+use loader;
+use localization::Localization;
+use mods;
+use serde_json::{self, Value};
+use settings::Settings;
+use state::UiAction;
+
+// Balance values for a run. The presets live in resources/rules.json so they can be tuned without a rebuild
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rules {
+    pub start_amount: i32,
+    pub max_power: i32,
+    pub starting_tick: i32,
+    // each additional city n demands demand_growth * (n + 1) more power per tick
+    pub demand_growth: i32,
+    // percent of income taxed per point of pollution
+    pub tax_rate: f32,
+    pub tick_rate: f32,
+}
+
+impl Rules {
+    pub fn get_power_per_tick(&self, city_count: usize) -> i32 {
+        let mut per_tick = self.starting_tick;
+        // each city is more demanding
+        for n in 0..city_count {
+            per_tick += self.demand_growth * ((n as i32) + 1);
+        }
+        per_tick
+    }
+
+    pub fn get_tax(&self, income: i32, pollution: i32) -> i32 {
+        if pollution > 0 {
+            (income as f32 * (pollution as f32 * self.tax_rate / 100.0)) as i32
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            start_amount: 25,
+            max_power: 10_000,
+            starting_tick: 40,
+            demand_growth: 15,
+            tax_rate: 1.0,
+            tick_rate: 2.5,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub fn all() -> [Difficulty; 4] {
+        [
+            Difficulty::Easy,
+            Difficulty::Normal,
+            Difficulty::Hard,
+            Difficulty::Custom,
+        ]
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Normal
+    }
+}
+
+#[derive(Deserialize)]
+struct Presets {
+    easy: Rules,
+    normal: Rules,
+    hard: Rules,
+}
+
+fn get_preset(difficulty: &Difficulty) -> Option<Rules> {
+    let data = loader::read_text_from_file("resources/rules.json").unwrap();
+    let mut presets: Value = serde_json::from_str(data.as_ref()).unwrap();
+    mods::apply_rules_patches(&mut presets);
+    let presets: Presets = serde_json::from_value(presets).unwrap();
+    match *difficulty {
+        Difficulty::Easy => Some(presets.easy),
+        Difficulty::Normal => Some(presets.normal),
+        Difficulty::Hard => Some(presets.hard),
+        Difficulty::Custom => None,
+    }
+}
+
+// read fresh each run, so changes to the presets apply to the next game
+pub fn get_rules(settings: &Settings) -> Rules {
+    get_preset(&settings.difficulty).unwrap_or_else(|| settings.custom_rules.clone())
+}
+
+widget_ids! {
+    pub struct Ids {
+        difficulty_label,
+        close_button,
+        note_label,
+        presets[],
+        start_amount,
+        max_power,
+        starting_tick,
+        demand_growth,
+        tax_rate,
+        tick_rate,
+    }
+}
+
+pub fn create_ui(
+    ui: &mut Ui,
+    ids: &mut Ids,
+    settings: &mut Settings,
+    localization: &Localization,
+) -> Option<UiAction> {
+    use conrod::{color, position::Relative, widget, Colorable, Labelable, Positionable, Sizeable,
+                 Widget};
+
+    let difficulties = Difficulty::all();
+    ids.presets
+        .resize(difficulties.len(), &mut ui.widget_id_generator());
+
+    let ui = &mut ui.set_widgets();
+
+    if widget::Button::new()
+        .top_right_with_margin_on(ui.window, 20.0)
+        .w_h(30.0, 30.0)
+        .label("X")
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .label_x(Relative::Scalar(1.0))
+        .label_y(Relative::Scalar(2.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.close_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::Close);
+    }
+
+    widget::Text::new(&localization.get("difficulty.title"))
+        .mid_top_with_margin_on(ui.window, 50.0)
+        .font_size(32)
+        .rgb(0.0, 1.0, 0.0)
+        .set(ids.difficulty_label, ui);
+
+    let button_width = 130.0;
+    let left = -button_width * (difficulties.len() as f64 - 1.0) / 2.0;
+    for (i, difficulty) in difficulties.iter().enumerate() {
+        let label_color = if *difficulty == settings.difficulty {
+            color::rgb(1.0, 1.0, 0.0)
+        } else {
+            color::rgb(0.0, 1.0, 0.0)
+        };
+
+        if widget::Button::new()
+            .down_from(ids.difficulty_label, 30.0)
+            .x_relative_to(ui.window, left + button_width * i as f64)
+            .w_h(button_width - 10.0, 35.0)
+            .label(&localization.get(&format!("difficulty.{:?}", difficulty)))
+            .label_color(label_color)
+            .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+            .set(ids.presets[i], ui)
+            .was_clicked()
+        {
+            settings.set_difficulty(*difficulty);
+        }
+    }
+
+    widget::Text::new(&localization.get("difficulty.note"))
+        .down_from(ids.presets[0], 20.0)
+        .align_middle_x_of(ids.difficulty_label)
+        .font_size(18)
+        .rgb(0.8, 0.8, 0.8)
+        .set(ids.note_label, ui);
+
+    if settings.difficulty != Difficulty::Custom {
+        return None;
+    }
+
+    let mut rules = settings.custom_rules.clone();
+    let mut changed = false;
+
+    if let Some(value) = widget::Slider::new(rules.start_amount as f32, 0.0, 500.0)
+        .down_from(ids.note_label, 25.0)
+        .align_middle_x_of(ids.difficulty_label)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.start_amount",
+            &[("money", localization.money(rules.start_amount))],
+        ))
+        .set(ids.start_amount, ui)
+    {
+        rules.start_amount = value as i32;
+        changed = true;
+    }
+
+    if let Some(value) = widget::Slider::new(rules.max_power as f32, 1000.0, 30_000.0)
+        .down_from(ids.start_amount, 15.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.max_power",
+            &[("amount", localization.number(rules.max_power as i64))],
+        ))
+        .set(ids.max_power, ui)
+    {
+        rules.max_power = value as i32;
+        changed = true;
+    }
+
+    if let Some(value) = widget::Slider::new(rules.starting_tick as f32, 10.0, 100.0)
+        .down_from(ids.max_power, 15.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.starting_tick",
+            &[("amount", localization.number(rules.starting_tick as i64))],
+        ))
+        .set(ids.starting_tick, ui)
+    {
+        rules.starting_tick = value as i32;
+        changed = true;
+    }
+
+    if let Some(value) = widget::Slider::new(rules.demand_growth as f32, 0.0, 50.0)
+        .down_from(ids.starting_tick, 15.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.demand_growth",
+            &[("amount", localization.number(rules.demand_growth as i64))],
+        ))
+        .set(ids.demand_growth, ui)
+    {
+        rules.demand_growth = value as i32;
+        changed = true;
+    }
+
+    if let Some(value) = widget::Slider::new(rules.tax_rate, 0.0, 3.0)
+        .down_from(ids.demand_growth, 15.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.tax_rate",
+            &[("rate", format!("{:.1}", rules.tax_rate))],
+        ))
+        .set(ids.tax_rate, ui)
+    {
+        rules.tax_rate = value;
+        changed = true;
+    }
+
+    if let Some(value) = widget::Slider::new(rules.tick_rate, 0.5, 5.0)
+        .down_from(ids.tax_rate, 15.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(350.0, 30.0)
+        .label(&localization.format(
+            "difficulty.tick_rate",
+            &[("seconds", format!("{:.1}", rules.tick_rate))],
+        ))
+        .set(ids.tick_rate, ui)
+    {
+        rules.tick_rate = value;
+        changed = true;
+    }
+
+    if changed {
+        settings.set_custom_rules(rules);
+    }
+
+    None
+}
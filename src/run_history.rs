@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+use std::fs;
+
+use conrod::Ui;
+use loader::get_run_history_path;
+use localization::Localization;
+use serde_json;
+use state::UiAction;
+
+const ROWS_TO_SHOW: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub cities_powered: usize,
+    pub time_survived: f32,
+    pub total_income: i32,
+    pub total_tax: i32,
+    pub peak_pollution: i32,
+    pub buffs_researched: usize,
+    pub seed: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HighScoreColumn {
+    CitiesPowered,
+    TimeSurvived,
+    TotalIncome,
+    TotalTax,
+    PeakPollution,
+    BuffsResearched,
+    Seed,
+}
+
+impl HighScoreColumn {
+    pub fn all() -> [HighScoreColumn; 7] {
+        [
+            HighScoreColumn::CitiesPowered,
+            HighScoreColumn::TimeSurvived,
+            HighScoreColumn::TotalIncome,
+            HighScoreColumn::TotalTax,
+            HighScoreColumn::PeakPollution,
+            HighScoreColumn::BuffsResearched,
+            HighScoreColumn::Seed,
+        ]
+    }
+
+    pub fn get_value(&self, record: &RunRecord, localization: &Localization) -> String {
+        match *self {
+            HighScoreColumn::CitiesPowered => format!("{}", record.cities_powered),
+            HighScoreColumn::TimeSurvived => format!(
+                "{}:{:02}",
+                record.time_survived as u32 / 60,
+                record.time_survived as u32 % 60
+            ),
+            HighScoreColumn::TotalIncome => localization.money(record.total_income),
+            HighScoreColumn::TotalTax => localization.money(record.total_tax),
+            HighScoreColumn::PeakPollution => format!("{}", record.peak_pollution),
+            HighScoreColumn::BuffsResearched => format!("{}", record.buffs_researched),
+            HighScoreColumn::Seed => format!("{}", record.seed),
+        }
+    }
+
+    // highest first for every column
+    fn compare(&self, a: &RunRecord, b: &RunRecord) -> Ordering {
+        match *self {
+            HighScoreColumn::CitiesPowered => b.cities_powered.cmp(&a.cities_powered),
+            HighScoreColumn::TimeSurvived => b.time_survived
+                .partial_cmp(&a.time_survived)
+                .unwrap_or(Ordering::Equal),
+            HighScoreColumn::TotalIncome => b.total_income.cmp(&a.total_income),
+            HighScoreColumn::TotalTax => b.total_tax.cmp(&a.total_tax),
+            HighScoreColumn::PeakPollution => b.peak_pollution.cmp(&a.peak_pollution),
+            HighScoreColumn::BuffsResearched => b.buffs_researched.cmp(&a.buffs_researched),
+            HighScoreColumn::Seed => b.seed.cmp(&a.seed),
+        }
+    }
+}
+
+impl Default for HighScoreColumn {
+    fn default() -> Self {
+        HighScoreColumn::CitiesPowered
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct RunHistory {
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    pub fn add(&mut self, record: RunRecord) {
+        self.runs.push(record);
+        self.save();
+    }
+
+    pub fn sorted_by(&self, column: &HighScoreColumn) -> Vec<&RunRecord> {
+        let mut runs: Vec<&RunRecord> = self.runs.iter().collect();
+        runs.sort_by(|a, b| column.compare(a, b));
+        runs
+    }
+
+    pub fn best_cities_powered(&self) -> usize {
+        self.runs
+            .iter()
+            .map(|run| run.cities_powered)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn save(&self) {
+        let text = serde_json::to_string(&self).unwrap();
+        fs::write(get_run_history_path().to_str().unwrap(), text)
+            .expect("Unable to write run history");
+    }
+}
+
+widget_ids! {
+    pub struct Ids {
+        high_scores_label,
+        close_button,
+        headers[],
+        cells[],
+    }
+}
+
+pub fn create_ui(
+    ui: &mut Ui,
+    ids: &mut Ids,
+    run_history: &RunHistory,
+    localization: &Localization,
+    sort_column: &mut HighScoreColumn,
+) -> Option<UiAction> {
+    use conrod::{color, position::Relative, widget, Colorable, Labelable, Positionable, Sizeable,
+                 Widget};
+
+    let columns = HighScoreColumn::all();
+    let runs = run_history.sorted_by(sort_column);
+    let row_count = runs.len().min(ROWS_TO_SHOW);
+
+    ids.headers
+        .resize(columns.len(), &mut ui.widget_id_generator());
+    ids.cells
+        .resize(columns.len() * ROWS_TO_SHOW, &mut ui.widget_id_generator());
+
+    let ui = &mut ui.set_widgets();
+
+    if widget::Button::new()
+        .top_right_with_margin_on(ui.window, 20.0)
+        .w_h(30.0, 30.0)
+        .label("X")
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .label_x(Relative::Scalar(1.0))
+        .label_y(Relative::Scalar(2.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.close_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::Close);
+    }
+
+    widget::Text::new(&localization.get("high_scores.title"))
+        .mid_top_with_margin_on(ui.window, 50.0)
+        .font_size(32)
+        .rgb(0.0, 1.0, 0.0)
+        .set(ids.high_scores_label, ui);
+
+    let column_width = 120.0;
+    let left = -column_width * (columns.len() as f64 - 1.0) / 2.0;
+
+    for (col, column) in columns.iter().enumerate() {
+        // highlight the column currently sorted by
+        let label_color = if *column == *sort_column {
+            color::rgb(1.0, 1.0, 0.0)
+        } else {
+            color::rgb(0.0, 1.0, 0.0)
+        };
+
+        if widget::Button::new()
+            .down_from(ids.high_scores_label, 30.0)
+            .x_relative_to(ui.window, left + column_width * col as f64)
+            .w_h(column_width - 10.0, 30.0)
+            .label(&localization.get(&format!("high_scores.{:?}", column)))
+            .label_color(label_color)
+            .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+            .set(ids.headers[col], ui)
+            .was_clicked()
+        {
+            *sort_column = *column;
+        }
+
+        for row in 0..row_count {
+            let id = ids.cells[row * columns.len() + col];
+            let text = column.get_value(runs[row], localization);
+            let cell = widget::Text::new(&text).font_size(18).rgb(0.8, 0.8, 0.8);
+            if row == 0 {
+                cell.down_from(ids.headers[col], 10.0).set(id, ui);
+            } else {
+                cell.down_from(ids.cells[(row - 1) * columns.len() + col], 10.0)
+                    .set(id, ui);
+            }
+        }
+    }
+
+    None
+}
@@ -1,8 +1,13 @@
 use std::fs;
 
+use components::Theme;
 use conrod::Ui;
+use key_bindings::KeyBindings;
 use loader::get_settings_path;
+use localization::Localization;
+use rules::{Difficulty, Rules};
 use serde_json;
+use state::UiAction;
 
 #[derive(Serialize, Deserialize)]
 pub struct Settings {
@@ -16,6 +21,85 @@ pub struct Settings {
     pub mute_sound_effects: bool,
     #[serde(default)]
     pub completed_tutorial: bool,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub custom_rules: Rules,
+    // a code naming one of the string tables in resources/lang, empty for english
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub key_bindings: KeyBindings,
+    #[serde(default)]
+    pub window: WindowSettings,
+    #[serde(default)]
+    pub theme: Theme,
+    // how much larger the sidebar and tech tree are drawn, one of UI_SCALES
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+}
+
+// the ui scales offered in the settings
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WindowMode {
+    Windowed,
+    // a window without decorations covering the whole monitor
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowMode {
+    pub fn all() -> [WindowMode; 3] {
+        [
+            WindowMode::Windowed,
+            WindowMode::Borderless,
+            WindowMode::Fullscreen,
+        ]
+    }
+}
+
+impl Default for WindowMode {
+    fn default() -> Self {
+        WindowMode::Windowed
+    }
+}
+
+// the window sizes offered in the settings, in logical pixels
+pub const WINDOW_SIZES: [(f64, f64); 6] = [
+    (960.0, 640.0),
+    (1280.0, 720.0),
+    (1440.0, 960.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub mode: WindowMode,
+    // the size in windowed mode, either picked in the settings or where the window was resized to
+    pub size: (f64, f64),
+    // where the window was last moved to in windowed mode, None leaves it up to the os
+    pub position: Option<(f64, f64)>,
+    pub vsync: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            mode: WindowMode::default(),
+            size: WINDOW_SIZES[0],
+            position: None,
+            vsync: true,
+        }
+    }
 }
 
 impl Settings {
@@ -44,6 +128,41 @@ impl Settings {
         self.save();
     }
 
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        self.save();
+    }
+
+    pub fn set_custom_rules(&mut self, rules: Rules) {
+        self.custom_rules = rules;
+        self.save();
+    }
+
+    pub fn set_language(&mut self, language: String) {
+        self.language = language;
+        self.save();
+    }
+
+    pub fn set_key_bindings(&mut self, key_bindings: KeyBindings) {
+        self.key_bindings = key_bindings;
+        self.save();
+    }
+
+    pub fn set_window(&mut self, window: WindowSettings) {
+        self.window = window;
+        self.save();
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.save();
+    }
+
+    pub fn set_ui_scale(&mut self, ui_scale: f32) {
+        self.ui_scale = ui_scale;
+        self.save();
+    }
+
     fn save(&self) {
         let text = serde_json::to_string(&self).unwrap();
         fs::write(get_settings_path().to_str().unwrap(), text)
@@ -59,6 +178,13 @@ impl Default for Settings {
             mute_music: false,
             mute_sound_effects: false,
             completed_tutorial: false,
+            difficulty: Difficulty::default(),
+            custom_rules: Rules::default(),
+            language: String::new(),
+            key_bindings: KeyBindings::default(),
+            window: WindowSettings::default(),
+            theme: Theme::default(),
+            ui_scale: default_ui_scale(),
         }
     }
 }
@@ -73,10 +199,33 @@ widget_ids! {
         mute_sound_effects_label,
         settings_label,
         close_button,
+        achievements_button,
+        high_scores_button,
+        difficulty_button,
+        mods_button,
+        controls_button,
+        language_label,
+        language_list,
+        window_mode_label,
+        window_mode_list,
+        window_size_label,
+        window_size_list,
+        vsync_label,
+        vsync,
+        theme_label,
+        theme_list,
+        ui_scale_label,
+        ui_scale_list,
     }
 }
 
-pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<String> {
+pub fn create_ui(
+    ui: &mut Ui,
+    ids: &mut Ids,
+    settings: &mut Settings,
+    localization: &Localization,
+    languages: &[(String, String)],
+) -> Option<UiAction> {
     use conrod::{color, position, position::Relative, widget, Colorable, Labelable, Positionable,
                  Sizeable, Widget};
 
@@ -93,10 +242,10 @@ pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<
         .set(ids.close_button, ui)
         .was_clicked()
     {
-        return Some("resume_game".to_string());
+        return Some(UiAction::ResumeGame);
     }
 
-    widget::Text::new("Settings")
+    widget::Text::new(&localization.get("settings.title"))
         .mid_top_with_margin_on(ui.window, 50.0)
         .font_size(32)
         .rgb(0.0, 1.0, 0.0)
@@ -108,7 +257,7 @@ pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<
         .x_align(position::Align::Middle)
         .color(color::rgb(0.0, 1.0, 0.0))
         .w_h(350.0, 35.0)
-        .label("Music Volume")
+        .label(&localization.get("settings.music_volume"))
         .set(ids.music_volume, ui)
     {
         settings.set_music_volume(volume);
@@ -118,14 +267,14 @@ pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<
         .middle_of(ui.window)
         .color(color::rgb(0.0, 1.0, 0.0))
         .w_h(350.0, 35.0)
-        .label("Sound Volume")
+        .label(&localization.get("settings.sound_volume"))
         .down_from(ids.music_volume, 25.0)
         .set(ids.sound_volume, ui)
     {
         settings.set_sound_volume(volume);
     }
 
-    widget::Text::new("Mute music")
+    widget::Text::new(&localization.get("settings.mute_music"))
         .down_from(ids.sound_volume, 25.0)
         .color(color::rgb(0.0, 1.0, 0.0))
         .font_size(20)
@@ -145,7 +294,7 @@ pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<
         settings.set_mute_music(state);
     }
 
-    widget::Text::new("Mute sound effects")
+    widget::Text::new(&localization.get("settings.mute_sound_effects"))
         .right_from(ids.mute_music_label, 100.0)
         .color(color::rgb(0.0, 1.0, 0.0))
         .font_size(20)
@@ -165,5 +314,220 @@ pub fn create_ui(ui: &mut Ui, ids: &mut Ids, settings: &mut Settings) -> Option<
         settings.set_mute_sound_effects(state);
     }
 
+    if widget::Button::new()
+        .down_from(ids.mute_music, 50.0)
+        .align_middle_x_of(ids.settings_label)
+        .w_h(200.0, 35.0)
+        .label(&localization.get("settings.achievements"))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.achievements_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::ShowAchievements);
+    }
+
+    if widget::Button::new()
+        .down_from(ids.achievements_button, 15.0)
+        .w_h(200.0, 35.0)
+        .label(&localization.get("settings.high_scores"))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.high_scores_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::ShowHighScores);
+    }
+
+    if widget::Button::new()
+        .down_from(ids.high_scores_button, 15.0)
+        .w_h(200.0, 35.0)
+        .label(&localization.format(
+            "settings.difficulty",
+            &[(
+                "difficulty",
+                localization.get(&format!("difficulty.{:?}", settings.difficulty)),
+            )],
+        ))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.difficulty_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::ShowDifficulty);
+    }
+
+    if widget::Button::new()
+        .down_from(ids.difficulty_button, 15.0)
+        .w_h(200.0, 35.0)
+        .label(&localization.get("settings.mods"))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.mods_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::ShowMods);
+    }
+
+    if widget::Button::new()
+        .down_from(ids.mods_button, 15.0)
+        .w_h(200.0, 35.0)
+        .label(&localization.get("settings.controls"))
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.controls_button, ui)
+        .was_clicked()
+    {
+        return Some(UiAction::ShowControls);
+    }
+
+    // set from the bottom up, so an opened list is drawn over the widgets below it
+    widget::Text::new(&localization.get("settings.ui_scale"))
+        .top_right_with_margins_on(ui.window, 360.0, 20.0)
+        .w(180.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.ui_scale_label, ui);
+
+    let ui_scales: Vec<String> = UI_SCALES
+        .iter()
+        .map(|scale| {
+            localization.format(
+                "settings.ui_scale_percent",
+                &[("percent", localization.number((scale * 100.0).round() as i64))],
+            )
+        })
+        .collect();
+    let selected = UI_SCALES
+        .iter()
+        .position(|scale| *scale == settings.ui_scale);
+    if let Some(index) = widget::DropDownList::new(&ui_scales, selected)
+        .top_right_with_margins_on(ui.window, 390.0, 20.0)
+        .w_h(180.0, 35.0)
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.ui_scale_list, ui)
+    {
+        settings.set_ui_scale(UI_SCALES[index]);
+    }
+
+    widget::Text::new(&localization.get("settings.theme"))
+        .top_right_with_margins_on(ui.window, 280.0, 20.0)
+        .w(180.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.theme_label, ui);
+
+    let themes: Vec<String> = Theme::all()
+        .iter()
+        .map(|theme| localization.get(&format!("theme.{:?}", theme)))
+        .collect();
+    let selected = Theme::all()
+        .iter()
+        .position(|theme| *theme == settings.theme);
+    if let Some(index) = widget::DropDownList::new(&themes, selected)
+        .top_right_with_margins_on(ui.window, 310.0, 20.0)
+        .w_h(180.0, 35.0)
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.theme_list, ui)
+    {
+        settings.set_theme(Theme::all()[index]);
+    }
+
+    widget::Text::new(&localization.get("settings.vsync"))
+        .top_right_with_margins_on(ui.window, 230.0, 65.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.vsync_label, ui);
+
+    if let Some(state) = widget::Toggle::new(settings.window.vsync)
+        .top_right_with_margins_on(ui.window, 225.0, 20.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .w_h(35.0, 35.0)
+        .label(if settings.window.vsync { "X" } else { "" })
+        .label_x(Relative::Scalar(0.0))
+        .label_y(Relative::Scalar(1.0))
+        .label_color(color::rgb(0.0, 0.0, 0.0))
+        .set(ids.vsync, ui)
+        .last()
+    {
+        let mut window = settings.window.clone();
+        window.vsync = state;
+        settings.set_window(window);
+    }
+
+    widget::Text::new(&localization.get("settings.window_size"))
+        .top_right_with_margins_on(ui.window, 150.0, 20.0)
+        .w(180.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.window_size_label, ui);
+
+    let sizes: Vec<String> = WINDOW_SIZES
+        .iter()
+        .map(|&(w, h)| format!("{} x {}", w, h))
+        .collect();
+    let selected = WINDOW_SIZES
+        .iter()
+        .position(|&size| size == settings.window.size);
+    if let Some(index) = widget::DropDownList::new(&sizes, selected)
+        .top_right_with_margins_on(ui.window, 180.0, 20.0)
+        .w_h(180.0, 35.0)
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.window_size_list, ui)
+    {
+        let mut window = settings.window.clone();
+        window.size = WINDOW_SIZES[index];
+        settings.set_window(window);
+    }
+
+    widget::Text::new(&localization.get("settings.window_mode"))
+        .top_right_with_margins_on(ui.window, 70.0, 20.0)
+        .w(180.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.window_mode_label, ui);
+
+    let modes: Vec<String> = WindowMode::all()
+        .iter()
+        .map(|mode| localization.get(&format!("window_mode.{:?}", mode)))
+        .collect();
+    let selected = WindowMode::all()
+        .iter()
+        .position(|mode| *mode == settings.window.mode);
+    if let Some(index) = widget::DropDownList::new(&modes, selected)
+        .top_right_with_margins_on(ui.window, 100.0, 20.0)
+        .w_h(180.0, 35.0)
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.window_mode_list, ui)
+    {
+        let mut window = settings.window.clone();
+        window.mode = WindowMode::all()[index];
+        settings.set_window(window);
+    }
+
+    widget::Text::new(&localization.get("settings.language"))
+        .top_left_with_margin_on(ui.window, 20.0)
+        .color(color::rgb(0.0, 1.0, 0.0))
+        .font_size(20)
+        .set(ids.language_label, ui);
+
+    let names: Vec<&str> = languages.iter().map(|&(_, ref name)| name.as_ref()).collect();
+    let selected = languages
+        .iter()
+        .position(|&(ref code, _)| code == localization.get_code());
+    if let Some(index) = widget::DropDownList::new(&names, selected)
+        .down_from(ids.language_label, 10.0)
+        .w_h(180.0, 35.0)
+        .label_color(color::rgb(0.0, 1.0, 0.0))
+        .color(color::rgb(16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0))
+        .set(ids.language_list, ui)
+    {
+        settings.set_language(languages[index].0.clone());
+    }
+
     None
 }
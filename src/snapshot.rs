@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::ops::DerefMut;
+use std::path::PathBuf;
+use std::process;
+
+use image::RgbaImage;
+use serde_json;
+use specs::{RunNow, World};
+
+use components::{DeltaTime, EventChannel, GameEvent, StateChange, StateId, UiCommand, UiCommands,
+                 Viewport};
+use loader;
+use renderer::{self, SoftwareRenderer};
+use spritesheet::Spritesheet;
+use state::{play_state::PlayLayouts, StateManager};
+use systems;
+
+// a fixed step, so frame n of a seed always shows the same moment of the game
+const FRAME_TIME: f32 = 1.0 / 60.0;
+
+// Runs the game without a window, writing frames to png through the software renderer:
+//   --snapshot <dir> [--state menu|play] [--seed n] [--frames n] [--every n] [--size wxh]
+//   [--ui-scale n]
+// Nothing reads input, so the same arguments always give the same images, which
+// tests/snapshots.rs compares against the references in tests/snapshots
+pub struct SnapshotOptions {
+    pub out_dir: PathBuf,
+    pub state: StateId,
+    pub seed: usize,
+    pub frames: usize,
+    // a frame is written every this many frames, and always for the last one
+    pub every: usize,
+    // the window size and ui scale to lay out and draw for, the base resolution by default
+    pub viewport: Viewport,
+}
+
+impl SnapshotOptions {
+    // None when the game wasn't started in snapshot mode
+    pub fn from_args(args: &[String]) -> Result<Option<SnapshotOptions>, String> {
+        let out_dir = match args.iter().position(|arg| arg == "--snapshot") {
+            Some(i) => args
+                .get(i + 1)
+                .ok_or_else(|| "--snapshot needs an output directory".to_string())?,
+            None => return Ok(None),
+        };
+
+        let mut options = SnapshotOptions {
+            out_dir: PathBuf::from(out_dir),
+            state: StateId::Play,
+            seed: 0,
+            frames: 1,
+            every: 0,
+            viewport: Viewport::default(),
+        };
+
+        let dim = renderer::get_dimensions();
+        let mut size = (dim[0], dim[1]);
+        let mut ui_scale = 1.0;
+        let mut i = 0;
+        while i < args.len() {
+            let value = args.get(i + 1);
+            match args[i].as_ref() {
+                "--state" => {
+                    options.state = match value.map(|value| value.as_ref()) {
+                        Some("menu") => StateId::Menu,
+                        Some("play") => StateId::Play,
+                        _ => return Err("--state is either menu or play".to_string()),
+                    }
+                }
+                "--seed" => options.seed = parse_number(value, "--seed")?,
+                "--frames" => options.frames = parse_number(value, "--frames")?,
+                "--every" => options.every = parse_number(value, "--every")?,
+                "--size" => size = parse_size(value)?,
+                "--ui-scale" => ui_scale = parse_scale(value)?,
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 2;
+        }
+
+        if options.frames == 0 {
+            return Err("--frames needs to be at least 1".to_string());
+        }
+        if options.every == 0 {
+            options.every = options.frames;
+        }
+        options.viewport = Viewport::new(size.0, size.1, ui_scale);
+
+        Ok(Some(options))
+    }
+}
+
+fn parse_number(value: Option<&String>, flag: &str) -> Result<usize, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("{} needs a number", flag))
+}
+
+// a window size such as 2560x1080
+fn parse_size(value: Option<&String>) -> Result<(f32, f32), String> {
+    let size: Vec<usize> = value
+        .map(|value| value.split('x').filter_map(|n| n.parse().ok()).collect())
+        .unwrap_or_else(Vec::new);
+    if size.len() != 2 || size[0] == 0 || size[1] == 0 {
+        return Err("--size needs a width and height, such as 1920x1080".to_string());
+    }
+
+    Ok((size[0] as f32, size[1] as f32))
+}
+
+fn parse_scale(value: Option<&String>) -> Result<f32, String> {
+    match value.and_then(|value| value.parse::<f32>().ok()) {
+        Some(scale) if scale > 0.0 => Ok(scale),
+        _ => Err("--ui-scale needs a number above 0, such as 1.5".to_string()),
+    }
+}
+
+// Points settings, saves and mods at an empty directory, so the player's files neither change
+// the images nor get written to. Returns the directory, to be removed once done
+pub fn isolate_data_dir() -> PathBuf {
+    let data_dir = env::temp_dir().join(format!("energy_grid_snapshot_{}", process::id()));
+    env::set_var(loader::DATA_DIR_VAR, &data_dir);
+    data_dir
+}
+
+// the menu backgrounds by their name in the texture map, along with their sizes
+pub fn load_screen_images() -> Result<([(u16, u16); 4], HashMap<String, RgbaImage>), String> {
+    let mut screen_sizes = [(0u16, 0u16); 4];
+    let mut texture_map = HashMap::new();
+    for (i, name) in loader::SCREEN_IMAGES.iter().enumerate() {
+        let image = loader::load_image(&format!("resources/startscreens/{}", name))?;
+        let (w, h) = image.dimensions();
+        screen_sizes[i] = (w as u16, h as u16);
+        texture_map.insert(name.to_string(), image);
+    }
+
+    Ok((screen_sizes, texture_map))
+}
+
+pub fn run(
+    options: &SnapshotOptions,
+    world: &mut World,
+    state_manager: &mut StateManager,
+    texture_map: HashMap<String, RgbaImage>,
+) -> Result<(), String> {
+    // the base sheet only, mods are left out like the rest of the data dir
+    let asset_data = loader::read_text_from_file("resources/assets.json")
+        .map_err(|err| format!("Could not read resources/assets.json: {}", err))?;
+    let spritesheet: Spritesheet = serde_json::from_str(asset_data.as_ref())
+        .map_err(|err| format!("Could not parse resources/assets.json: {}", err))?;
+    let asset_image = loader::load_image("resources/assets.png")?;
+    world.add_resource(PlayLayouts::load(&spritesheet)?);
+    let mut renderer = SoftwareRenderer::new(
+        spritesheet,
+        asset_image,
+        texture_map,
+        options.viewport.clone(),
+    );
+
+    fs::create_dir_all(&options.out_dir)
+        .map_err(|err| format!("Could not create {:?}: {}", options.out_dir, err))?;
+
+    world
+        .write_resource::<UiCommands>()
+        .push(UiCommand::RestartSameSeed(options.seed));
+    state_manager.switch_state(options.state, world);
+
+    for frame in 1..(options.frames + 1) {
+        world.write_resource::<DeltaTime>().dt = FRAME_TIME;
+        world.write_resource::<EventChannel<GameEvent>>().update();
+        state_manager.update(world);
+        world.maintain();
+        systems::ApplyTheme.run_now(&world.res);
+        systems::AnchorLayout.run_now(&world.res);
+        systems::TextAbsoluteCache.run_now(&world.res);
+
+        if frame % options.every == 0 || frame == options.frames {
+            renderer.clear();
+            renderer::render_scene(&mut renderer, world);
+            renderer.save(&options.out_dir.join(format!("frame_{:04}.png", frame)))?;
+        }
+
+        let mut state_change = {
+            let mut state_change_storage = world.write_resource::<StateChange>();
+            let state_change = state_change_storage.deref_mut();
+            let copy = state_change.clone();
+            state_change.reset();
+            copy
+        };
+        state_manager.process_state_change(&mut state_change, world);
+    }
+
+    Ok(())
+}
@@ -16,10 +16,14 @@ pub struct Size {
 #[serde(rename_all = "camelCase")]
 pub struct Frame {
     pub filename: String,
+    // where the frame is in the sheet, w and h being its size before any rotation
     pub frame: FrameData,
+    // stored turned 90 degrees clockwise
     pub rotated: bool,
     pub trimmed: bool,
+    // the part of the original image the frame holds, all of it unless trimmed
     pub sprite_source_size: FrameData,
+    // the original image's size
     pub source_size: Size,
 }
 
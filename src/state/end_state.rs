@@ -0,0 +1,71 @@
+use conrod::Ui;
+use specs::{Dispatcher, DispatcherBuilder, World};
+
+use components::{InternalState, StateAction, UiCommand};
+use state::{State, StateUi};
+use systems;
+
+// Pushed over a game once it is lost, leaving the final map visible under the end screen
+pub struct EndState<'a> {
+    dispatcher: Dispatcher<'a, 'a>,
+    state: InternalState,
+    state_ui: StateUi,
+}
+
+impl<'a> EndState<'a> {
+    pub fn new() -> Self {
+        let dispatcher = DispatcherBuilder::new()
+            .with(systems::ButtonHover {}, "button_hover", &[])
+            .with(systems::TextAbsoluteCache {}, "text_absolute_cache", &[])
+            .with(
+                systems::EndScreen::new(),
+                "end_screen",
+                &["button_hover", "text_absolute_cache"],
+            )
+            .build();
+
+        EndState {
+            dispatcher,
+            state: InternalState::End,
+            state_ui: StateUi::new(),
+        }
+    }
+}
+
+impl<'a> State for EndState<'a> {
+    fn setup(&mut self, world: &mut World) {
+        self.state = InternalState::End;
+        world.add_resource(InternalState::End);
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.dispatcher.dispatch(&mut world.res);
+    }
+
+    fn handle_custom_change(&mut self, action: &StateAction, world: &mut World) {
+        match *action {
+            StateAction::ShowHighScores => {
+                self.state_ui.show_high_scores();
+                self.state = InternalState::HighScores;
+                world.add_resource(InternalState::HighScores);
+            }
+            StateAction::HideHighScores => {
+                self.state = InternalState::End;
+                world.add_resource(InternalState::End);
+            }
+            _ => {}
+        }
+    }
+
+    fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
+        Some(&mut self.state_ui.ui)
+    }
+
+    fn create_ui_widgets(&mut self, world: &mut World) -> Option<UiCommand> {
+        self.state_ui.create_ui_widgets(world)
+    }
+
+    fn should_render_ui(&self) -> bool {
+        self.state == InternalState::HighScores
+    }
+}
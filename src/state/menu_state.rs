@@ -1,23 +1,16 @@
-use std::path::Path;
-
-use conrod::{Ui, UiBuilder};
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng, StdRng};
 use specs::{Dispatcher, DispatcherBuilder, World};
 
-use components::{Button, Color, CurrentState, EntityLookup, InternalState, MenuScreen, Node,
-                 Sprite, Texture, Transform};
-use loader;
+use components::{Anchor, Button, Color, CurrentState, EntityLookup, HorizontalAnchor,
+                 InternalState, MenuScreen, Node, Sprite, StateAction, StateId, Texture, Transform,
+                 UiCommand, UiCommands, VerticalAnchor};
 use renderer;
-use settings::{create_ui, Ids, Settings};
 use state::State;
 use systems;
 
 pub struct MenuState<'a> {
     dispatcher: Dispatcher<'a, 'a>,
     screen_sizes: [(u16, u16); 4],
-    state: InternalState,
-    ui: Ui,
-    ids: Ids,
 }
 
 impl<'a> MenuState<'a> {
@@ -34,33 +27,26 @@ impl<'a> MenuState<'a> {
             .with(systems::Fade {}, "fade", &[])
             .build();
 
-        let dim = renderer::get_dimensions();
-        let mut ui = UiBuilder::new([dim[0] as f64, dim[1] as f64]).build();
-        ui.fonts
-            .insert_from_file(Path::new(&loader::get_exe_path().join("resources/MunroSmall.ttf")))
-            .unwrap();
-
-        let ids = Ids::new(ui.widget_id_generator());
-
         MenuState {
             dispatcher,
             screen_sizes,
-            state: InternalState::Game,
-            ui,
-            ids,
         }
     }
-
-    pub fn get_name() -> String {
-        "menu_state".to_string()
-    }
 }
 
 impl<'a> State for MenuState<'a> {
     fn setup(&mut self, world: &mut World) {
-        world.add_resource(CurrentState(MenuState::get_name()));
+        world.add_resource(CurrentState(StateId::Menu));
         world.add_resource(InternalState::Game);
-        let mut rng = thread_rng();
+        // the same seed picks the same backgrounds, so a snapshot of the menu is repeatable
+        let seed = world
+            .write_resource::<UiCommands>()
+            .take(|command| match *command {
+                UiCommand::RestartSameSeed(seed) => Some(seed),
+                _ => None,
+            })
+            .unwrap_or_else(|| thread_rng().gen::<usize>());
+        let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
         let pos = MenuScreen::get_random_position(&mut rng);
         let end_pos = MenuScreen::get_random_position(&mut rng);
         let menu_screen = MenuScreen::new(0.0, pos.clone(), end_pos);
@@ -200,8 +186,27 @@ impl<'a> State for MenuState<'a> {
 
         let mut lookup = EntityLookup::new();
 
+        let dim = renderer::get_dimensions();
+        let mut content = Node::new();
+        content.add_many(child_entities);
+        let content_entity = world
+            .create_entity()
+            .with(Transform::visible(
+                0.0,
+                0.0,
+                0.0,
+                dim[0] as u16,
+                dim[1] as u16,
+                0.0,
+                1.0,
+                1.0,
+            ))
+            .with(Anchor::new(HorizontalAnchor::Center, VerticalAnchor::Center))
+            .with(content)
+            .build();
+
         let mut root = Node::new();
-        root.add_many(child_entities);
+        root.add(content_entity);
 
         let root_entity = world.create_entity().with(root).build();
         lookup.entities.insert("root".to_string(), root_entity);
@@ -213,28 +218,13 @@ impl<'a> State for MenuState<'a> {
         self.dispatcher.dispatch(&mut world.res);
     }
 
-    fn handle_custom_change(&mut self, action: &String, world: &mut World) {
-        if action == "resume" {
-            self.state = InternalState::Game;
-            world.add_resource(InternalState::Game);
-        } else if action == "pause" {
-            self.state = InternalState::Pause;
-            world.add_resource(InternalState::Pause);
-        } else if action == "transition" {
-            self.state = InternalState::Transition;
+    fn handle_custom_change(&mut self, action: &StateAction, world: &mut World) {
+        if *action == StateAction::StartFade {
             world.add_resource(InternalState::Transition);
         }
     }
 
-    fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
-        Some(&mut self.ui)
-    }
-
-    fn create_ui_widgets(&mut self, settings: &mut Settings) -> Option<String> {
-        create_ui(&mut self.ui, &mut self.ids, settings)
-    }
-
-    fn should_render_ui(&self) -> bool {
-        self.state == InternalState::Pause
+    fn resume(&mut self, world: &mut World) {
+        world.add_resource(InternalState::Game);
     }
 }
@@ -1,39 +1,55 @@
+pub mod end_state;
 pub mod menu_state;
+pub mod pause_state;
 pub mod play_state;
+mod state_ui;
+pub mod tech_tree_state;
+
+pub use self::state_ui::{StateUi, UiAction};
 
-use settings::Settings;
 use specs::World;
 use std::collections::HashMap;
 
-use components::StateChange;
+use components::{StateAction, StateChange, StateId, Transition, UiCommand};
 use conrod::Ui;
 
 pub trait State {
     fn setup(&mut self, world: &mut World);
     fn update(&mut self, &mut World);
-    fn handle_custom_change(&mut self, &String, &mut World);
-    fn get_ui_to_render(&mut self) -> Option<&mut Ui>;
-    fn should_render_ui(&self) -> bool;
-    fn create_ui_widgets(&mut self, settings: &mut Settings) -> Option<String>;
+    fn handle_custom_change(&mut self, &StateAction, &mut World) {}
+    // the overlay above this state was popped
+    fn resume(&mut self, &mut World) {}
+    // this state is being popped, its world stays with the state below
+    fn teardown(&mut self, &mut World) {}
+    fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
+        None
+    }
+    fn should_render_ui(&self) -> bool {
+        false
+    }
+    fn create_ui_widgets(&mut self, &mut World) -> Option<UiCommand> {
+        None
+    }
 }
 
 pub struct StateManager {
-    current_state: String,
-    states: HashMap<String, Box<State>>,
+    // the last state is the one updated, the ones below it are suspended
+    stack: Vec<StateId>,
+    states: HashMap<StateId, Box<State>>,
     pub restart_next_frame: bool,
 }
 
 impl StateManager {
     pub fn new() -> StateManager {
         StateManager {
-            current_state: "".to_string(),
+            stack: Vec::new(),
             states: HashMap::new(),
             restart_next_frame: false,
         }
     }
 
-    pub fn add_state(&mut self, name: String, state: Box<State>) {
-        self.states.insert(name, state);
+    pub fn add_state(&mut self, id: StateId, state: Box<State>) {
+        self.states.insert(id, state);
     }
 
     pub fn cleanup_state(&self, world: &mut World) {
@@ -41,64 +57,89 @@ impl StateManager {
     }
 
     pub fn process_state_change(&mut self, state_change: &mut StateChange, world: &mut World) {
-        if state_change.action != "" && state_change.state != "" {
-            if state_change.action == "restart" {
-                self.restart_current_state(world);
-            } else if state_change.action == "start" {
-                self.swap_state(state_change.state.clone(), world);
-            } else {
-                self.states
-                    .get_mut(&self.current_state)
-                    .unwrap()
-                    .handle_custom_change(&state_change.action, world);
+        match state_change.transition {
+            Some(Transition::Switch(id)) => self.switch_state(id, world),
+            Some(Transition::Restart) => self.restart_current_state(world),
+            Some(Transition::Push(id)) => self.push_state(id, world),
+            Some(Transition::Pop) => self.pop_state(world),
+            Some(Transition::Custom(action)) => {
+                self.top_state().handle_custom_change(&action, world)
             }
+            None => {}
         }
     }
 
     pub fn restart_current_state(&mut self, world: &mut World) {
+        self.pop_overlays(world);
         self.cleanup_state(world);
-        if let Some(current_state) = self.states.get_mut(&self.current_state) {
-            current_state.setup(world);
+        if let Some(&id) = self.stack.first() {
+            self.state(id).setup(world);
             world.maintain();
         }
     }
 
-    pub fn swap_state(&mut self, name: String, world: &mut World) {
+    pub fn switch_state(&mut self, id: StateId, world: &mut World) {
+        self.pop_overlays(world);
+        self.stack.clear();
         self.cleanup_state(world);
-        self.current_state = name;
-        self.states
-            .get_mut(&self.current_state)
-            .unwrap()
-            .setup(world);
+        self.stack.push(id);
+        self.state(id).setup(world);
 
         world.maintain();
     }
 
+    pub fn push_state(&mut self, id: StateId, world: &mut World) {
+        if self.stack.contains(&id) {
+            return;
+        }
+        self.stack.push(id);
+        self.state(id).setup(world);
+
+        world.maintain();
+    }
+
+    pub fn pop_state(&mut self, world: &mut World) {
+        // the state at the bottom owns the world, it is only replaced through a switch
+        if self.stack.len() < 2 {
+            return;
+        }
+        let id = self.stack.pop().unwrap();
+        self.state(id).teardown(world);
+        self.top_state().resume(world);
+
+        world.maintain();
+    }
+
+    fn pop_overlays(&mut self, world: &mut World) {
+        while self.stack.len() > 1 {
+            let id = self.stack.pop().unwrap();
+            self.state(id).teardown(world);
+        }
+    }
+
+    fn state(&mut self, id: StateId) -> &mut Box<State> {
+        self.states.get_mut(&id).unwrap()
+    }
+
+    fn top_state(&mut self) -> &mut Box<State> {
+        let id = *self.stack.last().unwrap();
+        self.state(id)
+    }
+
     pub fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
-        self.states
-            .get_mut(&self.current_state)
-            .unwrap()
-            .get_ui_to_render()
+        self.top_state().get_ui_to_render()
     }
 
     pub fn should_render_ui(&self) -> bool {
-        self.states
-            .get(&self.current_state)
-            .unwrap()
-            .should_render_ui()
+        let id = self.stack.last().unwrap();
+        self.states.get(id).unwrap().should_render_ui()
     }
 
-    pub fn create_ui_widgets(&mut self, settings: &mut Settings) -> Option<String> {
-        self.states
-            .get_mut(&self.current_state)
-            .unwrap()
-            .create_ui_widgets(settings)
+    pub fn create_ui_widgets(&mut self, world: &mut World) -> Option<UiCommand> {
+        self.top_state().create_ui_widgets(world)
     }
 
     pub fn update(&mut self, world: &mut World) {
-        self.states
-            .get_mut(&self.current_state)
-            .unwrap()
-            .update(world);
+        self.top_state().update(world);
     }
 }
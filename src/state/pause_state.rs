@@ -0,0 +1,50 @@
+use conrod::Ui;
+use specs::{Dispatcher, DispatcherBuilder, World};
+
+use components::{InternalState, UiCommand};
+use state::{State, StateUi};
+use systems;
+
+// The settings screen, pushed over the menu or a game
+pub struct PauseState<'a> {
+    dispatcher: Dispatcher<'a, 'a>,
+    state_ui: StateUi,
+}
+
+impl<'a> PauseState<'a> {
+    pub fn new() -> Self {
+        let dispatcher = DispatcherBuilder::new()
+            .with(systems::ButtonHover {}, "button_hover", &[])
+            .with(systems::TextAbsoluteCache {}, "text_absolute_cache", &[])
+            .with(systems::TogglePause {}, "toggle_pause", &["button_hover"])
+            .build();
+
+        PauseState {
+            dispatcher,
+            state_ui: StateUi::new(),
+        }
+    }
+}
+
+impl<'a> State for PauseState<'a> {
+    fn setup(&mut self, world: &mut World) {
+        self.state_ui.reset();
+        world.add_resource(InternalState::Pause);
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.dispatcher.dispatch(&mut world.res);
+    }
+
+    fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
+        Some(&mut self.state_ui.ui)
+    }
+
+    fn create_ui_widgets(&mut self, world: &mut World) -> Option<UiCommand> {
+        self.state_ui.create_ui_widgets(world)
+    }
+
+    fn should_render_ui(&self) -> bool {
+        true
+    }
+}
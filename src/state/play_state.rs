@@ -1,31 +1,69 @@
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::ops::Deref;
 
-use conrod::{Ui, UiBuilder};
-use loader;
 use specs::{Dispatcher, DispatcherBuilder, LazyUpdate, Read, World};
 use state::State;
 
-use components::{ui::WalletUI, upgrade, upgrade::Buff, Button, CityPowerState, Color,
-                 CurrentState, EntityLookup, GathererPositions, GatheringRate, InternalState,
-                 Node, PollutedTiles, PowerBar, Rect, ResearchedBuffs, ResearchingEntities, Resources,
-                 SelectedTile, Sprite, Text, Tile, TileNodes, TileType, Transform,
-                 Wallet};
-use entities::{create_map, create_power_bar, create_text, tech_tree};
-use rand::{thread_rng, Rng};
-use renderer;
-use settings::{create_ui, Ids, Settings};
+use components::{ui::{UiScaled, WalletUI}, upgrade, upgrade::Buff, Anchor, Button, ChosenGatherer,
+                 CityPowerState, Color, CurrentState, DeltaTime, EntityLookup, GameSpeed,
+                 GathererPositions, GatheringRate, HorizontalAnchor, InternalState, MapBlockers,
+                 MapSeed, Node, Notifications, PollutedTiles, PowerBar, Rect, ResearchedBuffs,
+                 ResearchingEntities, Resources, RunStats, SelectedTile, Sprite, StateId,
+                 Statistics, Tile, TileCursor, TileNodes, TileType, Transform, UiCommand,
+                 UiCommands, VerticalAnchor, Wallet, MAP_SIZE, SIDEBAR_WIDTH};
+use entities::{create_map, create_notification_panel, create_power_bar,
+               create_statistics_panel, create_text_button, tech_tree, ui_layout};
+use rand::{thread_rng, Rng, SeedableRng, StdRng};
+use localization::Localization;
+use rules;
+use settings::Settings;
+use spritesheet::Spritesheet;
 use storage_types::*;
 use systems;
 
+const SIDEBAR_LAYOUT: &str = "resources/ui/sidebar.json";
+
+// the sidebar entities systems look up, the layout has to give each of them an element
+const SIDEBAR_LOOKUP_KEYS: [&str; 15] = [
+    "side_bar_container",
+    "show_button_entity",
+    "wallet_text",
+    "power_gain_text",
+    "show_statistics",
+    "undo_build",
+    "show_notifications",
+    "gathering_rate_coal",
+    "gathering_rate_oil",
+    "gathering_rate_hydro",
+    "gathering_rate_solar",
+    "gathering_rate_power",
+    "gathering_rate_money",
+    "game_speed_text",
+    "power_additional_city",
+];
+
+// The layouts of the play screen, read and checked once at startup so a broken file is reported
+// before any game starts instead of when one does
+pub struct PlayLayouts {
+    pub sidebar: ui_layout::LayoutElement,
+}
+
+impl PlayLayouts {
+    pub fn load(spritesheet: &Spritesheet) -> Result<PlayLayouts, String> {
+        Ok(PlayLayouts {
+            sidebar: ui_layout::load_layout(SIDEBAR_LAYOUT, &SIDEBAR_LOOKUP_KEYS, spritesheet)?,
+        })
+    }
+}
+
+// the buttons along the bottom of the tech tree stay there however tall the window is
+fn get_bottom_row_anchor(x: f32) -> Anchor {
+    Anchor::new(HorizontalAnchor::Left, VerticalAnchor::Bottom).margin(x, 0.0, 0.0, 32.0)
+}
+
+// Overlays run on top of it through the StateManager: PauseState, TechTreeState and EndState
 pub struct PlayState<'a> {
     dispatcher: Dispatcher<'a, 'a>,
-    tech_tree_dispatcher: Dispatcher<'a, 'a>,
-    pause_dispatcher: Dispatcher<'a, 'a>,
-    end_dispatcher: Dispatcher<'a, 'a>,
-    state: InternalState,
-    ui: Ui,
-    ids: Ids,
 }
 
 impl<'a> PlayState<'a> {
@@ -34,17 +72,29 @@ impl<'a> PlayState<'a> {
             .with(systems::AnimationSystem::new(), "animation_system", &[])
             .with(systems::ButtonHover {}, "button_hover", &[])
             .with(
-                systems::BuildGatherer {},
+                systems::BuildGatherer::new(),
                 "build_gatherer",
                 &["button_hover"],
             )
+            .with(systems::MoveTileCursor {}, "move_tile_cursor", &[])
             .with(
                 systems::TileSelection::new(),
                 "tile_selection",
-                &["build_gatherer"],
+                &["build_gatherer", "move_tile_cursor"],
+            )
+            .with(
+                systems::BuildPreview::new(),
+                "build_preview",
+                &["build_gatherer", "tile_selection"],
+            )
+            .with(
+                systems::InspectTile::new(),
+                "inspect_tile",
+                &["button_hover", "move_tile_cursor", "tile_selection"],
             )
             .with(systems::Gathering::new(), "gathering", &[])
             .with(systems::SellEnergy::new(), "sell_energy", &["gathering"])
+            .with(systems::ChangeGameSpeed {}, "change_game_speed", &[])
             .with(
                 systems::ToggleTechTree::new(),
                 "toggle_tech_tree",
@@ -57,6 +107,11 @@ impl<'a> PlayState<'a> {
                 &[],
             )
             .with(systems::TogglePause {}, "toggle_pause", &["button_hover"])
+            .with(
+                systems::StatisticsPanel::new(),
+                "statistics_panel",
+                &["button_hover", "sell_energy"],
+            )
             .with(systems::Tutorial::new(), "tutorial", &[])
             .with(systems::PulseSystem {}, "pulse", &[])
             .with(
@@ -64,83 +119,62 @@ impl<'a> PlayState<'a> {
                 "text_absolute_cache",
                 &[
                     "build_gatherer",
+                    "build_preview",
+                    "change_game_speed",
                     "floating_text_system",
+                    "inspect_tile",
                     "toggle_tech_tree",
                     "sell_energy",
                     "tutorial",
                 ],
             )
-            .with(systems::Errors {}, "errors", &["build_gatherer"])
-            .build();
-
-        let tech_tree_dispatcher = DispatcherBuilder::new()
-            .with(systems::ButtonHover {}, "button_hover", &[])
             .with(
-                systems::ToggleTechTree::new(),
-                "toggle_tech_tree",
-                &["button_hover"],
+                systems::TrackAchievements::new(),
+                "track_achievements",
+                &["build_gatherer", "sell_energy"],
             )
-            .with(systems::TechTree::new(), "tech_tree", &[])
-            .with(systems::Tutorial::new(), "tutorial", &[])
-            .with(systems::PulseSystem {}, "pulse", &[])
             .with(
-                systems::TextAbsoluteCache {},
-                "text_absolute_cache",
-                &["tutorial", "tech_tree", "toggle_tech_tree"],
+                systems::NotificationCenter::new(),
+                "notification_center",
+                &[
+                    "build_gatherer",
+                    "research",
+                    "sell_energy",
+                    "statistics_panel",
+                    "track_achievements",
+                ],
             )
-            .with(systems::Errors {}, "errors", &["tech_tree"])
-            .build();
-
-        let pause_dispatcher = DispatcherBuilder::new()
-            .with(systems::ButtonHover {}, "button_hover", &[])
-            .with(systems::TextAbsoluteCache {}, "text_absolute_cache", &[])
-            .with(systems::TogglePause {}, "toggle_pause", &["button_hover"])
-            .build();
-
-        let end_dispatcher = DispatcherBuilder::new()
-            .with(systems::ButtonHover {}, "button_hover", &[])
-            .with(systems::TextAbsoluteCache {}, "text_absolute_cache", &[])
             .with(
-                systems::EndScreen {},
-                "end_screen",
-                &["button_hover", "text_absolute_cache"],
+                systems::BlockMapTiles {},
+                "block_map_tiles",
+                &["notification_center", "statistics_panel", "toggle_tech_tree"],
             )
             .build();
 
-        let dim = renderer::get_dimensions();
-        let mut ui = UiBuilder::new([dim[0] as f64, dim[1] as f64]).build();
-        ui.fonts
-            .insert_from_file(Path::new(&loader::get_exe_path().join("resources/MunroSmall.ttf")))
-            .unwrap();
-
-        let ids = Ids::new(ui.widget_id_generator());
-
-        let ps = PlayState {
-            dispatcher,
-            tech_tree_dispatcher,
-            pause_dispatcher,
-            end_dispatcher,
-            state: InternalState::Game,
-            ui,
-            ids,
-        };
+        let ps = PlayState { dispatcher };
 
         ps
     }
-
-    pub fn get_name() -> String {
-        "play_state".to_string()
-    }
 }
 
 impl<'a> State for PlayState<'a> {
     fn setup(&mut self, world: &mut World) {
-        let mut set_nodes = create_map::create();
-        let mut rng = thread_rng();
+        let seed = world
+            .write_resource::<UiCommands>()
+            .take(|command| match *command {
+                UiCommand::RestartSameSeed(seed) => Some(seed),
+                _ => None,
+            })
+            .unwrap_or_else(|| thread_rng().gen::<usize>());
+        let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+        let mut set_nodes = create_map::create(&mut rng);
+        world.add_resource(MapSeed(seed));
+
+        let rules = rules::get_rules(world.read_resource::<Settings>().deref());
 
         let mut entities_under_root = Vec::new();
 
-        world.add_resource(CurrentState(PlayState::get_name()));
+        world.add_resource(CurrentState(StateId::Play));
 
         for row in 0..10 {
             for col in 0..10 {
@@ -209,47 +243,59 @@ impl<'a> State for PlayState<'a> {
         world.add_resource(GathererPositions::new());
         world.add_resource(GatheringRate::new());
         world.add_resource(Resources::new());
-        world.add_resource(Wallet::new());
+        world.add_resource(Wallet::new(rules.start_amount));
+        world.add_resource(RunStats::new());
+        world.add_resource(Statistics::new());
+        world.add_resource(Notifications::new());
+        world.add_resource(MapBlockers::default());
         world.add_resource(InternalState::Game);
+        world.add_resource(GameSpeed::default());
+        world.add_resource(ChosenGatherer::default());
         world.add_resource::<PollutedTiles>(HashSet::new());
+        world.add_resource(rules.clone());
 
-        let dimensions = renderer::get_dimensions();
-
-        let mut side_bar_container_node = Node::new();
+        world.add_resource(CityPowerState::new());
 
+        // selected
         let entity = world
             .create_entity()
-            .with(Transform::visible(
-                dimensions[0] - 640.0 - 52.0,
-                4.0,
-                0.0,
-                22,
-                22,
-                0.0,
-                1.0,
-                1.0,
-            ))
-            .with(Sprite {
-                frame_name: "menu.png".to_string(),
-            })
-            .with(Button::new(
-                "menu".to_string(),
-                ["menu.png".to_string(), "menu_hover.png".to_string()],
-            ))
+            .with(SelectedTile {})
+            .with(Transform::new(0.0, 0.0, 1.0, 64, 64, 0.0, 1.0, 1.0, false))
+            .with(Rect::new())
+            .with(Color([1.0, 1.0, 1.0, 0.6]))
             .build();
+        entities_under_root.push(entity);
 
-        side_bar_container_node.add(entity);
-
+        // tile cursor
         let entity = world
             .create_entity()
-            .with(Transform::visible(30.0, 32.0, 0.0, 260, 32, 0.0, 1.0, 1.0))
-            .with(Sprite {
-                frame_name: "powerbar.png".to_string(),
-            })
+            .with(TileCursor {})
+            .with(Transform::new(0.0, 0.0, 1.5, 64, 64, 0.0, 1.0, 1.0, false))
+            .with(Rect::new())
+            .with(Color([0.0, 1.0, 0.0, 0.35]))
             .build();
-        side_bar_container_node.add(entity);
+        entities_under_root.push(entity);
 
-        world.add_resource(CityPowerState::new());
+        let mut lookup = EntityLookup::new();
+
+        // cloned, as building the entities needs the world
+        let sidebar_layout = world.read_resource::<PlayLayouts>().sidebar.clone();
+        let text_args = {
+            let localization = world.read_resource::<Localization>();
+            vec![
+                ("money", localization.money(rules.start_amount)),
+                ("power", localization.number(-rules.starting_tick as i64)),
+                ("income", localization.money(0)),
+                ("tax", localization.money(0)),
+            ]
+        };
+        let side_bar_container =
+            ui_layout::build_layout(world, &sidebar_layout, &text_args, &mut lookup);
+
+        world
+            .write_storage::<WalletUI>()
+            .insert(*lookup.get("wallet_text").unwrap(), WalletUI {})
+            .unwrap();
 
         // create first power bar
         {
@@ -266,262 +312,28 @@ impl<'a> State for PlayState<'a> {
                 transform_storage: &mut transform_storage,
             };
 
-            let entity = create_power_bar::create(&mut storages, 34.0, 36.0);
-            side_bar_container_node.add(entity);
+            let entity = create_power_bar::create(&mut storages, 34.0, 36.0, &rules);
+            world
+                .write_storage::<Node>()
+                .get_mut(side_bar_container)
+                .unwrap()
+                .add(entity);
         }
 
-        // selected
-        let entity = world
-            .create_entity()
-            .with(SelectedTile {})
-            .with(Transform::new(0.0, 0.0, 1.0, 64, 64, 0.0, 1.0, 1.0, false))
-            .with(Rect::new())
-            .with(Color([1.0, 1.0, 1.0, 0.6]))
-            .build();
-        entities_under_root.push(entity);
-
-        let mut lookup = EntityLookup::new();
-
-        // add additional city button
-        let entity = world
-            .create_entity()
-            .with(Button::new(
-                "power_additional_city".to_string(),
-                [
-                    "power_additional_city.png".to_string(),
-                    "power_additional_city_hover.png".to_string(),
-                ],
-            ))
-            .with(Transform::visible(30.0, 140.0, 0.0, 96, 32, 0.0, 1.0, 1.0))
-            .with(Sprite {
-                frame_name: "power_additional_city.png".to_string(),
-            })
-            .build();
-        side_bar_container_node.add(entity);
-
+        let statistics_panel = create_statistics_panel::create(world);
         lookup
             .entities
-            .insert("power_additional_city".to_string(), entity);
-
-        // tech tree button
-        let entity = world
-            .create_entity()
-            .with(Button::new(
-                "show_tech".to_string(),
-                [
-                    "show_tech.png".to_string(),
-                    "show_tech_hover.png".to_string(),
-                ],
-            ))
-            .with(Transform::visible(112.0, 576.0, 0.0, 96, 32, 0.0, 1.0, 1.0))
-            .with(Sprite {
-                frame_name: "show_tech.png".to_string(),
-            })
-            .build();
-        side_bar_container_node.add(entity);
-
-        let mut gathering_rate_container_node = Node::new();
-
-        {
-            lookup
-                .entities
-                .insert("show_button_entity".to_string(), entity);
-
-            let entities = world.entities();
-            let mut wallet_ui_storage = world.write_storage::<WalletUI>();
-
-            let lazy: Read<LazyUpdate> = Read::<LazyUpdate>::from(world.read_resource::<LazyUpdate>());
-
-            // money text
-            let entity = create_text::create(
-                &entities,
-                &lazy,
-                format!("Wallet: ${}", Wallet::start_amount()),
-                28.0,
-                33.0,
-                430.0,
-                0.0,
-                220,
-                70,
-                Color([1.0, 1.0, 0.0, 1.0]),
-                None,
-            );
-            wallet_ui_storage.insert(entity, WalletUI {}).unwrap();
-            side_bar_container_node.add(entity);
-
-            let gathering_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Gathering Rate".to_string(),
-                22.0,
-                0.0,
-                0.0,
-                0.0,
-                160,
-                32,
-                Color([0.0, 0.6, 0.0, 1.0]),
-                None,
-            );
-            gathering_rate_container_node.add(gathering_rate_label);
-
-            let coal_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Coal: 0".to_string(),
-                20.0,
-                0.0,
-                35.0,
-                0.0,
-                160,
-                32,
-                Color([0.6, 0.6, 0.6, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_coal".to_string(), coal_rate_label.clone());
-            gathering_rate_container_node.add(coal_rate_label);
-
-            let oil_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Oil: 0".to_string(),
-                20.0,
-                0.0,
-                60.0,
-                0.0,
-                160,
-                32,
-                Color([0.8, 0.8, 0.8, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_oil".to_string(), oil_rate_label.clone());
-            gathering_rate_container_node.add(oil_rate_label);
-
-            let hydro_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Hydro: 0".to_string(),
-                20.0,
-                0.0,
-                85.0,
-                0.0,
-                160,
-                32,
-                Color([0.188, 0.57647, 1.0, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_hydro".to_string(), hydro_rate_label.clone());
-            gathering_rate_container_node.add(hydro_rate_label);
-
-            let solar_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Solar: 0".to_string(),
-                20.0,
-                0.0,
-                110.0,
-                0.0,
-                160,
-                32,
-                Color([1.0, 1.0, 0.6196, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_solar".to_string(), solar_rate_label.clone());
-            gathering_rate_container_node.add(solar_rate_label);
-
-            let power_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Power: 0".to_string(),
-                20.0,
-                0.0,
-                135.0,
-                0.0,
-                160,
-                32,
-                Color([0.0, 0.6, 0.0, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_power".to_string(), power_rate_label.clone());
-            gathering_rate_container_node.add(power_rate_label);
-
-            let money_rate_label = create_text::create(
-                &entities,
-                &lazy,
-                "Income: $0, Tax: $0".to_string(),
-                20.0,
-                0.0,
-                160.0,
-                0.0,
-                220,
-                32,
-                Color([1.0, 1.0, 0.0, 1.0]),
-                None,
-            );
-            lookup
-                .entities
-                .insert("gathering_rate_money".to_string(), money_rate_label.clone());
-            gathering_rate_container_node.add(money_rate_label);
-
-            // power gain text
-            let entity = create_text::create(
-                &entities,
-                &lazy,
-                "Power: -40\n1 city".to_string(),
-                24.0,
-                30.0,
-                70.0,
-                0.0,
-                160,
-                64,
-                Color([0.6, 0.0, 0.0, 1.0]),
-                None,
-            );
-
-            println!("Power gain text {:?}", entity);
-            lookup
-                .entities
-                .insert("power_gain_text".to_string(), entity.clone());
-            side_bar_container_node.add(entity);
-        }
-
-        let gathering_rate_container = world
-            .create_entity()
-            .with(Transform::visible(
-                33.0, 210.0, 0.0, 160, 200, 0.0, 1.0, 1.0,
-            ))
-            .with(gathering_rate_container_node)
-            .build();
-
-        side_bar_container_node.add(gathering_rate_container);
-
-        let side_bar_container = world
-            .create_entity()
-            .with(Transform::visible(
-                640.0,
-                0.0,
-                0.0,
-                (dimensions[0] - 640.0) as u16,
-                dimensions[1] as u16,
-                0.0,
-                1.0,
-                1.0,
-            ))
-            .with(side_bar_container_node)
-            .build();
+            .insert("statistics_panel".to_string(), statistics_panel);
+        entities_under_root.push(statistics_panel);
 
+        let notifications_title = world
+            .read_resource::<Localization>()
+            .get("notifications.title");
+        let notification_panel = create_notification_panel::create(world, notifications_title);
         lookup
             .entities
-            .insert("side_bar_container".to_string(), side_bar_container);
+            .insert("notification_panel".to_string(), notification_panel);
+        entities_under_root.push(notification_panel);
 
         entities_under_root.push(side_bar_container);
 
@@ -539,6 +351,7 @@ impl<'a> State for PlayState<'a> {
                 ["resume.png".to_string(), "resume_hover.png".to_string()],
             ))
             .with(Transform::visible(112.0, 576.0, 0.0, 96, 32, 0.0, 1.0, 1.0))
+            .with(get_bottom_row_anchor(112.0))
             .with(Sprite {
                 frame_name: "resume.png".to_string(),
             })
@@ -555,21 +368,50 @@ impl<'a> State for PlayState<'a> {
             .insert("resume_from_upgrades".to_string(), resume_from_upgrades);
         tech_tree_container.add(resume_from_upgrades);
 
+        {
+            let entities = world.entities();
+            let lazy: Read<LazyUpdate> = Read::<LazyUpdate>::from(world.read_resource::<LazyUpdate>());
+            let localization = world.read_resource::<Localization>();
+            let mut node_storage = world.write_storage::<Node>();
+
+            // TechTree shows this while a research start can still be undone
+            let entity = create_text_button::create(
+                &entities,
+                &lazy,
+                &mut node_storage,
+                "undo_research",
+                localization.get("undo.button"),
+                216.0,
+                576.0,
+                0.0,
+                96,
+                32,
+            );
+            lazy.insert(entity, get_bottom_row_anchor(216.0));
+            lookup.entities.insert("undo_research".to_string(), entity);
+            tech_tree_container.add(entity);
+        }
+
         let tech_tree_container_entity = world
             .create_entity()
             .with(Transform::new(
-                640.0,
+                MAP_SIZE,
                 0.0,
                 2.0,
-                (dimensions[0] - 640.0) as u16,
-                dimensions[1] as u16,
+                SIDEBAR_WIDTH as u16,
+                MAP_SIZE as u16,
                 0.0,
                 1.0,
                 1.0,
                 false,
             ))
+            .with(Anchor::new(
+                HorizontalAnchor::Right,
+                VerticalAnchor::Stretch,
+            ))
             .with(Rect {})
             .with(Color([16.0 / 256.0, 14.0 / 256.0, 22.0 / 256.0, 1.0]))
+            .with(UiScaled)
             .with(tech_tree_container)
             .build();
 
@@ -595,41 +437,12 @@ impl<'a> State for PlayState<'a> {
     }
 
     fn update(&mut self, world: &mut World) {
-        match self.state {
-            InternalState::Game | InternalState::Transition => {
-                self.dispatcher.dispatch(&mut world.res)
-            }
-            InternalState::TechTree => self.tech_tree_dispatcher.dispatch(&mut world.res),
-            InternalState::Pause => self.pause_dispatcher.dispatch(&mut world.res),
-            InternalState::End => self.end_dispatcher.dispatch(&mut world.res),
-        }
-    }
-
-    fn handle_custom_change(&mut self, action: &String, world: &mut World) {
-        if action == "tech_tree_pause" {
-            self.state = InternalState::TechTree;
-            world.add_resource(InternalState::TechTree);
-        } else if action == "resume" {
-            self.state = InternalState::Game;
-            world.add_resource(InternalState::Game);
-        } else if action == "pause" {
-            self.state = InternalState::Pause;
-            world.add_resource(InternalState::Pause);
-        } else if action == "end" {
-            self.state = InternalState::End;
-            world.add_resource(InternalState::End);
-        }
-    }
-
-    fn get_ui_to_render(&mut self) -> Option<&mut Ui> {
-        Some(&mut self.ui)
+        let speed = world.read_resource::<GameSpeed>().0;
+        world.write_resource::<DeltaTime>().dt *= speed;
+        self.dispatcher.dispatch(&mut world.res);
     }
 
-    fn create_ui_widgets(&mut self, settings: &mut Settings) -> Option<String> {
-        create_ui(&mut self.ui, &mut self.ids, settings)
-    }
-
-    fn should_render_ui(&self) -> bool {
-        self.state == InternalState::Pause
+    fn resume(&mut self, world: &mut World) {
+        world.add_resource(InternalState::Game);
     }
 }
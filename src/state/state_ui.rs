@@ -0,0 +1,274 @@
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+use achievements::{self, Achievements};
+use components::{Input, Palette, UiCommand};
+use conrod::{text::font, Ui, UiBuilder};
+use key_bindings::{self, InputAction};
+use loader;
+use localization::{self, Localization};
+use mods::{self, ModList};
+use renderer;
+use rules;
+use run_history::{self, HighScoreColumn, RunHistory};
+use settings::{self, Settings};
+use specs::World;
+
+// what the buttons of a screen ask for, the screens themselves only draw
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UiAction {
+    ResumeGame,
+    ShowAchievements,
+    ShowHighScores,
+    ShowDifficulty,
+    ShowMods,
+    ShowControls,
+    ResetControls,
+    // back to settings, or to the state that opened the screen
+    Close,
+}
+
+#[derive(PartialEq)]
+enum UiScreen {
+    Settings,
+    Achievements,
+    HighScores,
+    Difficulty,
+    Mods,
+    Controls,
+}
+
+// The conrod screens shared by states. Settings is the entry point, other screens are navigated to from it.
+pub struct StateUi {
+    pub ui: Ui,
+    screen: UiScreen,
+    // screens opened directly by a state, rather than from settings, hand the close action back to it
+    opened_from_settings: bool,
+    high_score_sort: HighScoreColumn,
+    settings_ids: settings::Ids,
+    achievements_ids: achievements::Ids,
+    run_history_ids: run_history::Ids,
+    rules_ids: rules::Ids,
+    mods_ids: mods::Ids,
+    key_bindings_ids: key_bindings::Ids,
+    // loaded when the mod list is opened, as mods can be installed while the game runs
+    mod_list: ModList,
+    // the action on the controls screen waiting for a key press to bind to
+    waiting_for_key: Option<InputAction>,
+    languages: Vec<(String, String)>,
+    font: font::Id,
+    fallback_font: Option<font::Id>,
+}
+
+impl StateUi {
+    pub fn new() -> Self {
+        let dim = renderer::get_dimensions();
+        let mut ui = UiBuilder::new([dim[0] as f64, dim[1] as f64]).build();
+        let font = ui.fonts
+            .insert_from_file(Path::new(&loader::get_resource_path("resources/MunroSmall.ttf")))
+            .unwrap();
+        let fallback_path = loader::get_resource_path(localization::FALLBACK_FONT);
+        let fallback_font = if fallback_path.exists() {
+            ui.fonts.insert_from_file(fallback_path).ok()
+        } else {
+            None
+        };
+
+        let settings_ids = settings::Ids::new(ui.widget_id_generator());
+        let achievements_ids = achievements::Ids::new(ui.widget_id_generator());
+        let run_history_ids = run_history::Ids::new(ui.widget_id_generator());
+        let rules_ids = rules::Ids::new(ui.widget_id_generator());
+        let mods_ids = mods::Ids::new(ui.widget_id_generator());
+        let key_bindings_ids = key_bindings::Ids::new(ui.widget_id_generator());
+
+        StateUi {
+            ui,
+            screen: UiScreen::Settings,
+            opened_from_settings: true,
+            high_score_sort: HighScoreColumn::default(),
+            settings_ids,
+            achievements_ids,
+            run_history_ids,
+            rules_ids,
+            mods_ids,
+            key_bindings_ids,
+            mod_list: ModList::default(),
+            waiting_for_key: None,
+            languages: localization::get_languages(),
+            font,
+            fallback_font,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.screen = UiScreen::Settings;
+        self.opened_from_settings = true;
+    }
+
+    pub fn show_high_scores(&mut self) {
+        self.screen = UiScreen::HighScores;
+        self.opened_from_settings = false;
+    }
+
+    fn close_screen(&mut self) -> Option<UiCommand> {
+        if self.opened_from_settings {
+            self.screen = UiScreen::Settings;
+            return None;
+        }
+
+        // the state that opened the screen takes over again
+        let command = if self.screen == UiScreen::HighScores {
+            Some(UiCommand::CloseHighScores)
+        } else {
+            None
+        };
+        self.reset();
+        command
+    }
+
+    pub fn create_ui_widgets(&mut self, world: &mut World) -> Option<UiCommand> {
+        // conrod has no per glyph fallback, so scripts MunroSmall lacks switch every widget over
+        self.ui.theme.font_id = if world.read_resource::<Localization>().needs_fallback_font() {
+            self.fallback_font.or(Some(self.font))
+        } else {
+            Some(self.font)
+        };
+
+        match self.screen {
+            UiScreen::Settings => {
+                let (action, language, theme) = {
+                    let mut settings_res = world.write_resource::<Settings>();
+                    let localization_res = world.read_resource::<Localization>();
+                    let previous_language = settings_res.language.clone();
+                    let previous_theme = settings_res.theme;
+                    let action = settings::create_ui(
+                        &mut self.ui,
+                        &mut self.settings_ids,
+                        settings_res.deref_mut(),
+                        localization_res.deref(),
+                        &self.languages,
+                    );
+                    let language = if settings_res.language != previous_language {
+                        Some(settings_res.language.clone())
+                    } else {
+                        None
+                    };
+                    let theme = if settings_res.theme != previous_theme {
+                        Some(settings_res.theme)
+                    } else {
+                        None
+                    };
+                    (action, language, theme)
+                };
+                if let Some(language) = language {
+                    world.add_resource(Localization::load(&language));
+                }
+                if let Some(theme) = theme {
+                    world.add_resource(Palette::new(theme));
+                }
+
+                match action {
+                    Some(UiAction::ResumeGame) => return Some(UiCommand::ResumeGame),
+                    Some(UiAction::ShowAchievements) => self.screen = UiScreen::Achievements,
+                    Some(UiAction::ShowHighScores) => self.screen = UiScreen::HighScores,
+                    Some(UiAction::ShowDifficulty) => self.screen = UiScreen::Difficulty,
+                    Some(UiAction::ShowMods) => {
+                        self.mod_list = ModList::load();
+                        self.screen = UiScreen::Mods;
+                    }
+                    Some(UiAction::ShowControls) => self.screen = UiScreen::Controls,
+                    _ => {}
+                }
+                None
+            }
+            UiScreen::Achievements => {
+                let achievements_res = world.read_resource::<Achievements>();
+                let localization_res = world.read_resource::<Localization>();
+                let action = achievements::create_ui(
+                    &mut self.ui,
+                    &mut self.achievements_ids,
+                    achievements_res.deref(),
+                    localization_res.deref(),
+                );
+                if action == Some(UiAction::Close) {
+                    return self.close_screen();
+                }
+                None
+            }
+            UiScreen::HighScores => {
+                let run_history_res = world.read_resource::<RunHistory>();
+                let localization_res = world.read_resource::<Localization>();
+                let action = run_history::create_ui(
+                    &mut self.ui,
+                    &mut self.run_history_ids,
+                    run_history_res.deref(),
+                    localization_res.deref(),
+                    &mut self.high_score_sort,
+                );
+                if action == Some(UiAction::Close) {
+                    return self.close_screen();
+                }
+                None
+            }
+            UiScreen::Difficulty => {
+                let mut settings_res = world.write_resource::<Settings>();
+                let localization_res = world.read_resource::<Localization>();
+                let action = rules::create_ui(
+                    &mut self.ui,
+                    &mut self.rules_ids,
+                    settings_res.deref_mut(),
+                    localization_res.deref(),
+                );
+                if action == Some(UiAction::Close) {
+                    return self.close_screen();
+                }
+                None
+            }
+            UiScreen::Mods => {
+                let action = mods::create_ui(&mut self.ui, &mut self.mods_ids, &mut self.mod_list);
+                if action == Some(UiAction::Close) {
+                    return self.close_screen();
+                }
+                None
+            }
+            UiScreen::Controls => {
+                let mut settings_res = world.write_resource::<Settings>();
+                let mut input_res = world.write_resource::<Input>();
+                let mut key_bindings = settings_res.key_bindings.clone();
+
+                if let Some(action) = self.waiting_for_key {
+                    if let Some(key) = input_res.captured_key.take() {
+                        // keys that can't be saved leave the binding as it was
+                        if key_bindings::is_bindable(&key) {
+                            key_bindings.set_key(action, key);
+                        }
+                        self.waiting_for_key = None;
+                    }
+                }
+
+                let localization_res = world.read_resource::<Localization>();
+                let action = key_bindings::create_ui(
+                    &mut self.ui,
+                    &mut self.key_bindings_ids,
+                    &key_bindings,
+                    localization_res.deref(),
+                    &mut self.waiting_for_key,
+                );
+                input_res.capturing_key = self.waiting_for_key.is_some();
+
+                if action == Some(UiAction::ResetControls) {
+                    key_bindings.reset();
+                }
+                if key_bindings != settings_res.key_bindings {
+                    input_res.key_bindings = key_bindings.clone();
+                    settings_res.set_key_bindings(key_bindings);
+                }
+
+                if action == Some(UiAction::Close) {
+                    return self.close_screen();
+                }
+                None
+            }
+        }
+    }
+}
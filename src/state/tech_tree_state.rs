@@ -0,0 +1,53 @@
+use specs::{Dispatcher, DispatcherBuilder, World};
+
+use components::InternalState;
+use state::State;
+use systems;
+
+// Pushed over a game while the tech tree is open, which keeps the map and its economy paused
+pub struct TechTreeState<'a> {
+    dispatcher: Dispatcher<'a, 'a>,
+}
+
+impl<'a> TechTreeState<'a> {
+    pub fn new() -> Self {
+        let dispatcher = DispatcherBuilder::new()
+            .with(systems::ButtonHover {}, "button_hover", &[])
+            .with(
+                systems::ToggleTechTree::new(),
+                "toggle_tech_tree",
+                &["button_hover"],
+            )
+            .with(systems::TechTree::new(), "tech_tree", &[])
+            .with(systems::Tutorial::new(), "tutorial", &[])
+            .with(systems::PulseSystem {}, "pulse", &[])
+            .with(
+                systems::TextAbsoluteCache {},
+                "text_absolute_cache",
+                &["tutorial", "tech_tree", "toggle_tech_tree"],
+            )
+            .with(
+                systems::NotificationCenter::new(),
+                "notification_center",
+                &["tech_tree"],
+            )
+            .with(
+                systems::BlockMapTiles {},
+                "block_map_tiles",
+                &["notification_center", "toggle_tech_tree"],
+            )
+            .build();
+
+        TechTreeState { dispatcher }
+    }
+}
+
+impl<'a> State for TechTreeState<'a> {
+    fn setup(&mut self, world: &mut World) {
+        world.add_resource(InternalState::TechTree);
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.dispatcher.dispatch(&mut world.res);
+    }
+}
@@ -0,0 +1,147 @@
+use gfx_glyph::HorizontalAlign;
+use specs::{Entity, Read, ReadStorage, System, WriteStorage};
+
+use components::{ui::UiScaled, Anchor, EntityLookup, Node, Text, Transform, Viewport};
+
+/**
+ * Moves anchored entities to where their anchor puts them in the current viewport, and scales
+ * UiScaled ones to the ui scale. Runs right before rendering rather than in a state's dispatcher,
+ * so it also covers entities created during the frame and windows resized while an overlay is open
+ */
+pub struct AnchorLayout;
+
+impl<'a> System<'a> for AnchorLayout {
+    type SystemData = (
+        ReadStorage<'a, Anchor>,
+        Read<'a, EntityLookup>,
+        ReadStorage<'a, Node>,
+        ReadStorage<'a, Text>,
+        WriteStorage<'a, Transform>,
+        ReadStorage<'a, UiScaled>,
+        Read<'a, Viewport>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            anchor_storage,
+            entity_lookup_storage,
+            node_storage,
+            text_storage,
+            mut transform_storage,
+            ui_scaled_storage,
+            viewport_storage,
+        ) = data;
+
+        let root_entity = match entity_lookup_storage.entities.get("root") {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let mut storages = LayoutStorages {
+            anchor_storage: &anchor_storage,
+            node_storage: &node_storage,
+            text_storage: &text_storage,
+            transform_storage: &mut transform_storage,
+            ui_scaled_storage: &ui_scaled_storage,
+            ui_scale: viewport_storage.ui_scale,
+        };
+        layout_children(root_entity, &viewport_storage.bounds, false, &mut storages);
+    }
+}
+
+struct LayoutStorages<'a, 'b: 'a> {
+    anchor_storage: &'a ReadStorage<'b, Anchor>,
+    node_storage: &'a ReadStorage<'b, Node>,
+    text_storage: &'a ReadStorage<'b, Text>,
+    transform_storage: &'a mut WriteStorage<'b, Transform>,
+    ui_scaled_storage: &'a ReadStorage<'b, UiScaled>,
+    ui_scale: f32,
+}
+
+// parent_moved marks the absolute positions below a moved node as stale
+fn layout_children(
+    entity: Entity,
+    parent: &[f32; 4],
+    parent_moved: bool,
+    storages: &mut LayoutStorages,
+) {
+    let children = match storages.node_storage.get(entity) {
+        Some(node) => node.entities.clone(),
+        None => return,
+    };
+
+    for child in children {
+        let moved = match storages.transform_storage.get_mut(child) {
+            Some(transform) => {
+                let scaled = storages.ui_scaled_storage.get(child).is_some()
+                    && apply_ui_scale(storages.ui_scale, transform);
+                let anchored = match storages.anchor_storage.get(child) {
+                    Some(anchor) => {
+                        let align = storages.text_storage.get(child).map(|text| text.align);
+                        apply_anchor(anchor, parent, align, transform)
+                    }
+                    None => false,
+                };
+                let moved = scaled || anchored;
+                if moved || parent_moved {
+                    transform.dirty_pos = true;
+                }
+                moved || parent_moved
+            }
+            None => parent_moved,
+        };
+
+        let bounds = match storages.transform_storage.get(child) {
+            Some(transform) => [0.0, 0.0, transform.size.x as f32, transform.size.y as f32],
+            None => *parent,
+        };
+        layout_children(child, &bounds, moved, storages);
+    }
+}
+
+// returns whether the scale changed
+fn apply_ui_scale(ui_scale: f32, transform: &mut Transform) -> bool {
+    if transform.scale.x == ui_scale && transform.scale.y == ui_scale {
+        return false;
+    }
+
+    transform.scale.x = ui_scale;
+    transform.scale.y = ui_scale;
+    true
+}
+
+// returns whether the transform changed
+fn apply_anchor(
+    anchor: &Anchor,
+    parent: &[f32; 4],
+    align: Option<HorizontalAlign>,
+    transform: &mut Transform,
+) -> bool {
+    // placed by the size it is drawn at, which the size only matches when unscaled
+    let (scale_x, scale_y) = (transform.scale.x, transform.scale.y);
+    let placed = anchor.place(
+        parent,
+        transform.size.x as f32 * scale_x,
+        transform.size.y as f32 * scale_y,
+    );
+    // aligned text is drawn from its center or right edge rather than the left
+    let x = match align {
+        Some(HorizontalAlign::Center) => placed[0] + placed[2] / 2.0,
+        Some(HorizontalAlign::Right) => placed[0] + placed[2],
+        _ => placed[0],
+    };
+    let (w, h) = (
+        (placed[2] / scale_x).round() as u16,
+        (placed[3] / scale_y).round() as u16,
+    );
+
+    let pos = *transform.get_pos();
+    if pos.x == x && pos.y == placed[1] && transform.size.x == w && transform.size.y == h {
+        return false;
+    }
+
+    transform.set_pos2(x, placed[1]);
+    transform.size.x = w;
+    transform.size.y = h;
+    true
+}
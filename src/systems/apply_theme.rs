@@ -0,0 +1,94 @@
+use cgmath::Vector2;
+use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+use components::{get_color_from_status, ui::TechStatusMark, upgrade::{Status, Upgrade}, Color,
+                 Palette, PaletteColor, Shape, ThemeColor};
+
+// the outline of a mark, in a square of tech_tree::MARK_SIZE
+fn get_mark_points(status: Status) -> Option<Vec<Vector2<f32>>> {
+    let points: &[(f32, f32)] = match status {
+        Status::Researched => &[(1.0, 6.0), (4.0, 10.0), (11.0, 1.0)],
+        // the shackle, then the body of the lock
+        Status::Locked => &[
+            (3.0, 6.0),
+            (3.0, 2.0),
+            (9.0, 2.0),
+            (9.0, 6.0),
+            (11.0, 6.0),
+            (11.0, 11.0),
+            (1.0, 11.0),
+            (1.0, 6.0),
+            (9.0, 6.0),
+        ],
+        _ => return None,
+    };
+
+    Some(points.iter().map(|&(x, y)| Vector2::new(x, y)).collect())
+}
+
+/**
+ * Colors entities from the palette of the current theme: those with a ThemeColor, and tech tree
+ * nodes by their status. Runs right before rendering like AnchorLayout, so a theme picked in the
+ * settings shows straight away, overlays and all
+ */
+pub struct ApplyTheme;
+
+impl<'a> System<'a> for ApplyTheme {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Color>,
+        Read<'a, Palette>,
+        WriteStorage<'a, Shape>,
+        WriteStorage<'a, TechStatusMark>,
+        ReadStorage<'a, ThemeColor>,
+        ReadStorage<'a, Upgrade>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut color_storage,
+            palette_storage,
+            mut shape_storage,
+            mut tech_status_mark_storage,
+            theme_color_storage,
+            upgrade_storage,
+        ) = data;
+
+        for (color, theme_color) in (&mut color_storage, &theme_color_storage).join() {
+            color.0 = palette_storage.get(theme_color.0);
+        }
+
+        for (color, upgrade) in (&mut color_storage, &upgrade_storage).join() {
+            color.0 = palette_storage.get(get_color_from_status(&upgrade.status));
+        }
+
+        for (entity, mark) in (&*entities, &mut tech_status_mark_storage).join() {
+            let status = match upgrade_storage.get(mark.upgrade) {
+                Some(upgrade) => upgrade.status,
+                None => continue,
+            };
+            let drawn = Some((status, palette_storage.theme));
+            if mark.drawn == drawn {
+                continue;
+            }
+            mark.drawn = drawn;
+
+            match get_mark_points(status) {
+                Some(points) => {
+                    let color = if status == Status::Researched {
+                        PaletteColor::TechResearched
+                    } else {
+                        PaletteColor::TechResearchable
+                    };
+                    shape_storage
+                        .insert(entity, Shape::new_line(points, palette_storage.get(color)))
+                        .unwrap();
+                }
+                None => {
+                    shape_storage.remove(entity);
+                }
+            }
+        }
+    }
+}
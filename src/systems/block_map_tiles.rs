@@ -0,0 +1,24 @@
+use components::{Button, MapBlockers, Tile};
+use specs::{Join, Read, ReadStorage, System, WriteStorage};
+
+// Keeps the tiles from taking clicks while an overlay covers the map, see MapBlockers
+pub struct BlockMapTiles;
+
+impl<'a> System<'a> for BlockMapTiles {
+    type SystemData = (
+        WriteStorage<'a, Button>,
+        Read<'a, MapBlockers>,
+        ReadStorage<'a, Tile>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut button_storage, map_blockers_storage, tile_storage) = data;
+
+        let blocked = map_blockers_storage.is_blocked();
+        for (_, button) in (&tile_storage, &mut button_storage).join() {
+            if button.disabled != blocked {
+                button.set_disabled(blocked);
+            }
+        }
+    }
+}
@@ -1,16 +1,97 @@
 use components::ui::WalletUI;
-use components::{ui::TutorialUI, upgrade::Buff, Actions, AnimationSheet, Button, ClickSound,
-                 Color, EffectedByPollutionTiles, EntityLookup, Gatherer, GathererPositions,
-                 GathererType, Input, Node, PollutedTiles, ResearchedBuffs, SelectedTile, Sprite, Text, Tile,
-                 TileNodes, TileType, Transform, TutorialStep, Wallet};
-use entities::tutorial;
-use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
+use components::{upgrade::Buff, AnimationSheet, Button, ChosenGatherer, ClickSound, Color,
+                 EffectedByPollutionTiles, EntityLookup, EventChannel, GameEvent, Gatherer,
+                 GathererPositions, GathererType, Input, Node, NotificationTarget, Notifications,
+                 PollutedTiles, ResearchedBuffs, RunStats, SelectedTile, Sprite, Text, Tile,
+                 TileNodes, Transform, Wallet};
+use entities::recursive_delete;
+use key_bindings::InputAction;
+use localization::Localization;
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage};
 use std::ops::{Deref, DerefMut};
-use systems::logic;
+use systems::{logic, UNDO_GRACE_PERIOD};
+
+// the build ui buttons, with the gatherer they build and the key that chooses them
+pub const BUILD_BUTTONS: [(&str, GathererType, InputAction); 4] = [
+    ("build_coal", GathererType::Coal, InputAction::BuildCoal),
+    ("build_oil", GathererType::Oil, InputAction::BuildOil),
+    ("build_solar", GathererType::Solar, InputAction::BuildSolar),
+    ("build_hydro", GathererType::Hydro, InputAction::BuildHydro),
+];
+
+// a build that can still be undone, with everything it changed
+struct BuildRecord {
+    time: f32,
+    gatherer_entity: Entity,
+    gatherer_type: GathererType,
+    tile: (i32, i32),
+    cost: i32,
+    // pollution sprites the build added, with the tiles they mark in PollutedTiles
+    pollution: Vec<(Entity, (i32, i32))>,
+    // neighbours that only have a gatherer of the same type next to them because of the build
+    adjacent_entities: Vec<Entity>,
+}
 
-pub struct BuildGatherer;
+pub struct BuildGatherer {
+    chosen_preview: Vec<Entity>,
+    chosen_preview_at: Option<(i32, i32, GathererType)>,
+    recent_builds: Vec<BuildRecord>,
+}
 
 impl BuildGatherer {
+    pub fn new() -> BuildGatherer {
+        BuildGatherer {
+            chosen_preview: Vec::new(),
+            chosen_preview_at: None,
+            recent_builds: Vec::new(),
+        }
+    }
+
+    // builds from a previous run, or past the grace period, can no longer be undone
+    fn expire_builds(&mut self, entities: &Entities, time: f32) {
+        self.recent_builds.retain(|build| {
+            entities.is_alive(build.gatherer_entity) && time >= build.time
+                && time - build.time <= UNDO_GRACE_PERIOD
+        });
+    }
+
+    // takes back the latest build, returning its cost to refund
+    fn undo_build(
+        &mut self,
+        entities: &Entities,
+        game_events: &mut EventChannel<GameEvent>,
+        gatherer_storage: &mut WriteStorage<Gatherer>,
+        gatherer_positions: &mut GathererPositions,
+        nodes_storage: &WriteStorage<Node>,
+        polluted_tiles: &mut PollutedTiles,
+        run_stats: &mut RunStats,
+    ) -> Option<i32> {
+        let build = match self.recent_builds.pop() {
+            Some(build) => build,
+            None => return None,
+        };
+
+        recursive_delete(entities, nodes_storage, &build.gatherer_entity);
+        gatherer_positions.gatherers.remove(&build.tile);
+        for (entity, tile) in build.pollution {
+            entities.delete(entity).unwrap();
+            polluted_tiles.remove(&tile);
+        }
+        for entity in build.adjacent_entities {
+            if let Some(gatherer) = gatherer_storage.get_mut(entity) {
+                gatherer.has_adjancent_of_same_type = false;
+            }
+        }
+
+        game_events.write(GameEvent::GathererRemoved {
+            gatherer_type: build.gatherer_type,
+            tile: build.tile,
+        });
+        run_stats.remove_gatherer_built(&build.gatherer_type);
+        run_stats.spent_on_gatherers -= build.cost;
+        Some(build.cost)
+    }
+
     fn remove_effected_by_pollution_tiles_entities(
         &self,
         entities: &Entities,
@@ -21,31 +102,89 @@ impl BuildGatherer {
         }
         effected_by_pollution_tiles.clear();
     }
+
+    fn remove_chosen_preview(&mut self, entities: &Entities) {
+        for entity in &self.chosen_preview {
+            entities.delete(*entity).unwrap();
+        }
+        self.chosen_preview.clear();
+        self.chosen_preview_at = None;
+    }
+
+    // warning sprites on the tiles a gatherer built on the selected tile would pollute
+    fn create_pollution_preview(
+        &self,
+        gatherer_type: GathererType,
+        selected_tile: (i32, i32),
+        entities: &Entities,
+        lookup: &EntityLookup,
+        tile_nodes: &TileNodes,
+        nodes_storage: &mut WriteStorage<Node>,
+        sprite_storage: &mut WriteStorage<Sprite>,
+        transform_storage: &mut WriteStorage<Transform>,
+    ) -> Vec<Entity> {
+        let mut preview = Vec::new();
+        for (col, row) in logic::get_polluted_tiles(gatherer_type, selected_tile, tile_nodes) {
+            let entity = entities.create();
+            transform_storage
+                .insert(
+                    entity,
+                    Transform::visible(
+                        col as f32 * Tile::get_size(),
+                        row as f32 * Tile::get_size(),
+                        4.0,
+                        64,
+                        64,
+                        0.0,
+                        1.0,
+                        1.0,
+                    ),
+                )
+                .unwrap();
+            sprite_storage
+                .insert(
+                    entity,
+                    Sprite {
+                        frame_name: "pollution_warning.png".to_string(),
+                    },
+                )
+                .unwrap();
+
+            preview.push(entity.clone());
+
+            let node = logic::get_root(lookup, nodes_storage);
+            node.add(entity);
+        }
+
+        preview
+    }
 }
 
 impl<'a> System<'a> for BuildGatherer {
     type SystemData = (
         Entities<'a>,
-        Write<'a, Actions>,
         WriteStorage<'a, AnimationSheet>,
         WriteStorage<'a, Button>,
+        Write<'a, ChosenGatherer>,
         Write<'a, ClickSound>,
         WriteStorage<'a, Color>,
         WriteStorage<'a, EffectedByPollutionTiles>,
         Read<'a, EntityLookup>,
+        Write<'a, EventChannel<GameEvent>>,
         WriteStorage<'a, Gatherer>,
         Write<'a, GathererPositions>,
         Read<'a, Input>,
+        Read<'a, Localization>,
         WriteStorage<'a, Node>,
+        Write<'a, Notifications>,
         Write<'a, PollutedTiles>,
         Read<'a, TileNodes>,
         Read<'a, ResearchedBuffs>,
+        Write<'a, RunStats>,
         ReadStorage<'a, SelectedTile>,
         WriteStorage<'a, Sprite>,
         WriteStorage<'a, Text>,
         WriteStorage<'a, Transform>,
-        Write<'a, TutorialStep>,
-        ReadStorage<'a, TutorialUI>,
         Write<'a, Wallet>,
         ReadStorage<'a, WalletUI>,
     );
@@ -53,26 +192,28 @@ impl<'a> System<'a> for BuildGatherer {
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            mut actions_storage,
             mut animation_sheet_storage,
             mut button_storage,
+            mut chosen_gatherer_storage,
             mut click_sound_storage,
             mut color_storage,
             mut effected_by_pollution_tiles_storage,
             entity_lookup_storage,
+            mut game_events_storage,
             mut gatherer_storage,
             mut gatherer_positions_storage,
             input_storage,
+            localization_storage,
             mut nodes_storage,
+            mut notifications_storage,
             mut polluted_tiles_storage,
             tile_nodes_storage,
             researched_buffs_storage,
+            mut run_stats_storage,
             selected_tile_storage,
             mut sprite_storage,
             mut text_storage,
             mut transform_storage,
-            tutorial_step_storage,
-            tutorial_ui_storage,
             mut wallet_storage,
             wallet_ui_storage,
         ) = data;
@@ -81,14 +222,18 @@ impl<'a> System<'a> for BuildGatherer {
         let click_sound: &mut ClickSound = click_sound_storage.deref_mut();
         let wallet: &mut Wallet = wallet_storage.deref_mut();
         let lookup = entity_lookup_storage.deref();
+        // gatherer picked with its key. pressing the key again, or the select key, builds it
+        let chosen_gatherer: &mut ChosenGatherer = chosen_gatherer_storage.deref_mut();
 
         let mut selected_tile_x = 0.0;
         let mut selected_tile_y = 0.0;
+        let mut selected_tile_visible = false;
 
         for (_, transform) in (&selected_tile_storage, &mut transform_storage).join() {
             if transform.visible {
                 selected_tile_x = transform.get_pos().x;
                 selected_tile_y = transform.get_pos().y;
+                selected_tile_visible = true;
             }
         }
 
@@ -99,59 +244,33 @@ impl<'a> System<'a> for BuildGatherer {
 
         let mut button_pressed = false;
         let mut gatherer_type = None;
+        // the build ui only has buttons for what can be built on the selected tile
+        let mut shown_types = Vec::new();
         for (button, effected_by_pollution_tiles) in (
             &mut button_storage,
             &mut effected_by_pollution_tiles_storage,
         ).join()
         {
+            let button_type = match BUILD_BUTTONS.iter().find(|build| build.0 == button.name) {
+                Some(build) => build.1,
+                None => continue,
+            };
+            if !button.disabled {
+                shown_types.push(button_type);
+            }
+
             if button.name != "build_solar" {
                 if button.mouse_is_over && !effected_by_pollution_tiles.has_entities() {
-                    for i in -1..2 {
-                        for j in -1..2 {
-                            if button.name == "build_hydro" && (i != 0 || j != 0) {
-                                continue;
-                            } else if button.name != "build_hydro" && i == 0 && j == 0 {
-                                continue;
-                            }
-
-                            if let Some(&(tile_type, _)) = tile_nodes
-                                .nodes
-                                .get(&(selected_tile_col + i, selected_tile_row + j))
-                            {
-                                if tile_type != TileType::Open {
-                                    let entity = entities.create();
-                                    transform_storage
-                                        .insert(
-                                            entity,
-                                            Transform::visible(
-                                                selected_tile_x + (i as f32) * Tile::get_size(),
-                                                selected_tile_y + (j as f32) * Tile::get_size(),
-                                                4.0,
-                                                64,
-                                                64,
-                                                0.0,
-                                                1.0,
-                                                1.0,
-                                            ),
-                                        )
-                                        .unwrap();
-                                    sprite_storage
-                                        .insert(
-                                            entity,
-                                            Sprite {
-                                                frame_name: "pollution_warning.png".to_string(),
-                                            },
-                                        )
-                                        .unwrap();
-
-                                    effected_by_pollution_tiles.tiles.push(entity.clone());
-
-                                    let node = logic::get_root(&lookup, &mut nodes_storage);
-                                    node.add(entity);
-                                }
-                            }
-                        }
-                    }
+                    effected_by_pollution_tiles.tiles = self.create_pollution_preview(
+                        button_type,
+                        (selected_tile_col, selected_tile_row),
+                        &entities,
+                        lookup,
+                        tile_nodes,
+                        &mut nodes_storage,
+                        &mut sprite_storage,
+                        &mut transform_storage,
+                    );
                 } else if !button.mouse_is_over && effected_by_pollution_tiles.has_entities() {
                     self.remove_effected_by_pollution_tiles_entities(
                         &entities,
@@ -160,55 +279,147 @@ impl<'a> System<'a> for BuildGatherer {
                 }
             }
 
-            if button.name == "build_coal" && button.clicked(&input) {
+            if button.clicked(&input) {
                 button_pressed = true;
-                gatherer_type = Some(GathererType::Coal);
-            } else if button.name == "build_oil" && button.clicked(&input) {
-                button_pressed = true;
-                gatherer_type = Some(GathererType::Oil);
-            } else if button.name == "build_solar" && button.clicked(&input) {
-                button_pressed = true;
-                gatherer_type = Some(GathererType::Solar);
-            } else if button.name == "build_hydro" && button.clicked(&input) {
+                gatherer_type = Some(button_type);
+            }
+        }
+
+        if !selected_tile_visible {
+            chosen_gatherer.0 = None;
+        }
+
+        // keyboard building takes two presses, so the pollution preview is seen before committing
+        if !button_pressed {
+            for &(_, build_type, action) in BUILD_BUTTONS.iter() {
+                if input.action_pressed(action) && shown_types.contains(&build_type) {
+                    if chosen_gatherer.0 == Some(build_type) {
+                        button_pressed = true;
+                        gatherer_type = Some(build_type);
+                    } else {
+                        chosen_gatherer.0 = Some(build_type);
+                    }
+                }
+            }
+        }
+
+        if let Some(chosen) = chosen_gatherer.0 {
+            if !shown_types.contains(&chosen) {
+                chosen_gatherer.0 = None;
+            } else if !button_pressed && input.action_pressed(InputAction::SelectTile) {
                 button_pressed = true;
-                gatherer_type = Some(GathererType::Hydro);
+                gatherer_type = Some(chosen);
+            }
+        }
+
+        // the preview follows the selected tile, which moves with the tile cursor
+        let chosen_preview_at = chosen_gatherer.0
+            .map(|chosen| (selected_tile_col, selected_tile_row, chosen));
+        if chosen_preview_at != self.chosen_preview_at {
+            self.remove_chosen_preview(&entities);
+            if let Some(chosen) = chosen_gatherer.0 {
+                self.chosen_preview = self.create_pollution_preview(
+                    chosen,
+                    (selected_tile_col, selected_tile_row),
+                    &entities,
+                    lookup,
+                    tile_nodes,
+                    &mut nodes_storage,
+                    &mut sprite_storage,
+                    &mut transform_storage,
+                );
+                self.chosen_preview_at = chosen_preview_at;
             }
+        }
 
-            if button_pressed {
-                click_sound.play = true;
+        if let Some(chosen) = chosen_gatherer.0 {
+            for (button, sprite) in (&button_storage, &mut sprite_storage).join() {
+                if BUILD_BUTTONS
+                    .iter()
+                    .any(|build| build.0 == button.name && build.1 == chosen)
+                {
+                    sprite.frame_name = button.get_hover_frame().clone();
+                }
+            }
+        }
+
+        if button_pressed {
+            click_sound.play = true;
+        }
+
+        self.expire_builds(&entities, run_stats_storage.time_survived);
+        let mut undo_pressed = input.ctrl_held() && input.action_pressed(InputAction::Undo);
+        for button in (&mut button_storage).join() {
+            if button.name == "undo_build" && button.clicked(&input) {
+                undo_pressed = true;
+            }
+        }
+        if undo_pressed {
+            match self.undo_build(
+                &entities,
+                game_events_storage.deref_mut(),
+                &mut gatherer_storage,
+                gatherer_positions_storage.deref_mut(),
+                &nodes_storage,
+                polluted_tiles_storage.deref_mut(),
+                run_stats_storage.deref_mut(),
+            ) {
+                Some(cost) => {
+                    click_sound.play = true;
+                    wallet.add_money(cost);
+                    logic::update_text(
+                        localization_storage.format(
+                            "sidebar.wallet",
+                            &[("money", localization_storage.money(wallet.get_money()))],
+                        ),
+                        &mut text_storage,
+                        &wallet_ui_storage,
+                    );
+                }
+                None => notifications_storage
+                    .error(localization_storage.get("error.nothing_to_undo"), None),
             }
         }
 
         let researched_buffs = researched_buffs_storage.deref();
 
         let mut create = false;
+        let mut cost = 0;
         // spend the money, and hide selected tile
         if button_pressed {
-            let mut amount = gatherer_type.unwrap().clone().get_build_cost();
+            cost = gatherer_type.unwrap().clone().get_build_cost();
             if gatherer_type.unwrap() == GathererType::Solar
                 && researched_buffs
                     .0
                     .contains_key(&Buff::PurchaseSolarCellCompany)
             {
-                amount -= amount * 20 / 100;
+                cost -= cost * 20 / 100;
             }
             for (_, transform) in (&selected_tile_storage, &mut transform_storage).join() {
                 if transform.visible {
-                    if wallet.spend(amount) {
+                    if wallet.spend(cost) {
                         transform.visible = false;
                         create = true;
+                        run_stats_storage.spent_on_gatherers += cost;
 
                         selected_tile_x = transform.get_pos().x;
                         selected_tile_y = transform.get_pos().y;
                         logic::update_text(
-                            format!("Wallet: ${}", wallet.get_money()),
+                            localization_storage.format(
+                                "sidebar.wallet",
+                                &[("money", localization_storage.money(wallet.get_money()))],
+                            ),
                             &mut text_storage,
                             &wallet_ui_storage,
                         );
                     } else {
-                        actions_storage.dispatch(
-                            "display_error".to_string(),
-                            "Not enough money to build".to_string(),
+                        let pos = transform.get_pos();
+                        notifications_storage.error(
+                            localization_storage.get("error.build_money"),
+                            Some(NotificationTarget::Tile(
+                                (pos.x / Tile::get_size()) as i32,
+                                (pos.y / Tile::get_size()) as i32,
+                            )),
                         );
                     }
                 }
@@ -216,101 +427,81 @@ impl<'a> System<'a> for BuildGatherer {
         }
 
         if create {
-            tutorial::clear_ui(
-                &entities,
-                &tutorial_step_storage,
-                &tutorial_ui_storage,
-                &nodes_storage,
-                TutorialStep::BuildCoal(0.0, 0.0),
-            );
-            tutorial::clear_ui(
-                &entities,
-                &tutorial_step_storage,
-                &tutorial_ui_storage,
-                &nodes_storage,
-                TutorialStep::ResourcesSold,
-            );
-
             for effected_by_pollution_tiles in (&mut effected_by_pollution_tiles_storage).join() {
                 self.remove_effected_by_pollution_tiles_entities(
                     &entities,
                     effected_by_pollution_tiles,
                 );
             }
+            chosen_gatherer.0 = None;
+            self.remove_chosen_preview(&entities);
 
             // create gatherer
             let gatherer_type = gatherer_type.unwrap();
 
-            let mut pollution = 0i32;
+            let polluted = logic::get_polluted_tiles(
+                gatherer_type,
+                (selected_tile_col, selected_tile_row),
+                tile_nodes,
+            );
+            let pollution = polluted.len() as i32 * gatherer_type.get_pollution_amount();
 
-            // calculate pollution, and add pollution sprites on top
+            // add pollution sprites on top
             // this will at present overlap polluting animations
-            // Solar doesn't pollute
-            if gatherer_type != GathererType::Solar {
-                for i in -1..2 {
-                    for j in -1..2 {
-                        if gatherer_type == GathererType::Hydro && (i != 0 || j != 0) {
-                            continue;
-                        } else if gatherer_type != GathererType::Hydro && i == 0 && j == 0 {
-                            continue;
-                        }
-                        if let Some(&(tile_type, _)) = tile_nodes
-                            .nodes
-                            .get(&(selected_tile_col + i, selected_tile_row + j))
-                        {
-                            // if its a non open tile
-                            if tile_type != TileType::Open {
-                                pollution += gatherer_type.get_pollution_amount();
-
-                                let selected_tile_x = selected_tile_x + (i as f32) * Tile::get_size();
-                                let selected_tile_y = selected_tile_y + (j as f32) * Tile::get_size();
-
-                                if polluted_tiles_storage.contains(&(selected_tile_x as i32, selected_tile_y as i32)) {
-                                    continue
-                                }
-
-                                polluted_tiles_storage.insert((selected_tile_x as i32, selected_tile_y as i32));
-
-                                let pollution_entity = entities.create();
-                                transform_storage
-                                    .insert(
-                                        pollution_entity,
-                                        Transform::visible(
-                                            selected_tile_x,
-                                            selected_tile_y,
-                                            3.0,
-                                            64,
-                                            64,
-                                            0.0,
-                                            1.0,
-                                            1.0,
-                                        ),
-                                    )
-                                    .unwrap();
-                                let mut animation = AnimationSheet::new(0.1);
-                                animation.add_animation(
-                                    "default".to_string(),
-                                    ["01", "02", "03", "04", "05", "06", "07", "08", "09", "10", "11", "12"]
-                                        .iter()
-                                        .map(|n| format!("pollution_grey_{}.png", n))
-                                        .collect(),
-                                );
-                                animation.set_current_animation("default".to_string());
-                                animation_sheet_storage
-                                    .insert(pollution_entity, animation)
-                                    .unwrap();
-                                color_storage
-                                    .insert(pollution_entity, Color([1.0, 1.0, 1.0, 1.0]))
-                                    .unwrap();
-
-                                let node = logic::get_root(&lookup, &mut nodes_storage);
-                                node.add(pollution_entity);
-                            }
-                        }
-                    }
+            let mut pollution_entities = Vec::new();
+            for (col, row) in polluted {
+                let polluted_x = col * Tile::get_size() as i32;
+                let polluted_y = row * Tile::get_size() as i32;
+
+                if polluted_tiles_storage.contains(&(polluted_x, polluted_y)) {
+                    continue
                 }
+
+                polluted_tiles_storage.insert((polluted_x, polluted_y));
+
+                let pollution_entity = entities.create();
+                transform_storage
+                    .insert(
+                        pollution_entity,
+                        Transform::visible(
+                            polluted_x as f32,
+                            polluted_y as f32,
+                            3.0,
+                            64,
+                            64,
+                            0.0,
+                            1.0,
+                            1.0,
+                        ),
+                    )
+                    .unwrap();
+                let mut animation = AnimationSheet::new(0.1);
+                animation.add_animation(
+                    "default".to_string(),
+                    ["01", "02", "03", "04", "05", "06", "07", "08", "09", "10", "11", "12"]
+                        .iter()
+                        .map(|n| format!("pollution_grey_{}.png", n))
+                        .collect(),
+                );
+                animation.set_current_animation("default".to_string());
+                animation_sheet_storage
+                    .insert(pollution_entity, animation)
+                    .unwrap();
+                color_storage
+                    .insert(pollution_entity, Color([1.0, 1.0, 1.0, 1.0]))
+                    .unwrap();
+
+                let node = logic::get_root(&lookup, &mut nodes_storage);
+                node.add(pollution_entity);
+                pollution_entities.push((pollution_entity, (polluted_x, polluted_y)));
             }
 
+            run_stats_storage.add_gatherer_built(&gatherer_type);
+            game_events_storage.write(GameEvent::GathererBuilt {
+                gatherer_type,
+                tile: (selected_tile_col, selected_tile_row),
+            });
+
             let gatherer = Gatherer::new(gatherer_type, pollution);
             let mut anim = AnimationSheet::new(0.35);
             anim.add_animation("default".to_string(), gatherer.gatherer_type.get_frames());
@@ -345,6 +536,7 @@ impl<'a> System<'a> for BuildGatherer {
 
             // check for adjacent gatherers
             let mut at_least_one_adjacent = false;
+            let mut adjacent_entities = Vec::new();
             for i in -1..2 {
                 for j in -1..2 {
                     if i == 0 && j == 0 {
@@ -355,10 +547,11 @@ impl<'a> System<'a> for BuildGatherer {
                         .get(&(selected_tile_col + i, selected_tile_row + j))
                     {
                         if gatherer_type == other_gatherer_type {
-                            gatherer_storage
-                                .get_mut(entity)
-                                .unwrap()
-                                .has_adjancent_of_same_type = true;
+                            let other_gatherer = gatherer_storage.get_mut(entity).unwrap();
+                            if !other_gatherer.has_adjancent_of_same_type {
+                                adjacent_entities.push(entity);
+                            }
+                            other_gatherer.has_adjancent_of_same_type = true;
                             at_least_one_adjacent = true;
                         }
                     }
@@ -374,6 +567,26 @@ impl<'a> System<'a> for BuildGatherer {
 
             let node = logic::get_root(&lookup, &mut nodes_storage);
             node.add(gatherer_entity);
+
+            self.recent_builds.push(BuildRecord {
+                time: run_stats_storage.time_survived,
+                gatherer_entity,
+                gatherer_type,
+                tile: (selected_tile_col, selected_tile_row),
+                cost,
+                pollution: pollution_entities,
+                adjacent_entities,
+            });
+        }
+
+        // the undo button only shows while there is a build to take back
+        let can_undo = !self.recent_builds.is_empty();
+        let undo_entity = *lookup.get("undo_build").unwrap();
+        if let Some(transform) = transform_storage.get_mut(undo_entity) {
+            transform.visible = can_undo;
+        }
+        if let Some(button) = button_storage.get_mut(undo_entity) {
+            button.set_disabled(!can_undo);
         }
     }
 }
@@ -0,0 +1,210 @@
+use components::{Button, ChosenGatherer, Color, EntityLookup, Gatherer, GathererPositions,
+                 GatheringRate, GathererType, Node, PowerBar, Rect, ResearchedBuffs, SelectedTile,
+                 Text, Tile, TileNodes, Transform};
+use entities::{create_build_ui, create_tooltip, recursive_delete};
+use localization::Localization;
+use renderer;
+use rules::Rules;
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, WriteStorage};
+use std::ops::Deref;
+use systems::{logic, BUILD_BUTTONS, POWER_FACTOR};
+
+const PREVIEW_WIDTH: u16 = 280;
+const PREVIEW_HEIGHT: u16 = 190;
+
+// shows what a build would do to the economy, for the hovered build button or the gatherer
+// chosen with its key
+pub struct BuildPreview {
+    preview_entity: Option<Entity>,
+    shown: Option<(f32, f32, String)>,
+}
+
+impl BuildPreview {
+    pub fn new() -> BuildPreview {
+        BuildPreview {
+            preview_entity: None,
+            shown: None,
+        }
+    }
+
+    fn close_preview(&mut self, entities: &Entities, node_storage: &WriteStorage<Node>) {
+        if let Some(preview_entity) = self.preview_entity {
+            recursive_delete(entities, node_storage, &preview_entity);
+        }
+        self.preview_entity = None;
+        self.shown = None;
+    }
+
+    fn get_text(
+        &self,
+        gatherer_type: GathererType,
+        projection: &logic::BuildProjection,
+        localization: &Localization,
+    ) -> String {
+        let mut lines = vec![
+            localization.get(&format!("inspect.gatherer.{:?}", gatherer_type)),
+            localization.format(
+                "preview.gathering",
+                &[
+                    ("resource", localization.get(&format!("resource.{:?}", gatherer_type))),
+                    ("amount", localization.number(projection.gathering_rate as i64)),
+                ],
+            ),
+            localization.format(
+                "preview.power",
+                &[
+                    ("before", localization.number(projection.power_balance_before as i64)),
+                    ("after", localization.number(projection.power_balance_after as i64)),
+                ],
+            ),
+            localization.format(
+                "preview.tax",
+                &[("amount", localization.number(projection.extra_tax as i64))],
+            ),
+        ];
+        if projection.adjacency_bonuses > 0 {
+            lines.push(localization.plural(
+                "preview.adjacency",
+                projection.adjacency_bonuses as i64,
+                &[],
+            ));
+        }
+        if projection.tax_exceeds_income() {
+            lines.push(localization.get("preview.tax_warning"));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl<'a> System<'a> for BuildPreview {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Button>,
+        Read<'a, ChosenGatherer>,
+        WriteStorage<'a, Color>,
+        Read<'a, EntityLookup>,
+        ReadStorage<'a, Gatherer>,
+        Read<'a, GathererPositions>,
+        Read<'a, GatheringRate>,
+        Read<'a, Localization>,
+        WriteStorage<'a, Node>,
+        ReadStorage<'a, PowerBar>,
+        WriteStorage<'a, Rect>,
+        Read<'a, ResearchedBuffs>,
+        Read<'a, Rules>,
+        ReadStorage<'a, SelectedTile>,
+        WriteStorage<'a, Text>,
+        Read<'a, TileNodes>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            button_storage,
+            chosen_gatherer_storage,
+            mut color_storage,
+            entity_lookup_storage,
+            gatherer_storage,
+            gatherer_positions_storage,
+            gathering_rate_storage,
+            localization_storage,
+            mut node_storage,
+            power_bar_storage,
+            mut rect_storage,
+            researched_buffs_storage,
+            rules_storage,
+            selected_tile_storage,
+            mut text_storage,
+            tile_nodes_storage,
+            mut transform_storage,
+        ) = data;
+
+        let mut selected_pos = None;
+        for (_, transform) in (&selected_tile_storage, &transform_storage).join() {
+            if transform.visible {
+                selected_pos = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
+
+        // hovering a button previews it over the one chosen by key
+        let hovered = (&button_storage)
+            .join()
+            .filter(|button| button.mouse_is_over && !button.disabled)
+            .filter_map(|button| BUILD_BUTTONS.iter().find(|build| build.0 == button.name))
+            .map(|build| build.1)
+            .next();
+        let previewed = hovered.or(chosen_gatherer_storage.0);
+
+        let (selected_x, selected_y, gatherer_type) = match (selected_pos, previewed) {
+            (Some((x, y)), Some(gatherer_type)) => (x, y, gatherer_type),
+            _ => {
+                self.close_preview(&entities, &node_storage);
+                return;
+            }
+        };
+
+        let power_demands = (&power_bar_storage)
+            .join()
+            .fold(0, |sum, power_bar| sum + power_bar.power_per_tick)
+            / POWER_FACTOR;
+        let projection = logic::project_build(
+            gatherer_type,
+            (
+                (selected_x / Tile::get_size()) as i32,
+                (selected_y / Tile::get_size()) as i32,
+            ),
+            &gatherer_storage,
+            gatherer_positions_storage.deref(),
+            gathering_rate_storage.deref(),
+            power_demands,
+            researched_buffs_storage.deref(),
+            rules_storage.deref(),
+            tile_nodes_storage.deref(),
+        );
+        let text = self.get_text(gatherer_type, &projection, localization_storage.deref());
+
+        let shown = Some((selected_x, selected_y, text.clone()));
+        if self.shown == shown {
+            return;
+        }
+        self.close_preview(&entities, &node_storage);
+
+        // below the build ui, or above it when that runs off the map
+        let dimensions = renderer::get_dimensions();
+        let (x, y) = create_build_ui::get_position(selected_x + Tile::get_size(), selected_y);
+        let below = y + create_build_ui::CONTAINER_HEIGHT as f32;
+        let y = if below + PREVIEW_HEIGHT as f32 <= dimensions[1] {
+            below
+        } else {
+            y - PREVIEW_HEIGHT as f32
+        };
+
+        let preview_entity = create_tooltip::create(
+            &entities,
+            &mut color_storage,
+            &mut node_storage,
+            &mut rect_storage,
+            &mut text_storage,
+            &mut transform_storage,
+            x,
+            y,
+            dimensions[0] as i32,
+            dimensions[1] as i32,
+            PREVIEW_WIDTH,
+            PREVIEW_HEIGHT,
+            text,
+            if projection.tax_exceeds_income() {
+                Some(Color([0.4, 0.0, 0.0, 0.85]))
+            } else {
+                Some(Color([0.0, 0.0, 0.0, 0.85]))
+            },
+        );
+        let node = logic::get_root(entity_lookup_storage.deref(), &mut node_storage);
+        node.add(preview_entity);
+
+        self.preview_entity = Some(preview_entity);
+        self.shown = shown;
+    }
+}
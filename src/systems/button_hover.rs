@@ -1,4 +1,4 @@
-use cgmath::Vector3;
+use cgmath::{Vector2, Vector3};
 use components::{Button, EntityLookup, Input, Node, Sprite, Transform};
 use specs::{Entities, Entity, Join, Read, System, WriteStorage};
 use std::ops::Deref;
@@ -36,29 +36,31 @@ impl<'a> System<'a> for ButtonHover {
 
         let root_entity = lookup.get("root").unwrap();
 
-        let mut button_entities: Vec<(i32, Entity, Vector3<f32>)> = Vec::new();
+        let mut button_entities: Vec<(i32, Entity, Vector3<f32>, Vector2<f32>)> = Vec::new();
 
-        for (button, entity, _, _) in (
-            &mut button_storage,
-            &*entities,
-            &mut sprite_storage,
-            &transform_storage,
-        ).join()
-        {
+        for (button, entity, _) in (&mut button_storage, &*entities, &transform_storage).join() {
             button.mouse_is_over = false;
-            let absolute_pos =
-                Node::get_absolute_pos(root_entity, &entity, &transform_storage, &node_storage);
-            button_entities.push((absolute_pos.z as i32, entity.clone(), absolute_pos));
+            let (absolute_pos, absolute_scale) = Node::get_absolute_transform(
+                root_entity,
+                &entity,
+                &transform_storage,
+                &node_storage,
+            );
+            button_entities.push((
+                absolute_pos.z as i32,
+                entity.clone(),
+                absolute_pos,
+                absolute_scale,
+            ));
         }
 
         button_entities.sort_by(|a, b| b.0.cmp(&a.0));
 
         let mut found_button = false;
 
-        for (_, button_entity, absolute_pos) in button_entities {
+        for (_, button_entity, absolute_pos, absolute_scale) in button_entities {
             let transform = transform_storage.get(button_entity).unwrap();
             let button = button_storage.get_mut(button_entity).unwrap();
-            let sprite = sprite_storage.get_mut(button_entity).unwrap();
 
             let abs_transform = Transform::visible(
                 absolute_pos.x,
@@ -67,16 +69,21 @@ impl<'a> System<'a> for ButtonHover {
                 transform.size.x,
                 transform.size.y,
                 transform.rotation,
-                transform.scale.x,
-                transform.scale.y,
+                absolute_scale.x,
+                absolute_scale.y,
             );
             if !found_button && !button.disabled && abs_transform.contains(&mouse_x, &mouse_y) {
                 button.mouse_is_over = true;
-                sprite.frame_name = button.get_hover_frame().clone();
+                if let Some(sprite) = sprite_storage.get_mut(button_entity) {
+                    sprite.frame_name = button.get_hover_frame().clone();
+                }
                 found_button = true;
             } else {
                 button.mouse_is_over = false;
-                sprite.frame_name = button.get_default_frame().clone();
+                // text buttons have no sprite to swap
+                if let Some(sprite) = sprite_storage.get_mut(button_entity) {
+                    sprite.frame_name = button.get_default_frame().clone();
+                }
             }
         }
     }
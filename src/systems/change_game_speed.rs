@@ -0,0 +1,44 @@
+use specs::{Read, System, Write, WriteStorage};
+
+use components::{EntityLookup, GameSpeed, Input, Text};
+use key_bindings::InputAction;
+use localization::Localization;
+
+pub struct ChangeGameSpeed;
+
+impl<'a> System<'a> for ChangeGameSpeed {
+    type SystemData = (
+        Read<'a, EntityLookup>,
+        Write<'a, GameSpeed>,
+        Read<'a, Input>,
+        Read<'a, Localization>,
+        WriteStorage<'a, Text>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entity_lookup_storage,
+            mut game_speed_storage,
+            input_storage,
+            localization_storage,
+            mut text_storage,
+        ) = data;
+
+        let previous_speed = game_speed_storage.0;
+        if input_storage.action_pressed(InputAction::SpeedUp) {
+            game_speed_storage.speed_up();
+        }
+        if input_storage.action_pressed(InputAction::SlowDown) {
+            game_speed_storage.slow_down();
+        }
+
+        if game_speed_storage.0 != previous_speed {
+            let entity = entity_lookup_storage.get("game_speed_text").unwrap();
+            let text = text_storage.get_mut(*entity).unwrap();
+            text.set_text(localization_storage.format(
+                "sidebar.speed",
+                &[("speed", format!("{}", game_speed_storage.0))],
+            ));
+        }
+    }
+}
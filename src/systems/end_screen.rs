@@ -2,52 +2,169 @@ use std::ops::{Deref, DerefMut};
 
 use gfx_glyph::HorizontalAlign;
 use specs::{Entities, Join, LazyUpdate, Read, System, Write, WriteStorage};
-use state::play_state::PlayState;
 
-use components::{Actions, Button, CityPowerState, Color, EntityLookup, Input, Node, Sprite,
-                 StateChange, Text, Transform};
-use entities::{create_colored_rect, create_text};
+use components::{upgrade::Buff, Anchor, Button, CityPowerState, Color, EntityLookup,
+                 EventChannel, EventReader, GameEvent, GathererType, HorizontalAnchor, Input,
+                 InternalState, MapSeed, Node, ResearchedBuffs, RunStats, StateAction, StateChange,
+                 Transform, Transition, UiCommand, UiCommands, VerticalAnchor};
+use entities::{create_colored_rect, create_text, create_text_button};
+use localization::Localization;
 use renderer;
+use run_history::{RunHistory, RunRecord};
 use systems::logic;
 
-pub struct EndScreen;
+const TIMELINE_EVENTS_TO_SHOW: usize = 14;
+
+pub struct EndScreen {
+    game_events: EventReader,
+}
+
+impl EndScreen {
+    pub fn new() -> Self {
+        EndScreen {
+            game_events: EventReader::new(),
+        }
+    }
+
+    fn get_timeline_text(&self, run_stats: &RunStats, localization: &Localization) -> String {
+        if run_stats.timeline.is_empty() {
+            return localization.get("end.no_events");
+        }
+
+        let skip = run_stats
+            .timeline
+            .len()
+            .saturating_sub(TIMELINE_EVENTS_TO_SHOW);
+        run_stats
+            .timeline
+            .iter()
+            .skip(skip)
+            .map(|event| {
+                format!(
+                    "{}:{:02}  {}",
+                    event.time as u32 / 60,
+                    event.time as u32 % 60,
+                    event.description
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn get_economy_text(&self, run_stats: &RunStats, localization: &Localization) -> String {
+        let mut lines = Vec::new();
+        for &(id, amount) in &[
+            ("end.income", run_stats.total_income),
+            ("end.tax", run_stats.total_tax),
+            ("end.net", run_stats.total_income - run_stats.total_tax),
+            ("", 0),
+            ("end.spent_on_gatherers", run_stats.spent_on_gatherers),
+            ("end.spent_on_research", run_stats.spent_on_research),
+        ] {
+            lines.push(if id == "" {
+                String::new()
+            } else {
+                localization.format(id, &[("money", localization.money(amount))])
+            });
+        }
+
+        lines.push(String::new());
+        lines.push(localization.get("end.gatherers_built"));
+        for gatherer_type in &[
+            GathererType::Coal,
+            GathererType::Oil,
+            GathererType::Solar,
+            GathererType::Hydro,
+        ] {
+            lines.push(format!(
+                "{}: {}",
+                localization.get(&format!("resource.{:?}", gatherer_type)),
+                run_stats.get_built_for_type(gatherer_type)
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
 
 impl<'a> System<'a> for EndScreen {
     type SystemData = (
         Entities<'a>,
         Read<'a, LazyUpdate>,
-        Write<'a, Actions>,
         WriteStorage<'a, Button>,
         Read<'a, CityPowerState>,
-        WriteStorage<'a, Color>,
         Write<'a, EntityLookup>,
+        Read<'a, EventChannel<GameEvent>>,
         Read<'a, Input>,
+        Read<'a, InternalState>,
+        Read<'a, Localization>,
+        Read<'a, MapSeed>,
         WriteStorage<'a, Node>,
-        WriteStorage<'a, Sprite>,
+        Read<'a, ResearchedBuffs>,
+        Write<'a, RunHistory>,
+        Read<'a, RunStats>,
         Write<'a, StateChange>,
-        WriteStorage<'a, Text>,
-        WriteStorage<'a, Transform>,
+        Write<'a, UiCommands>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
             lazy,
-            mut actions_storage,
             mut button_storage,
             city_power_state_storage,
-            mut color_storage,
             mut entity_lookup_storage,
+            game_events_storage,
             input_storage,
+            internal_state_storage,
+            localization_storage,
+            map_seed_storage,
             mut node_storage,
-            mut sprite_storage,
+            researched_buffs_storage,
+            mut run_history_storage,
+            run_stats_storage,
             mut state_change_storage,
-            mut text_storage,
-            mut transform_storage,
+            mut ui_commands_storage,
         ) = data;
 
-        if actions_storage.action_fired("display_end_screen") {
-            actions_storage.remove("display_end_screen".to_string());
+        let blackout = game_events_storage
+            .read(&mut self.game_events)
+            .iter()
+            .any(|event| *event == GameEvent::Blackout);
+        if blackout {
+
+            let best_cities_powered = run_history_storage.best_cities_powered();
+            run_history_storage.add(RunRecord {
+                cities_powered: city_power_state_storage.current_city_count,
+                time_survived: run_stats_storage.time_survived,
+                total_income: run_stats_storage.total_income,
+                total_tax: run_stats_storage.total_tax,
+                peak_pollution: run_stats_storage.peak_pollution,
+                // coal is researched from the start
+                buffs_researched: researched_buffs_storage
+                    .0
+                    .keys()
+                    .filter(|buff| **buff != Buff::Coal)
+                    .count(),
+                seed: map_seed_storage.0,
+            });
+
+            let mut buttons = Vec::with_capacity(3);
+            for (i, name) in ["same_seed", "new_map", "high_scores"].iter().enumerate() {
+                buttons.push(create_text_button::create(
+                    &entities,
+                    &lazy,
+                    &mut node_storage,
+                    name,
+                    localization_storage.get(&format!("end.{}", name)),
+                    168.0 + 216.0 * i as f32,
+                    580.0,
+                    10.0,
+                    192,
+                    40,
+                ));
+            }
+
             let lookup = entity_lookup_storage.deref_mut();
             let root_node = logic::get_root(&lookup, &mut node_storage);
 
@@ -61,63 +178,173 @@ impl<'a> System<'a> for EndScreen {
                 &entities,
                 &lazy,
             );
+            lazy.insert(entity, Anchor::fill());
             lookup.entities.insert("pause_black".to_string(), entity);
 
             root_node.add(entity);
 
+            let dim = renderer::get_dimensions();
+            let mut content = Node::new();
+
             {
-                let dim = renderer::get_dimensions();
                 let text = create_text::create(
                     &entities,
                     &lazy,
-                    format!(
-                        "You were able to provide power to {} cities",
-                        city_power_state_storage.current_city_count
+                    localization_storage.plural(
+                        "end.cities_powered",
+                        city_power_state_storage.current_city_count as i64,
+                        &[],
                     ),
                     30.0,
                     dim[0] / 2.0,
-                    250.0,
+                    20.0,
                     10.0,
                     600,
-                    100,
+                    40,
                     Color([0.0, 0.6, 0.0, 1.0]),
                     Some(HorizontalAlign::Center),
                 );
 
-                root_node.add(text);
+                content.add(text);
+
+                let best_text = if city_power_state_storage.current_city_count > best_cities_powered
+                {
+                    localization_storage.get("end.new_best")
+                } else {
+                    localization_storage.plural("end.best", best_cities_powered as i64, &[])
+                };
+                let text = create_text::create(
+                    &entities,
+                    &lazy,
+                    best_text,
+                    24.0,
+                    dim[0] / 2.0,
+                    65.0,
+                    10.0,
+                    600,
+                    30,
+                    Color([1.0, 1.0, 0.0, 1.0]),
+                    Some(HorizontalAlign::Center),
+                );
+
+                content.add(text);
+
+                let text = create_text::create(
+                    &entities,
+                    &lazy,
+                    run_stats_storage.blackout_cause.clone(),
+                    20.0,
+                    dim[0] / 2.0,
+                    105.0,
+                    10.0,
+                    860,
+                    50,
+                    Color([0.8, 0.2, 0.2, 1.0]),
+                    Some(HorizontalAlign::Center),
+                );
+
+                content.add(text);
             }
 
-            let restart = entities.create();
-            sprite_storage
-                .insert(
-                    restart,
-                    Sprite {
-                        frame_name: "restart.png".to_string(),
-                    },
-                )
-                .unwrap();
-            button_storage
-                .insert(
-                    restart,
-                    Button::new(
-                        "restart".to_string(),
-                        ["restart.png".to_string(), "restart_hover.png".to_string()],
-                    ),
-                )
-                .unwrap();
-            transform_storage
-                .insert(
-                    restart,
-                    Transform::visible(384.0, 520.0, 10.0, 192, 50, 0.0, 1.0, 1.0),
-                )
-                .unwrap();
+            let text = create_text::create(
+                &entities,
+                &lazy,
+                localization_storage.get("end.timeline"),
+                24.0,
+                60.0,
+                170.0,
+                10.0,
+                420,
+                30,
+                Color([0.0, 0.6, 0.0, 1.0]),
+                None,
+            );
+            content.add(text);
+
+            let text = create_text::create(
+                &entities,
+                &lazy,
+                self.get_timeline_text(run_stats_storage.deref(), &localization_storage),
+                18.0,
+                60.0,
+                205.0,
+                10.0,
+                420,
+                360,
+                Color([0.8, 0.8, 0.8, 1.0]),
+                None,
+            );
+            content.add(text);
+
+            let text = create_text::create(
+                &entities,
+                &lazy,
+                localization_storage.get("end.economy"),
+                24.0,
+                560.0,
+                170.0,
+                10.0,
+                340,
+                30,
+                Color([0.0, 0.6, 0.0, 1.0]),
+                None,
+            );
+            content.add(text);
+
+            let text = create_text::create(
+                &entities,
+                &lazy,
+                self.get_economy_text(run_stats_storage.deref(), &localization_storage),
+                18.0,
+                560.0,
+                205.0,
+                10.0,
+                340,
+                360,
+                Color([0.8, 0.8, 0.8, 1.0]),
+                None,
+            );
+            content.add(text);
+
+            content.add_many(buttons);
+            let container = lazy.create_entity(&entities)
+                .with(Transform::visible(
+                    0.0,
+                    0.0,
+                    10.0,
+                    dim[0] as u16,
+                    dim[1] as u16,
+                    0.0,
+                    1.0,
+                    1.0,
+                ))
+                .with(Anchor::new(HorizontalAnchor::Center, VerticalAnchor::Center))
+                .with(content)
+                .build();
+            root_node.add(container);
+        }
+
+        let high_scores_closed = ui_commands_storage.take(|command| match *command {
+            UiCommand::CloseHighScores => Some(()),
+            _ => None,
+        });
+        if high_scores_closed.is_some() {
+            state_change_storage.set(Transition::Custom(StateAction::HideHighScores));
+        }
 
-            root_node.add(restart);
+        // conrod is drawn over the end screen while viewing high scores
+        if *internal_state_storage != InternalState::End {
+            return;
         }
 
         for button in (&mut button_storage).join() {
-            if button.name == "restart" && button.clicked(&input_storage.deref()) {
-                state_change_storage.set(PlayState::get_name(), "restart".to_string());
+            if button.name == "new_map" && button.clicked(&input_storage.deref()) {
+                state_change_storage.set(Transition::Restart);
+            } else if button.name == "same_seed" && button.clicked(&input_storage.deref()) {
+                ui_commands_storage.push(UiCommand::RestartSameSeed(map_seed_storage.0));
+                state_change_storage.set(Transition::Restart);
+            } else if button.name == "high_scores" && button.clicked(&input_storage.deref()) {
+                state_change_storage.set(Transition::Custom(StateAction::ShowHighScores));
             }
         }
     }
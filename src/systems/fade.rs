@@ -1,6 +1,6 @@
 use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
 
-use components::{self, Color, DeltaTime, FadeMode, StateChange, TransitionToState};
+use components::{self, Color, DeltaTime, FadeMode, StateChange, Transition, TransitionToState};
 
 pub struct Fade;
 
@@ -34,8 +34,7 @@ impl<'a> System<'a> for Fade {
             color.0[3] = alpha;
             if fade.tick <= 0.0 {
                 if let Some(transition_to_state) = transition_to_state_storage.get(entity) {
-                    state_change_storage.action = "start".to_string();
-                    state_change_storage.state = transition_to_state.state.clone();
+                    state_change_storage.set(Transition::Switch(transition_to_state.state));
                 } else {
                     // if we dont transition, clean it up manually
                     entities.delete(entity).unwrap();
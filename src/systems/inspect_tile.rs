@@ -0,0 +1,267 @@
+use components::{upgrade::Buff, Button, EntityLookup, Gatherer, GathererPositions, Input,
+                 Node, PollutedTiles, ResearchedBuffs, SelectedTile, Tile, TileCursor, TileNodes,
+                 TileType, Transform, UiCommand, UiCommands, MAP_TILES};
+use entities::{create_inspect_panel, recursive_delete};
+use key_bindings::InputAction;
+use localization::Localization;
+use specs::{Entities, Entity, Join, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
+use std::ops::Deref;
+use systems::logic;
+
+pub struct InspectTile {
+    inspected: Option<(i32, i32)>,
+    panel_entity: Option<Entity>,
+    // the text on the panel, which is rebuilt when the tile changes underneath it
+    shown_text: Option<(String, String)>,
+    selected_pos: Option<(f32, f32)>,
+    mouse_was_pressed: bool,
+}
+
+impl InspectTile {
+    pub fn new() -> InspectTile {
+        InspectTile {
+            inspected: None,
+            panel_entity: None,
+            shown_text: None,
+            selected_pos: None,
+            mouse_was_pressed: false,
+        }
+    }
+
+    fn close_panel(&mut self, entities: &Entities, node_storage: &WriteStorage<Node>) {
+        if let Some(panel_entity) = self.panel_entity {
+            recursive_delete(entities, node_storage, &panel_entity);
+        }
+        self.inspected = None;
+        self.panel_entity = None;
+        self.shown_text = None;
+    }
+
+    fn get_text(
+        &self,
+        tile: (i32, i32),
+        gatherer: Option<&Gatherer>,
+        localization: &Localization,
+        polluted_tiles: &PollutedTiles,
+        researched_buffs: &ResearchedBuffs,
+        tile_nodes: &TileNodes,
+    ) -> (String, String) {
+        let (col, row) = tile;
+        let tile_type = tile_nodes
+            .nodes
+            .get(&tile)
+            .map(|&(tile_type, _)| tile_type)
+            .unwrap_or(TileType::Open);
+        let title = localization.get(&format!("inspect.tile.{:?}", tile_type));
+
+        let mut lines = Vec::new();
+        if let Some(gatherer) = gatherer {
+            let gatherer_yield = gatherer.get_yield(researched_buffs);
+            lines.push(localization.get(&format!("inspect.gatherer.{:?}", gatherer.gatherer_type)));
+            lines.push(localization.format(
+                "inspect.yield",
+                &[("amount", localization.number(gatherer_yield.total() as i64))],
+            ));
+            lines.push(localization.format(
+                "inspect.base",
+                &[("amount", localization.number(gatherer_yield.base as i64))],
+            ));
+            for &(buff, amount) in &gatherer_yield.buffs {
+                lines.push(localization.format(
+                    "inspect.buff",
+                    &[
+                        ("buff", buff.get_localized_name(localization)),
+                        ("amount", localization.number(amount as i64)),
+                    ],
+                ));
+            }
+
+            let pollution = gatherer
+                .gatherer_type
+                .apply_pollution_filter(gatherer.get_pollution(researched_buffs), researched_buffs);
+            lines.push(localization.format(
+                "inspect.pollution",
+                &[("amount", localization.number(pollution as i64))],
+            ));
+
+            lines.push(if !gatherer.has_adjancent_of_same_type {
+                localization.get("inspect.adjacent.none")
+            } else if researched_buffs.0.contains_key(&Buff::ResourceTrading) {
+                localization.get("inspect.adjacent.active")
+            } else {
+                localization.format(
+                    "inspect.adjacent.needs_research",
+                    &[("buff", Buff::ResourceTrading.get_localized_name(localization))],
+                )
+            });
+        } else if tile_type == TileType::Open || tile_type == TileType::River {
+            lines.push(localization.get("inspect.empty"));
+        }
+
+        let size = Tile::get_size() as i32;
+        if polluted_tiles.contains(&(col * size, row * size)) {
+            lines.push(localization.get("inspect.polluted"));
+        }
+        let mut polluted_neighbours = 0;
+        for i in -1..2 {
+            for j in -1..2 {
+                if (i != 0 || j != 0)
+                    && polluted_tiles.contains(&((col + i) * size, (row + j) * size))
+                {
+                    polluted_neighbours += 1;
+                }
+            }
+        }
+        lines.push(localization.plural("inspect.polluted_neighbours", polluted_neighbours, &[]));
+
+        (title, lines.join("\n"))
+    }
+}
+
+impl<'a> System<'a> for InspectTile {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Button>,
+        Read<'a, EntityLookup>,
+        ReadStorage<'a, Gatherer>,
+        Read<'a, GathererPositions>,
+        Read<'a, Input>,
+        Read<'a, Localization>,
+        WriteStorage<'a, Node>,
+        Read<'a, PollutedTiles>,
+        Read<'a, ResearchedBuffs>,
+        ReadStorage<'a, SelectedTile>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, TileCursor>,
+        Read<'a, TileNodes>,
+        ReadStorage<'a, Transform>,
+        Write<'a, UiCommands>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            lazy,
+            button_storage,
+            entity_lookup_storage,
+            gatherer_storage,
+            gatherer_positions_storage,
+            input_storage,
+            localization_storage,
+            mut node_storage,
+            polluted_tiles_storage,
+            researched_buffs_storage,
+            selected_tile_storage,
+            tile_storage,
+            tile_cursor_storage,
+            tile_nodes_storage,
+            transform_storage,
+            mut ui_commands_storage,
+        ) = data;
+
+        let input: &Input = input_storage.deref();
+        let gatherer_positions = gatherer_positions_storage.deref();
+        let tile_nodes = tile_nodes_storage.deref();
+        let size = Tile::get_size();
+
+        let mut target = None;
+
+        // clicks on tiles that don't open the build ui inspect them
+        let mouse_released = self.mouse_was_pressed && !input.mouse_pressed;
+        self.mouse_was_pressed = input.mouse_pressed;
+        let over_other_button = (&button_storage, !&tile_storage)
+            .join()
+            .any(|(button, _)| button.mouse_is_over);
+        let (mouse_x, mouse_y) = input.mouse_pos;
+        if mouse_released && !over_other_button && mouse_x >= 0.0 && mouse_y >= 0.0 {
+            let tile = ((mouse_x / size) as i32, (mouse_y / size) as i32);
+            if tile.0 < MAP_TILES && tile.1 < MAP_TILES {
+                let tile_type = tile_nodes
+                    .nodes
+                    .get(&tile)
+                    .map(|&(tile_type, _)| tile_type)
+                    .unwrap_or(TileType::Open);
+                let buildable = tile_type == TileType::Open || tile_type == TileType::River;
+                if !buildable || gatherer_positions.gatherers.contains_key(&tile) {
+                    target = Some(tile);
+                }
+            }
+        }
+
+        if input.action_pressed(InputAction::InspectTile) {
+            for (_, transform) in (&tile_cursor_storage, &transform_storage).join() {
+                if transform.visible {
+                    target = Some((
+                        (transform.get_pos().x / size) as i32,
+                        (transform.get_pos().y / size) as i32,
+                    ));
+                }
+            }
+        }
+
+        let mut selected_pos = None;
+        for (_, transform) in (&selected_tile_storage, &transform_storage).join() {
+            if transform.visible {
+                selected_pos = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
+
+        // notifications jump straight to their tile, rather than toggling it
+        let jump_to = ui_commands_storage.take(|command| match *command {
+            UiCommand::InspectTile(col, row) => Some((col, row)),
+            _ => None,
+        });
+
+        if let Some(tile) = jump_to {
+            self.close_panel(&entities, &node_storage);
+            self.inspected = Some(tile);
+        } else if let Some(tile) = target {
+            let same_tile = self.inspected == Some(tile);
+            self.close_panel(&entities, &node_storage);
+            if !same_tile {
+                self.inspected = Some(tile);
+            }
+        } else if selected_pos.is_some() && selected_pos != self.selected_pos {
+            // opening the build ui takes over from the panel
+            self.close_panel(&entities, &node_storage);
+        }
+        self.selected_pos = selected_pos;
+
+        if let Some(tile) = self.inspected {
+            let gatherer = gatherer_positions
+                .gatherers
+                .get(&tile)
+                .and_then(|&(_, entity)| gatherer_storage.get(entity));
+            let text = self.get_text(
+                tile,
+                gatherer,
+                localization_storage.deref(),
+                polluted_tiles_storage.deref(),
+                researched_buffs_storage.deref(),
+                tile_nodes,
+            );
+
+            if self.shown_text.as_ref() != Some(&text) {
+                if let Some(panel_entity) = self.panel_entity {
+                    recursive_delete(&entities, &node_storage, &panel_entity);
+                }
+
+                let entity = create_inspect_panel::create(
+                    tile.0 as f32 * size,
+                    tile.1 as f32 * size,
+                    text.0.clone(),
+                    text.1.clone(),
+                    &entities,
+                    &lazy,
+                    &mut node_storage,
+                );
+                let node = logic::get_root(entity_lookup_storage.deref(), &mut node_storage);
+                node.add(entity);
+
+                self.panel_entity = Some(entity);
+                self.shown_text = Some(text);
+            }
+        }
+    }
+}
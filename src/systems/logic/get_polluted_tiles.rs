@@ -0,0 +1,33 @@
+use components::{GathererType, TileNodes, TileType};
+
+// the non open tiles a gatherer built on the given tile pollutes. Hydro pollutes its own river,
+// solar nothing, and the rest their neighbours
+pub fn get_polluted_tiles(
+    gatherer_type: GathererType,
+    tile: (i32, i32),
+    tile_nodes: &TileNodes,
+) -> Vec<(i32, i32)> {
+    let mut tiles = Vec::new();
+    if gatherer_type == GathererType::Solar {
+        return tiles;
+    }
+
+    let (col, row) = tile;
+    for i in -1..2 {
+        for j in -1..2 {
+            if gatherer_type == GathererType::Hydro && (i != 0 || j != 0) {
+                continue;
+            } else if gatherer_type != GathererType::Hydro && i == 0 && j == 0 {
+                continue;
+            }
+
+            if let Some(&(tile_type, _)) = tile_nodes.nodes.get(&(col + i, row + j)) {
+                if tile_type != TileType::Open {
+                    tiles.push((col + i, row + j));
+                }
+            }
+        }
+    }
+
+    tiles
+}
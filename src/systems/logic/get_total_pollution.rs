@@ -0,0 +1,23 @@
+use components::{Gatherer, GathererType, ResearchedBuffs};
+
+pub fn get_total_pollution<'a, I>(gatherers: I, researched_buffs: &ResearchedBuffs) -> i32
+where
+    I: Iterator<Item = &'a Gatherer>,
+{
+    let mut coal_pollution = 0;
+    let mut oil_pollution = 0;
+    let mut hydro_pollution = 0;
+    for gatherer in gatherers {
+        let amount = gatherer.get_pollution(researched_buffs);
+        match gatherer.gatherer_type {
+            GathererType::Coal => coal_pollution += amount,
+            GathererType::Oil => oil_pollution += amount,
+            GathererType::Hydro => hydro_pollution += amount,
+            GathererType::Solar => {}
+        }
+    }
+
+    GathererType::Coal.apply_pollution_filter(coal_pollution, researched_buffs)
+        + GathererType::Oil.apply_pollution_filter(oil_pollution, researched_buffs)
+        + GathererType::Hydro.apply_pollution_filter(hydro_pollution, researched_buffs)
+}
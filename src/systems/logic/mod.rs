@@ -1,7 +1,13 @@
+mod get_polluted_tiles;
 mod get_root;
 mod get_total_gathering_rate;
+mod get_total_pollution;
+mod project_build;
 mod update_text;
 
+pub use self::get_polluted_tiles::*;
 pub use self::get_root::*;
 pub use self::get_total_gathering_rate::*;
+pub use self::get_total_pollution::*;
+pub use self::project_build::*;
 pub use self::update_text::*;
@@ -0,0 +1,108 @@
+use components::{upgrade::Buff, Gatherer, GathererPositions, GathererType, GatheringRate,
+                 ResearchedBuffs, TileNodes};
+use rules::Rules;
+use specs::{Join, ReadStorage};
+use std::cmp;
+use std::iter;
+use systems::logic;
+
+// how the economy would change if a gatherer were built, assuming the last tick repeats
+pub struct BuildProjection {
+    // extra resources of the built type gathered per tick
+    pub gathering_rate: i32,
+    // gatherers, the new one included, that would gain the adjacency bonus
+    pub adjacency_bonuses: i32,
+    pub power_balance_before: i32,
+    pub power_balance_after: i32,
+    pub extra_tax: i32,
+    pub income_after: i32,
+    pub tax_after: i32,
+}
+
+impl BuildProjection {
+    pub fn tax_exceeds_income(&self) -> bool {
+        self.tax_after > self.income_after
+    }
+}
+
+// cities only pay for the power they use, so income is production capped by demand
+fn get_income(power_production: i32, power_demands: i32) -> i32 {
+    cmp::max(0, cmp::min(power_production, power_demands))
+}
+
+pub fn project_build(
+    gatherer_type: GathererType,
+    tile: (i32, i32),
+    gatherer_storage: &ReadStorage<Gatherer>,
+    gatherer_positions: &GathererPositions,
+    gathering_rate: &GatheringRate,
+    power_demands: i32,
+    researched_buffs: &ResearchedBuffs,
+    rules: &Rules,
+    tile_nodes: &TileNodes,
+) -> BuildProjection {
+    let (col, row) = tile;
+    let mut neighbours = Vec::new();
+    for i in -1..2 {
+        for j in -1..2 {
+            if i == 0 && j == 0 {
+                continue;
+            }
+            if let Some(&(other_gatherer_type, entity)) =
+                gatherer_positions.gatherers.get(&(col + i, row + j))
+            {
+                if other_gatherer_type == gatherer_type {
+                    if let Some(gatherer) = gatherer_storage.get(entity) {
+                        neighbours.push(gatherer);
+                    }
+                }
+            }
+        }
+    }
+
+    let pollution = logic::get_polluted_tiles(gatherer_type, tile, tile_nodes).len() as i32
+        * gatherer_type.get_pollution_amount();
+    let mut gatherer = Gatherer::new(gatherer_type, pollution);
+    gatherer.has_adjancent_of_same_type = !neighbours.is_empty();
+
+    // neighbours without a match yet get the bonus too
+    let mut gathering_rate_delta = gatherer.get_yield(researched_buffs).total();
+    let mut adjacency_bonuses = 0;
+    if let Some(level) = researched_buffs.0.get(&Buff::ResourceTrading) {
+        let unmatched = neighbours
+            .iter()
+            .filter(|neighbour| !neighbour.has_adjancent_of_same_type)
+            .count() as i32;
+        gathering_rate_delta += unmatched * *level as i32;
+        adjacency_bonuses += unmatched;
+        if gatherer.has_adjancent_of_same_type {
+            adjacency_bonuses += 1;
+        }
+    }
+
+    let mut gathering_rate_after = gathering_rate.clone();
+    gathering_rate_after.add_to_resource_amount(&gatherer_type, gathering_rate_delta);
+    let power_production_before = logic::get_total_gathering_rate(gathering_rate);
+    let power_production_after = logic::get_total_gathering_rate(&gathering_rate_after);
+
+    let pollution_before = logic::get_total_pollution(gatherer_storage.join(), researched_buffs);
+    let pollution_after = logic::get_total_pollution(
+        gatherer_storage.join().chain(iter::once(&gatherer)),
+        researched_buffs,
+    );
+
+    let income_before = get_income(power_production_before, power_demands);
+    let income_after = get_income(power_production_after, power_demands);
+    let tax_before = rules.get_tax(income_before, pollution_before);
+    let tax_after = rules.get_tax(income_after, pollution_after);
+
+    BuildProjection {
+        gathering_rate: gathering_rate_delta,
+        adjacency_bonuses,
+        power_balance_before: power_production_before - power_demands,
+        power_balance_after: power_production_after - power_demands,
+        extra_tax: tax_after - tax_before,
+        income_after,
+        tax_after,
+    }
+}
@@ -1,10 +1,10 @@
 use rand::thread_rng;
 use specs::{Entities, Join, Read, System, Write, WriteStorage};
 
-use components::{Button, Color, DeltaTime, EntityLookup, Fade, FadeMode, Input, InternalState,
-                 MenuScreen, Node, Rect, StateChange, Transform, TransitionToState};
+use components::{Anchor, Button, Color, DeltaTime, EntityLookup, Fade, FadeMode, Input,
+                 InternalState, MenuScreen, Node, Rect, StateAction, StateChange, StateId,
+                 Transform, Transition, TransitionToState};
 use entities::create_fade;
-use state::{menu_state::MenuState, play_state::PlayState};
 
 const DURATION: f32 = 2.0;
 
@@ -23,6 +23,7 @@ impl MenuAnimation {
 impl<'a> System<'a> for MenuAnimation {
     type SystemData = (
         Entities<'a>,
+        WriteStorage<'a, Anchor>,
         WriteStorage<'a, Button>,
         WriteStorage<'a, Color>,
         Read<'a, DeltaTime>,
@@ -41,6 +42,7 @@ impl<'a> System<'a> for MenuAnimation {
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
+            mut anchor_storage,
             mut button_storage,
             mut color_storage,
             delta_time_storage,
@@ -64,17 +66,17 @@ impl<'a> System<'a> for MenuAnimation {
 
         for button in (&mut button_storage).join() {
             if button.name == "start" && button.clicked(&input_storage) {
-                state_change_storage.state = MenuState::get_name();
-                state_change_storage.action = "transition".to_string();
+                state_change_storage.set(Transition::Custom(StateAction::StartFade));
                 create_fade::create(
                     &entities,
+                    &mut anchor_storage,
                     &mut color_storage,
                     &mut fade_storage,
                     &mut node_storage,
                     &mut rect_storage,
                     &mut transform_storage,
                     Some(&mut transition_to_state_storage),
-                    Some(PlayState::get_name()),
+                    Some(StateId::Play),
                     &lookup_storage,
                     FadeMode::In,
                     1.0,
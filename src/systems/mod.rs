@@ -1,41 +1,60 @@
+mod anchor_layout;
 mod animation_system;
+mod apply_theme;
+mod block_map_tiles;
 mod build_gatherer;
+mod build_preview;
 mod button_hover;
+mod change_game_speed;
 mod end_screen;
-mod errors;
 mod fade;
 mod floating_text_system;
 mod gathering;
+mod inspect_tile;
 pub mod logic;
 mod menu_animation;
+mod move_tile_cursor;
+mod notification_center;
 mod pulse_system;
 mod research;
 mod sell_energy;
+mod statistics_panel;
 mod tech_tree;
 mod text_absolute_cache;
 mod tile_selection;
 mod toggle_pause;
 mod toggle_tech_tree;
+mod track_achievements;
 mod tutorial;
 
-pub const TICK_RATE: f32 = 2.5;
 pub const POWER_FACTOR: i32 = 1;
+// seconds of game time in which a build or research start can be undone
+pub const UNDO_GRACE_PERIOD: f32 = 10.0;
 
+pub use self::anchor_layout::*;
 pub use self::animation_system::*;
+pub use self::apply_theme::*;
+pub use self::block_map_tiles::*;
 pub use self::build_gatherer::*;
+pub use self::build_preview::*;
 pub use self::button_hover::*;
+pub use self::change_game_speed::*;
 pub use self::end_screen::*;
-pub use self::errors::*;
 pub use self::fade::*;
 pub use self::floating_text_system::*;
 pub use self::gathering::*;
+pub use self::inspect_tile::*;
 pub use self::menu_animation::*;
+pub use self::move_tile_cursor::*;
+pub use self::notification_center::*;
 pub use self::pulse_system::*;
 pub use self::research::*;
 pub use self::sell_energy::*;
+pub use self::statistics_panel::*;
 pub use self::tech_tree::*;
 pub use self::text_absolute_cache::*;
 pub use self::tile_selection::*;
 pub use self::toggle_pause::*;
 pub use self::toggle_tech_tree::*;
+pub use self::track_achievements::*;
 pub use self::tutorial::*;
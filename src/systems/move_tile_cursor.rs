@@ -0,0 +1,61 @@
+use components::{Input, SelectedTile, Tile, TileCursor, Transform, MAP_TILES};
+use key_bindings::InputAction;
+use specs::{Join, Read, ReadStorage, System, WriteStorage};
+use std::cmp;
+
+pub struct MoveTileCursor;
+
+impl<'a> System<'a> for MoveTileCursor {
+    type SystemData = (
+        Read<'a, Input>,
+        ReadStorage<'a, SelectedTile>,
+        ReadStorage<'a, TileCursor>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (input, selected_tile_storage, tile_cursor_storage, mut transform_storage) = data;
+
+        let mut movement = (0, 0);
+        if input.action_pressed(InputAction::CursorUp) {
+            movement.1 -= 1;
+        }
+        if input.action_pressed(InputAction::CursorDown) {
+            movement.1 += 1;
+        }
+        if input.action_pressed(InputAction::CursorLeft) {
+            movement.0 -= 1;
+        }
+        if input.action_pressed(InputAction::CursorRight) {
+            movement.0 += 1;
+        }
+
+        if movement == (0, 0) {
+            return;
+        }
+
+        let mut selected_tile = None;
+        for (_, transform) in (&selected_tile_storage, &transform_storage).join() {
+            if transform.visible {
+                selected_tile = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
+
+        let size = Tile::get_size();
+        for (_, transform) in (&tile_cursor_storage, &mut transform_storage).join() {
+            if !transform.visible {
+                // the first key press shows the cursor where the player was already looking
+                let (x, y) = selected_tile.unwrap_or((0.0, 0.0));
+                transform.visible = true;
+                transform.set_pos2(x, y);
+                continue;
+            }
+
+            let col = transform.get_pos().x as i32 / size as i32 + movement.0;
+            let row = transform.get_pos().y as i32 / size as i32 + movement.1;
+            let col = cmp::max(0, cmp::min(col, MAP_TILES - 1));
+            let row = cmp::max(0, cmp::min(row, MAP_TILES - 1));
+            transform.set_pos2(col as f32 * size, row as f32 * size);
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use gfx_glyph::HorizontalAlign;
+use specs::{Entities, Entity, Join, LazyUpdate, Read, System, Write, WriteStorage};
+
+use components::{Anchor, Button, Color, DeltaTime, EntityLookup, HorizontalAnchor, Input,
+                 MapBlocker, MapBlockers, Node, NotificationTarget, Notifications, Palette, Rect,
+                 RunStats, ThemeColor, Toast, Transform, UiCommand, UiCommands, VerticalAnchor};
+use entities::{create_text, recursive_delete};
+use localization::Localization;
+use renderer::get_dimensions;
+use std::ops::{Deref, DerefMut};
+use systems::logic;
+
+const TOAST_TIME: f32 = 4.0;
+const MAX_TOASTS: usize = 5;
+const ROW_HEIGHT: f32 = 30.0;
+const VISIBLE_ROWS: usize = 18;
+
+fn get_toast_y(stacked: usize) -> f32 {
+    20.0 + 30.0 * stacked as f32
+}
+
+fn get_toast_anchor(stacked: usize) -> Anchor {
+    Anchor::new(HorizontalAnchor::Center, VerticalAnchor::Top).margin(
+        0.0,
+        get_toast_y(stacked),
+        0.0,
+        0.0,
+    )
+}
+
+// Shows new notifications as stacking toasts, and the history in the log panel. Clicking an
+// entry in the history jumps to the tile or tech node it is about
+pub struct NotificationCenter {
+    // row entities of the open log panel, with the index of their notification
+    rows: Vec<(Entity, Option<usize>)>,
+    // rows scrolled past, counted from the newest notification
+    scroll: usize,
+    rows_dirty: bool,
+}
+
+impl NotificationCenter {
+    pub fn new() -> Self {
+        NotificationCenter {
+            rows: Vec::new(),
+            scroll: 0,
+            rows_dirty: false,
+        }
+    }
+
+    fn remove_rows(&mut self, entities: &Entities, node_storage: &WriteStorage<Node>) {
+        for &(entity, _) in &self.rows {
+            if entities.is_alive(entity) {
+                recursive_delete(entities, node_storage, &entity);
+            }
+        }
+        self.rows.clear();
+    }
+
+    fn create_rows(
+        &mut self,
+        panel_entity: Entity,
+        entities: &Entities,
+        lazy: &Read<LazyUpdate>,
+        localization: &Localization,
+        node_storage: &mut WriteStorage<Node>,
+        notifications: &Notifications,
+        palette: &Palette,
+    ) {
+        let history = &notifications.history;
+        let mut rows = Vec::new();
+        if history.is_empty() {
+            let text = create_text::create(
+                entities,
+                lazy,
+                localization.get("notification.empty"),
+                18.0,
+                20.0,
+                50.0,
+                1.0,
+                560,
+                24,
+                Color([0.6, 0.6, 0.6, 1.0]),
+                None,
+            );
+            rows.push((text, None));
+        }
+
+        // newest first
+        for (i, index) in (0..history.len())
+            .rev()
+            .skip(self.scroll)
+            .take(VISIBLE_ROWS)
+            .enumerate()
+        {
+            let notification = &history[index];
+            let time = notification.time as u32;
+            let color = notification.severity.get_palette_color();
+            let text = create_text::create(
+                entities,
+                lazy,
+                format!("{}:{:02} {}", time / 60, time % 60, notification.message),
+                18.0,
+                6.0,
+                4.0,
+                1.0,
+                548,
+                24,
+                Color(palette.get(color)),
+                None,
+            );
+            lazy.insert(text, ThemeColor(color));
+
+            let mut row = lazy.create_entity(entities)
+                .with(Transform::visible(
+                    20.0,
+                    50.0 + i as f32 * ROW_HEIGHT,
+                    1.0,
+                    560,
+                    ROW_HEIGHT as u16 - 2,
+                    0.0,
+                    1.0,
+                    1.0,
+                ))
+                .with(Rect {});
+            // rows that lead somewhere are buttons, and a little lighter
+            row = if notification.target.is_some() {
+                row.with(Button::new(
+                    "notification_row".to_string(),
+                    [String::new(), String::new()],
+                )).with(Color([0.2, 0.2, 0.28, 1.0]))
+            } else {
+                row.with(Color([0.0, 0.0, 0.0, 0.5]))
+            };
+            let mut row_node = Node::new();
+            row_node.add(text);
+            let row = row.with(row_node).build();
+            rows.push((row, Some(index)));
+        }
+
+        let panel_node = node_storage.get_mut(panel_entity).unwrap();
+        for &(entity, _) in &rows {
+            panel_node.add(entity);
+        }
+        self.rows = rows;
+    }
+}
+
+impl<'a> System<'a> for NotificationCenter {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
+        WriteStorage<'a, Anchor>,
+        WriteStorage<'a, Button>,
+        Read<'a, DeltaTime>,
+        Read<'a, EntityLookup>,
+        Read<'a, Input>,
+        Read<'a, Localization>,
+        Write<'a, MapBlockers>,
+        WriteStorage<'a, Node>,
+        Write<'a, Notifications>,
+        Read<'a, Palette>,
+        Read<'a, RunStats>,
+        WriteStorage<'a, Toast>,
+        WriteStorage<'a, Transform>,
+        Write<'a, UiCommands>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            lazy,
+            mut anchor_storage,
+            mut button_storage,
+            delta_time_storage,
+            entity_lookup_storage,
+            input_storage,
+            localization_storage,
+            mut map_blockers_storage,
+            mut node_storage,
+            mut notifications_storage,
+            palette_storage,
+            run_stats_storage,
+            mut toast_storage,
+            mut transform_storage,
+            mut ui_commands_storage,
+        ) = data;
+
+        let input: &Input = input_storage.deref();
+        let notifications: &mut Notifications = notifications_storage.deref_mut();
+
+        // anything outside of the systems, like failed hot reloads, reports through a command
+        let error = ui_commands_storage.take(|command| match *command {
+            UiCommand::DisplayError(ref message) => Some(message.clone()),
+            _ => None,
+        });
+        if let Some(message) = error {
+            notifications.error(message, None);
+        }
+
+        let mut toast_count = (&toast_storage).join().count();
+        let added = notifications.take_pending(run_stats_storage.time_survived);
+        if added > 0 {
+            self.rows_dirty = true;
+        }
+        let width = get_dimensions()[0];
+        let history_len = notifications.history.len();
+        for notification in &notifications.history[history_len - added.min(history_len)..] {
+            let color = notification.severity.get_palette_color();
+            let text_entity = create_text::create(
+                &entities,
+                &lazy,
+                notification.message.clone(),
+                24.0,
+                width / 2.0,
+                get_toast_y(toast_count),
+                9.0,
+                width as u16,
+                30,
+                Color(palette_storage.get(color)),
+                Some(HorizontalAlign::Center),
+            );
+            lazy.insert(text_entity, ThemeColor(color));
+            toast_storage
+                .insert(text_entity, Toast::new(TOAST_TIME))
+                .unwrap();
+            // centered on the window rather than the map
+            anchor_storage
+                .insert(text_entity, get_toast_anchor(toast_count))
+                .unwrap();
+            let root = logic::get_root(&entity_lookup_storage, &mut node_storage);
+            root.add(text_entity);
+            toast_count += 1;
+        }
+
+        // the oldest toasts go first, at the top of the stack
+        let mut toasts = Vec::new();
+        for (entity, toast) in (&*entities, &mut toast_storage).join() {
+            toast.tick -= delta_time_storage.dt;
+            toasts.push((entity, toast.tick));
+        }
+        toasts.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        let overflow = toasts.len().saturating_sub(MAX_TOASTS);
+        let mut stacked = 0;
+        for (i, &(entity, tick)) in toasts.iter().enumerate() {
+            if tick <= 0.0 || i < overflow {
+                entities.delete(entity).unwrap();
+                continue;
+            }
+            if let Some(anchor) = anchor_storage.get_mut(entity) {
+                *anchor = get_toast_anchor(stacked);
+            }
+            stacked += 1;
+        }
+
+        let panel_entity = *entity_lookup_storage.get("notification_panel").unwrap();
+        let clicked = button_storage
+            .get_mut(*entity_lookup_storage.get("show_notifications").unwrap())
+            .map(|button| button.clicked(input))
+            .unwrap_or(false);
+
+        let mut visible = transform_storage.get(panel_entity).unwrap().visible;
+        if clicked {
+            visible = !visible;
+            self.scroll = 0;
+            self.rows_dirty = true;
+        }
+
+        let mut jump_to = None;
+        if visible {
+            for &(entity, index) in &self.rows {
+                if let (Some(button), Some(index)) = (button_storage.get_mut(entity), index) {
+                    if button.clicked(input) {
+                        jump_to = notifications.history[index].target;
+                    }
+                }
+            }
+            if jump_to.is_some() {
+                visible = false;
+            }
+
+            let max_scroll = history_len.saturating_sub(VISIBLE_ROWS) as i32;
+            let scroll = (self.scroll as i32 - input.mouse_scroll.round() as i32)
+                .max(0)
+                .min(max_scroll) as usize;
+            if scroll != self.scroll {
+                self.scroll = scroll;
+                self.rows_dirty = true;
+            }
+        }
+
+        if clicked || jump_to.is_some() {
+            transform_storage.get_mut(panel_entity).unwrap().visible = visible;
+            map_blockers_storage.set(MapBlocker::Notifications, visible);
+        }
+
+        if !visible {
+            if !self.rows.is_empty() {
+                self.remove_rows(&entities, &node_storage);
+            }
+        } else if self.rows_dirty {
+            self.remove_rows(&entities, &node_storage);
+            self.create_rows(
+                panel_entity,
+                &entities,
+                &lazy,
+                &localization_storage,
+                &mut node_storage,
+                notifications,
+                &palette_storage,
+            );
+            self.rows_dirty = false;
+        }
+
+        match jump_to {
+            Some(NotificationTarget::Tile(col, row)) => {
+                ui_commands_storage.push(UiCommand::InspectTile(col, row));
+            }
+            Some(NotificationTarget::TechNode(buff)) => {
+                ui_commands_storage.push(UiCommand::ShowTechNode(buff));
+            }
+            None => {}
+        }
+    }
+}
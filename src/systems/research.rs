@@ -1,13 +1,18 @@
 use components::{upgrade::{Buff, LearnProgress, Status, Upgrade, UpgradeLinesLookup},
-                 Color,
                  DeltaTime,
+                 EventChannel,
+                 GameEvent,
                  Node,
+                 NotificationTarget,
+                 Notifications,
                  ResearchedBuffs,
                  ResearchingEntities,
+                 RunStats,
                  Shape,
                  Transform};
 use entities::{recursive_delete,
-               tech_tree::{get_color_from_status, traverse_tree, TechTreeNode}};
+               tech_tree::{traverse_tree, TechTreeNode}};
+use localization::Localization;
 use specs::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
@@ -57,7 +62,7 @@ impl Research {
         if progress_entities_removed {
             // loop through updated array set to put in positions
             for (i, entity) in researching_entities.entities.iter().enumerate() {
-                let mut transform = transform_storage.get_mut(*entity).unwrap();
+                let transform = transform_storage.get_mut(*entity).unwrap();
                 let y = transform.get_pos().y;
                 transform.set_pos2(20.0 + 40.0 * i as f32, y);
             }
@@ -68,12 +73,15 @@ impl Research {
 impl<'a> System<'a> for Research {
     type SystemData = (
         Entities<'a>,
-        WriteStorage<'a, Color>,
         Read<'a, DeltaTime>,
+        Write<'a, EventChannel<GameEvent>>,
         ReadStorage<'a, LearnProgress>,
+        Read<'a, Localization>,
         WriteStorage<'a, Node>,
+        Write<'a, Notifications>,
         Write<'a, ResearchedBuffs>,
         Write<'a, ResearchingEntities>,
+        Write<'a, RunStats>,
         WriteStorage<'a, Shape>,
         ReadExpect<'a, TechTreeNode>,
         WriteStorage<'a, Transform>,
@@ -84,12 +92,15 @@ impl<'a> System<'a> for Research {
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            mut color_storage,
             delta_time_storage,
+            mut game_events_storage,
             learn_progress_storage,
+            localization_storage,
             node_storage,
+            mut notifications_storage,
             mut researched_buffs,
             mut researching_entities_storage,
+            mut run_stats_storage,
             mut shape_storage,
             tech_tree_storage,
             transform_storage,
@@ -102,9 +113,7 @@ impl<'a> System<'a> for Research {
         let mut upgrade_entities_researched = Vec::with_capacity(3);
         let mut researching_entities = researching_entities_storage.deref_mut();
         let mut researching_upgrades: ResearchingUpgrades = HashMap::new();
-        for (entity, color, upgrade) in
-            (&*entities, &mut color_storage, &mut upgrade_storage).join()
-        {
+        for (entity, upgrade) in (&*entities, &mut upgrade_storage).join() {
             if upgrade.status == Status::Learning {
                 upgrade.current_research_progress += dt;
                 let current_research_progress = upgrade.current_research_progress;
@@ -126,13 +135,27 @@ impl<'a> System<'a> for Research {
                     }
 
                     self.research_finished(upgrade.buff, researched_buffs.deref_mut(), level);
+                    let name = upgrade.buff.get_localized_name(&localization_storage);
+                    let message = if level > 0 {
+                        localization_storage.format(
+                            "timeline.research_level",
+                            &[("name", name), ("level", level.to_string())],
+                        )
+                    } else {
+                        localization_storage.format("timeline.research", &[("name", name)])
+                    };
+                    notifications_storage
+                        .info(message.clone(), Some(NotificationTarget::TechNode(upgrade.buff)));
+                    run_stats_storage.add_event(message);
+                    game_events_storage.write(GameEvent::ResearchCompleted {
+                        buff: upgrade.buff,
+                        level,
+                    });
 
                     // has no level or first time researched
                     if level <= 1 {
                         upgrade_entities_researched.push(entity);
                     }
-
-                    *color = Color(get_color_from_status(&upgrade.status));
                 }
                 researching_upgrades.insert(
                     upgrade.buff,
@@ -157,19 +180,13 @@ impl<'a> System<'a> for Research {
                     // update colour of sub node line bars
                     if let Some(line_entities) = upgrade_lines_lookup.entities.get(&node.entity) {
                         for entity in line_entities {
-                            let mut shape = shape_storage.get_mut(*entity).unwrap();
+                            let shape = shape_storage.get_mut(*entity).unwrap();
                             shape.set_color([0.7, 0.7, 0.7, 1.0]);
                         }
                     }
                     for sub_node in &node.sub_nodes {
                         upgrade_storage.get_mut(sub_node.entity).unwrap().status =
                             Status::Researchable;
-                        color_storage
-                            .insert(
-                                sub_node.entity,
-                                Color(get_color_from_status(&Status::Researchable)),
-                            )
-                            .unwrap();
                     }
                     return true;
                 }
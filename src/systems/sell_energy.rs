@@ -1,35 +1,45 @@
 use std::ops::{Deref, DerefMut};
 
-use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
+use specs::{Join, Read, ReadStorage, System, Write, WriteStorage};
 
-use components::{ui::{TutorialUI, WalletUI},
+use components::{ui::WalletUI,
                  upgrade::Buff,
-                 Actions,
                  Button,
                  CityPowerState,
-                 Color,
                  DeltaTime,
                  EntityLookup,
+                 EventChannel,
+                 GameEvent,
                  Gatherer,
-                 GathererType,
                  GatheringRate,
                  Input,
-                 Node,
+                 Notifications,
+                 PaletteColor,
                  PowerBar,
                  ResearchedBuffs,
                  ResourceType,
                  Resources,
+                 RunStats,
+                 StatSample,
                  StateChange,
+                 StateId,
+                 Statistics,
                  Text,
+                 ThemeColor,
                  Transform,
-                 TutorialStep,
-                 Wallet,
-                 STARTING_TICK};
-use entities::tutorial;
-use state::play_state::PlayState;
-use systems::{logic, POWER_FACTOR, TICK_RATE};
+                 Transition,
+                 Wallet};
+use key_bindings::InputAction;
+use localization::Localization;
+use rules::Rules;
+use systems::{logic, POWER_FACTOR};
+
+// tax has to grow by this much, and by half, between ticks to be reported as a spike
+const TAX_SPIKE_MIN: i32 = 5;
 
 pub struct SellEnergy {
+    in_deficit: bool,
+    last_tax: i32,
     minute_ticker: f32,
     sell_ticker: f32,
 }
@@ -37,6 +47,8 @@ pub struct SellEnergy {
 impl SellEnergy {
     pub fn new() -> Self {
         SellEnergy {
+            in_deficit: false,
+            last_tax: 0,
             minute_ticker: 0.0,
             sell_ticker: 0.0,
         }
@@ -53,11 +65,12 @@ impl SellEnergy {
             }
 
             let width =
-                PowerBar::get_max_width() * (power_bar.power_left as f32 / PowerBar::get_max_f32());
+                PowerBar::get_max_width() * (power_bar.power_left as f32 / power_bar.get_max_f32());
             transform.size.x = width as u16;
         }
     }
 
+    // returns the money made, and whether the cities ran out of power
     fn sell_power_to_cities(
         &self,
         mut power_to_spend: i32,
@@ -65,14 +78,14 @@ impl SellEnergy {
         transform_storage: &mut WriteStorage<Transform>,
         power_bar_storage: &mut WriteStorage<PowerBar>,
         state_change_storage: &mut Write<StateChange>,
-        actions_storage: &mut Write<Actions>,
-    ) -> i32 {
+    ) -> (i32, bool) {
         let money_from_power = power_to_spend;
+        let mut blackout = false;
         wallet_storage.add_money(money_from_power);
         power_to_spend *= POWER_FACTOR;
 
         for (transform, power_bar) in (&mut *transform_storage, power_bar_storage).join() {
-            let amount_to_power = PowerBar::get_max() - power_bar.power_left;
+            let amount_to_power = power_bar.get_max() - power_bar.power_left;
             power_to_spend -= amount_to_power;
             if power_to_spend >= 0 {
                 power_bar.add_power(amount_to_power);
@@ -85,18 +98,18 @@ impl SellEnergy {
             }
 
             if power_bar.power_left <= 0 {
-                actions_storage.dispatch("display_end_screen".to_string(), "".to_string());
+                blackout = true;
                 power_bar.power_left = 0;
                 let state_change = state_change_storage.deref_mut();
-                state_change.set(PlayState::get_name(), "end".to_string());
+                state_change.set(Transition::Push(StateId::End));
             }
 
             let width =
-                PowerBar::get_max_width() * (power_bar.power_left as f32 / PowerBar::get_max_f32());
+                PowerBar::get_max_width() * (power_bar.power_left as f32 / power_bar.get_max_f32());
             transform.size.x = width as u16;
         }
 
-        money_from_power
+        (money_from_power, blackout)
     }
 
     fn update_gathering_rate_ui(
@@ -104,37 +117,53 @@ impl SellEnergy {
         gathering_rate_storage: &Read<GatheringRate>,
         text_storage: &mut WriteStorage<Text>,
         entity_lookup_storage: &Read<EntityLookup>,
+        localization: &Localization,
         power_to_spend: i32,
     ) {
         if gathering_rate_storage.changed() {
             let entity = entity_lookup_storage.get("gathering_rate_coal").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                text.set_text(format!("Coal: {}", gathering_rate_storage.coal));
+                text.set_text(localization.format(
+                    "sidebar.coal",
+                    &[("amount", localization.number(gathering_rate_storage.coal as i64))],
+                ));
             }
 
             let entity = entity_lookup_storage.get("gathering_rate_oil").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                text.set_text(format!("Oil: {}", gathering_rate_storage.oil));
+                text.set_text(localization.format(
+                    "sidebar.oil",
+                    &[("amount", localization.number(gathering_rate_storage.oil as i64))],
+                ));
             }
 
             let entity = entity_lookup_storage.get("gathering_rate_hydro").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                text.set_text(format!("Hydro: {}", gathering_rate_storage.hydro));
+                text.set_text(localization.format(
+                    "sidebar.hydro",
+                    &[("amount", localization.number(gathering_rate_storage.hydro as i64))],
+                ));
             }
 
             let entity = entity_lookup_storage.get("gathering_rate_solar").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                text.set_text(format!("Solar: {}", gathering_rate_storage.solar));
+                text.set_text(localization.format(
+                    "sidebar.solar",
+                    &[("amount", localization.number(gathering_rate_storage.solar as i64))],
+                ));
             }
 
             let entity = entity_lookup_storage.get("gathering_rate_power").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                text.set_text(format!("Power: {}", power_to_spend));
+                text.set_text(localization.format(
+                    "sidebar.power",
+                    &[("amount", localization.number(power_to_spend as i64))],
+                ));
             }
         }
     }
@@ -145,8 +174,9 @@ impl SellEnergy {
         power_bar_storage: &WriteStorage<PowerBar>,
         city_power_state: &CityPowerState,
         lookup: &EntityLookup,
-        color_storage: &mut WriteStorage<Color>,
+        localization: &Localization,
         text_storage: &mut WriteStorage<Text>,
+        theme_color_storage: &mut WriteStorage<ThemeColor>,
     ) {
         let gathering_rate = gathering_rate_storage.deref();
 
@@ -158,27 +188,33 @@ impl SellEnergy {
             / POWER_FACTOR;
 
         let total_gathering_rate = logic::get_total_gathering_rate(&gathering_rate);
-
-        let powering_text = if city_power_state.current_city_count > 1 {
-            format!(
-                "Power: {}\n{} cities",
-                total_gathering_rate - power_demands,
-                city_power_state.current_city_count
-            )
-        } else {
-            format!("Power: {}\n1 city", total_gathering_rate - power_demands)
-        };
+        let surplus = total_gathering_rate >= power_demands;
+
+        // the sign tells a surplus from a deficit without relying on the color
+        let balance = localization.number((total_gathering_rate - power_demands) as i64);
+        let powering_text = localization.plural(
+            "sidebar.power_balance",
+            city_power_state.current_city_count as i64,
+            &[(
+                "power",
+                if surplus {
+                    format!("+{}", balance)
+                } else {
+                    balance
+                },
+            )],
+        );
 
         let power_gain_entity = lookup.entities.get(&"power_gain_text".to_string()).unwrap();
         text_storage.get_mut(*power_gain_entity).unwrap().text = powering_text;
 
-        color_storage
+        theme_color_storage
             .insert(
                 *power_gain_entity,
-                Color(if total_gathering_rate >= power_demands {
-                    [0.0, 0.6, 0.0, 1.0]
+                ThemeColor(if surplus {
+                    PaletteColor::PowerSurplus
                 } else {
-                    [0.6, 0.0, 0.0, 1.0]
+                    PaletteColor::PowerDeficit
                 }),
             )
             .unwrap();
@@ -187,50 +223,52 @@ impl SellEnergy {
 
 impl<'a> System<'a> for SellEnergy {
     type SystemData = (
-        Entities<'a>,
-        Write<'a, Actions>,
         WriteStorage<'a, Button>,
         Write<'a, CityPowerState>,
-        WriteStorage<'a, Color>,
         Read<'a, DeltaTime>,
         Read<'a, EntityLookup>,
+        Write<'a, EventChannel<GameEvent>>,
         ReadStorage<'a, Gatherer>,
         Read<'a, GatheringRate>,
         Read<'a, Input>,
-        WriteStorage<'a, Node>,
+        Read<'a, Localization>,
+        Write<'a, Notifications>,
         WriteStorage<'a, PowerBar>,
         Read<'a, ResearchedBuffs>,
         Write<'a, Resources>,
+        Read<'a, Rules>,
+        Write<'a, RunStats>,
         Write<'a, StateChange>,
+        Write<'a, Statistics>,
         WriteStorage<'a, Text>,
+        WriteStorage<'a, ThemeColor>,
         WriteStorage<'a, Transform>,
-        Write<'a, TutorialStep>,
-        ReadStorage<'a, TutorialUI>,
         Write<'a, Wallet>,
         WriteStorage<'a, WalletUI>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
-            entities,
-            mut actions_storage,
             mut button_storage,
             mut city_power_state_storage,
-            mut color_storage,
             delta_time_storage,
             entity_lookup_storage,
+            mut game_events_storage,
             gatherer_storage,
             gathering_rate_storage,
             input_storage,
-            node_storage,
+            localization_storage,
+            mut notifications_storage,
             mut power_bar_storage,
             researched_buffs_storage,
             mut resources_storage,
+            rules_storage,
+            mut run_stats_storage,
             mut state_change_storage,
+            mut statistics_storage,
             mut text_storage,
+            mut theme_color_storage,
             mut transform_storage,
-            mut tutorial_step_storage,
-            tutorial_ui_storage,
             mut wallet_storage,
             mut wallet_ui_storage,
         ) = data;
@@ -240,19 +278,30 @@ impl<'a> System<'a> for SellEnergy {
         let researched_buffs = researched_buffs_storage.deref();
 
         self.sell_ticker += delta_time_storage.deref().dt;
+        run_stats_storage.time_survived += delta_time_storage.deref().dt;
 
         let button = button_storage
             .get_mut(*entity_lookup_storage.get("power_additional_city").unwrap())
             .unwrap();
-        if button.clicked(&input_storage) {
+        if button.clicked(&input_storage)
+            || (!button.disabled && input_storage.action_pressed(InputAction::AddCity))
+        {
             city_power_state_storage.current_city_count += 1;
+            let message = localization_storage.format(
+                "timeline.city_added",
+                &[(
+                    "city",
+                    localization_storage.number(city_power_state_storage.current_city_count as i64),
+                )],
+            );
+            notifications_storage.info(message.clone(), None);
+            run_stats_storage.add_event(message);
+            game_events_storage.write(GameEvent::CityAdded {
+                city_count: city_power_state_storage.current_city_count,
+            });
             for power_bar in (&mut power_bar_storage).join() {
-                let mut per_tick = STARTING_TICK;
-                // each city is more demanding
-                for n in 0..city_power_state_storage.current_city_count {
-                    per_tick += 15 * ((n as i32) + 1);
-                }
-                power_bar.power_per_tick = per_tick;
+                power_bar.power_per_tick =
+                    rules_storage.get_power_per_tick(city_power_state_storage.current_city_count);
             }
 
             self.update_power_ui(
@@ -260,33 +309,22 @@ impl<'a> System<'a> for SellEnergy {
                 &power_bar_storage,
                 &city_power_state_storage,
                 &entity_lookup_storage,
-                &mut color_storage,
+                &localization_storage,
                 &mut text_storage,
+                &mut theme_color_storage,
             );
         }
 
-        if self.sell_ticker > TICK_RATE {
+        if self.sell_ticker > rules_storage.tick_rate {
             self.sell_ticker = 0.0;
             self.remove_power_based_on_demand(&mut transform_storage, &mut power_bar_storage);
             // divide by power factor, so demand can be met based on resource numbers
             let mut amount_to_power = (&power_bar_storage).join().fold(0, |sum, power_bar| {
-                PowerBar::get_max() - power_bar.power_left + sum
+                power_bar.get_max() - power_bar.power_left + sum
             }) / POWER_FACTOR;
 
             let mut power_to_spend = 0i32;
 
-            if wallet_storage.get_money() > 10 {
-                tutorial::next_step(
-                    &entities,
-                    &mut actions_storage,
-                    &mut tutorial_step_storage,
-                    &tutorial_ui_storage,
-                    &node_storage,
-                    TutorialStep::CoalGathered,
-                    TutorialStep::ResourcesSold,
-                );
-            }
-
             'resources: for r_type in &[
                 ResourceType::Coal,
                 ResourceType::Oil,
@@ -315,76 +353,118 @@ impl<'a> System<'a> for SellEnergy {
                 &gathering_rate_storage,
                 &mut text_storage,
                 &entity_lookup_storage,
+                &localization_storage,
                 power_to_spend,
             );
 
-            let money_from_power = self.sell_power_to_cities(
+            let (money_from_power, blackout) = self.sell_power_to_cities(
                 power_to_spend,
                 &mut wallet_storage,
                 &mut transform_storage,
                 &mut power_bar_storage,
                 &mut state_change_storage,
-                &mut actions_storage,
             );
 
-            let mut coal_pollution = 0;
-            let mut oil_pollution = 0;
-            let mut hydro_pollution = 0;
-            let researched_buffs = researched_buffs_storage.deref();
-
-            for gatherer in (&gatherer_storage).join() {
-                let mut amount = gatherer.pollution;
-                if amount > 0 {
-                    if gatherer.gatherer_type == GathererType::Coal {
-                        coal_pollution += amount;
-                        if let Some(n) = researched_buffs.0.get(&Buff::ConveyerBelts) {
-                            coal_pollution += *n as i32;
-                        }
-                    } else if gatherer.gatherer_type == GathererType::Oil {
-                        oil_pollution += amount;
-                        if let Some(n) = researched_buffs.0.get(&Buff::AutomatedRefiners) {
-                            oil_pollution += 2 * *n as i32;
-                        }
-                    } else if gatherer.gatherer_type == GathererType::Hydro {
-                        hydro_pollution += amount;
-                    }
-                }
-            }
+            let pollution =
+                logic::get_total_pollution((&gatherer_storage).join(), researched_buffs);
 
-            if researched_buffs.0.contains_key(&Buff::PollutionFilters) {
-                coal_pollution -= coal_pollution * 20 / 100;
-            }
-            if researched_buffs.0.contains_key(&Buff::FudgeTheNumbers) {
-                oil_pollution -= oil_pollution * 20 / 100;
+            let tax = rules_storage.get_tax(money_from_power, pollution);
+            if tax > 0 {
+                wallet_storage.remove_amount(tax);
             }
-            if researched_buffs.0.contains_key(&Buff::SalmonCannon) {
-                hydro_pollution -= hydro_pollution * 20 / 100;
+
+            if tax - self.last_tax >= TAX_SPIKE_MIN && tax * 2 >= self.last_tax * 3 {
+                notifications_storage.warning(
+                    localization_storage.format(
+                        "notification.tax_spike",
+                        &[
+                            ("before", localization_storage.money(self.last_tax)),
+                            ("after", localization_storage.money(tax)),
+                        ],
+                    ),
+                    None,
+                );
             }
+            self.last_tax = tax;
 
-            let pollution = coal_pollution + oil_pollution + hydro_pollution;
+            game_events_storage.write(GameEvent::EnergySold {
+                income: money_from_power,
+                tax,
+            });
 
-            let tax = if pollution > 0 {
-                let tax = (money_from_power as f32 * (pollution as f32 / 100.0)) as i32;
-                wallet_storage.remove_amount(tax);
-                tax
+            run_stats_storage.total_income += money_from_power;
+            run_stats_storage.total_tax += tax;
+            if pollution > run_stats_storage.peak_pollution {
+                run_stats_storage.peak_pollution = pollution;
+            }
+
+            let power_demands = (&power_bar_storage)
+                .join()
+                .fold(0, |sum, power_bar| sum + power_bar.power_per_tick)
+                / POWER_FACTOR;
+            let power_production = logic::get_total_gathering_rate(gathering_rate_storage.deref());
+            statistics_storage.add_sample(StatSample {
+                coal: gathering_rate_storage.coal,
+                oil: gathering_rate_storage.oil,
+                solar: gathering_rate_storage.solar,
+                hydro: gathering_rate_storage.hydro,
+                income: money_from_power,
+                tax,
+                pollution,
+                power_balance: power_production - power_demands,
+            });
+
+            // warn once each time production falls below demand
+            let in_deficit = power_production < power_demands;
+            if in_deficit && !self.in_deficit {
+                notifications_storage.warning(
+                    localization_storage.format(
+                        "notification.power_deficit",
+                        &[(
+                            "amount",
+                            localization_storage.number((power_demands - power_production) as i64),
+                        )],
+                    ),
+                    None,
+                );
+            }
+            self.in_deficit = in_deficit;
+
+            if blackout {
+                game_events_storage.write(GameEvent::Blackout);
+                run_stats_storage.set_blackout_cause(
+                    power_production,
+                    power_demands,
+                    city_power_state_storage.current_city_count,
+                    &localization_storage,
+                );
             } else {
-                0
-            };
+                run_stats_storage.update_power_balance(
+                    power_production - power_demands,
+                    resources,
+                    &localization_storage,
+                );
+            }
 
             // this could be potentially optimized by tracking last tax & money amounts in a resource, and check if it changes.
             // though gfx-glyph cache should do plenty for us
             let entity = entity_lookup_storage.get("gathering_rate_money").unwrap();
             {
                 let text = text_storage.get_mut(*entity).unwrap();
-                let sign = if tax > 0 { "-" } else { "" };
-                text.set_text(format!(
-                    "Income: ${}, Tax: {}${}",
-                    money_from_power, sign, tax
+                text.set_text(localization_storage.format(
+                    "sidebar.income_tax",
+                    &[
+                        ("income", localization_storage.money(money_from_power)),
+                        ("tax", localization_storage.money(-tax)),
+                    ],
                 ));
             }
 
             logic::update_text_mut(
-                format!("Wallet: ${}", wallet_storage.get_money()),
+                localization_storage.format(
+                    "sidebar.wallet",
+                    &[("money", localization_storage.money(wallet_storage.get_money()))],
+                ),
                 &mut text_storage,
                 &mut wallet_ui_storage,
             );
@@ -395,8 +475,9 @@ impl<'a> System<'a> for SellEnergy {
             &power_bar_storage,
             city_power_state_storage.deref(),
             &entity_lookup_storage,
-            &mut color_storage,
+            &localization_storage,
             &mut text_storage,
+            &mut theme_color_storage,
         );
 
         if researched_buffs
@@ -408,7 +489,10 @@ impl<'a> System<'a> for SellEnergy {
                 self.minute_ticker = 0.0;
                 wallet_storage.add_money(5000);
                 logic::update_text_mut(
-                    format!("Wallet ${}", wallet_storage.get_money()),
+                    localization_storage.format(
+                        "sidebar.wallet",
+                        &[("money", localization_storage.money(wallet_storage.get_money()))],
+                    ),
                     &mut text_storage,
                     &mut wallet_ui_storage,
                 );
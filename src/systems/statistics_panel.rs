@@ -0,0 +1,102 @@
+use components::{ui::StatisticsGraph, Button, EntityLookup, Input, MapBlocker, MapBlockers, Palette,
+                 Shape, Statistics, Text, Theme, Transform};
+use entities::create_statistics_panel::{get_graph_label, get_graph_points};
+use localization::Localization;
+use specs::{Join, Read, ReadStorage, System, Write, WriteStorage};
+
+pub struct StatisticsPanel {
+    drawn_samples: usize,
+    drawn_theme: Theme,
+    drawn_language: String,
+}
+
+impl StatisticsPanel {
+    pub fn new() -> Self {
+        StatisticsPanel {
+            drawn_samples: 0,
+            drawn_theme: Theme::default(),
+            drawn_language: String::new(),
+        }
+    }
+}
+
+impl<'a> System<'a> for StatisticsPanel {
+    type SystemData = (
+        WriteStorage<'a, Button>,
+        Read<'a, EntityLookup>,
+        Read<'a, Input>,
+        Read<'a, Localization>,
+        Write<'a, MapBlockers>,
+        Read<'a, Palette>,
+        WriteStorage<'a, Shape>,
+        Read<'a, Statistics>,
+        ReadStorage<'a, StatisticsGraph>,
+        WriteStorage<'a, Text>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut button_storage,
+            entity_lookup_storage,
+            input_storage,
+            localization_storage,
+            mut map_blockers_storage,
+            palette_storage,
+            mut shape_storage,
+            statistics_storage,
+            statistics_graph_storage,
+            mut text_storage,
+            mut transform_storage,
+        ) = data;
+
+        let panel_entity = *entity_lookup_storage.get("statistics_panel").unwrap();
+
+        let clicked = button_storage
+            .get_mut(*entity_lookup_storage.get("show_statistics").unwrap())
+            .unwrap()
+            .clicked(&input_storage);
+
+        let visible = {
+            let transform = transform_storage.get_mut(panel_entity).unwrap();
+            if clicked {
+                transform.visible = !transform.visible;
+                // force a redraw, the graphs are not updated while hidden
+                self.drawn_samples = 0;
+            }
+            transform.visible
+        };
+
+        map_blockers_storage.set(MapBlocker::Statistics, visible);
+
+        // the lines carry their own color, so a theme change redraws them too, as does a language
+        // change for the labels
+        if !visible
+            || (self.drawn_samples == statistics_storage.get_total_samples()
+                && self.drawn_theme == palette_storage.theme
+                && self.drawn_language == localization_storage.get_code())
+        {
+            return;
+        }
+        self.drawn_samples = statistics_storage.get_total_samples();
+        self.drawn_theme = palette_storage.theme;
+        self.drawn_language = localization_storage.get_code().to_string();
+
+        for (graph, shape) in (&statistics_graph_storage, &mut shape_storage).join() {
+            let values = statistics_storage.get_values(&graph.series);
+            let color = palette_storage.get(graph.series.get_palette_color());
+            *shape = Shape::new_line(get_graph_points(&values), color);
+        }
+
+        for (graph, text) in (&statistics_graph_storage, &mut text_storage).join() {
+            let values = statistics_storage.get_values(&graph.series);
+            let peak = values.iter().cloned().max().unwrap_or(0);
+            text.set_text(get_graph_label(
+                &graph.series,
+                statistics_storage.get_latest(&graph.series),
+                peak,
+                &localization_storage,
+            ));
+        }
+    }
+}
@@ -1,32 +1,46 @@
 use components::ui;
 use components::{ui::{TutorialUI, WalletUI},
                  upgrade::{Buff, LearnProgress},
-                 Actions,
+                 Button,
                  Color,
                  EntityLookup,
                  Input,
                  Node,
+                 NotificationTarget,
+                 Notifications,
                  Rect,
                  ResearchedBuffs,
                  ResearchingEntities,
+                 RunStats,
                  Sprite,
                  Text,
                  Transform,
                  TutorialStep,
+                 UiCommands,
                  Wallet};
 use entities::{create_text,
                create_tooltip,
                recursive_delete,
-               tech_tree::{get_color_from_status, Status, Upgrade},
+               tech_tree::{Status, Upgrade},
                tutorial};
+use key_bindings::InputAction;
+use localization::Localization;
 use specs::{Entities, Entity, Join, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
 use std::ops::{Deref, DerefMut};
-use storage_types::*;
-use systems::logic;
+use systems::{logic, UNDO_GRACE_PERIOD};
+
+// a research start that can still be undone
+struct ResearchRecord {
+    time: f32,
+    upgrade_entity: Entity,
+    progress_entity: Entity,
+    cost: i32,
+}
 
 pub struct TechTree {
     current_tooltip: Option<Entity>,
     current_tech_tree_node_entity: Option<Entity>,
+    recent_research: Vec<ResearchRecord>,
 }
 
 impl TechTree {
@@ -34,9 +48,59 @@ impl TechTree {
         TechTree {
             current_tooltip: None,
             current_tech_tree_node_entity: None,
+            recent_research: Vec::new(),
         }
     }
 
+    // research that finished, is from a previous run, or is past the grace period can no
+    // longer be undone
+    fn expire_research(
+        &mut self,
+        entities: &Entities,
+        upgrade_storage: &WriteStorage<Upgrade>,
+        time: f32,
+    ) {
+        self.recent_research.retain(|research| {
+            entities.is_alive(research.progress_entity)
+                && upgrade_storage
+                    .get(research.upgrade_entity)
+                    .map(|upgrade| upgrade.status == Status::Learning)
+                    .unwrap_or(false) && time >= research.time
+                && time - research.time <= UNDO_GRACE_PERIOD
+        });
+    }
+
+    // stops the latest research start, returning its cost to refund
+    fn undo_research(
+        &mut self,
+        entities: &Entities,
+        node_storage: &WriteStorage<Node>,
+        researching_entities: &mut ResearchingEntities,
+        transform_storage: &mut WriteStorage<Transform>,
+        upgrade_storage: &mut WriteStorage<Upgrade>,
+    ) -> Option<i32> {
+        let research = match self.recent_research.pop() {
+            Some(research) => research,
+            None => return None,
+        };
+
+        let upgrade = upgrade_storage.get_mut(research.upgrade_entity).unwrap();
+        upgrade.status = Status::Researchable;
+        upgrade.current_research_progress = 0.0;
+
+        recursive_delete(entities, node_storage, &research.progress_entity);
+        researching_entities
+            .entities
+            .retain(|entity| *entity != research.progress_entity);
+        for (i, entity) in researching_entities.entities.iter().enumerate() {
+            let transform = transform_storage.get_mut(*entity).unwrap();
+            let y = transform.get_pos().y;
+            transform.set_pos2(33.0 + 40.0 * i as f32, y);
+        }
+
+        Some(research.cost)
+    }
+
     fn build_research_progress_ui(
         &self,
         buff: Buff,
@@ -99,21 +163,25 @@ impl<'a> System<'a> for TechTree {
     type SystemData = (
         Entities<'a>,
         Read<'a, LazyUpdate>,
-        Write<'a, Actions>,
+        WriteStorage<'a, Button>,
         WriteStorage<'a, Color>,
         Read<'a, EntityLookup>,
         Read<'a, Input>,
         WriteStorage<'a, LearnProgress>,
+        Read<'a, Localization>,
         WriteStorage<'a, Node>,
+        Write<'a, Notifications>,
         WriteStorage<'a, Rect>,
         Write<'a, ResearchedBuffs>,
         Write<'a, ResearchingEntities>,
+        Write<'a, RunStats>,
         WriteStorage<'a, Sprite>,
         ReadStorage<'a, ui::TechTreeButton>,
         WriteStorage<'a, Text>,
         Write<'a, TutorialStep>,
         ReadStorage<'a, TutorialUI>,
         WriteStorage<'a, Transform>,
+        Write<'a, UiCommands>,
         WriteStorage<'a, Upgrade>,
         Write<'a, Wallet>,
         ReadStorage<'a, WalletUI>,
@@ -123,21 +191,25 @@ impl<'a> System<'a> for TechTree {
         let (
             entities,
             lazy,
-            mut actions_storage,
+            mut button_storage,
             mut color_storage,
             entity_lookup_storage,
             input_storage,
             mut learn_progress_storage,
+            localization_storage,
             mut node_storage,
+            mut notifications_storage,
             mut rect_storage,
             researched_buffs_storage,
             mut researching_entities_storage,
+            mut run_stats_storage,
             mut sprite_storage,
             tech_tree_node_storage,
             mut text_storage,
             mut tutorial_step_storage,
             tutorial_ui_storage,
             mut transform_storage,
+            mut ui_commands_storage,
             mut upgrade_storage,
             mut wallet_storage,
             wallet_ui_storage,
@@ -146,6 +218,49 @@ impl<'a> System<'a> for TechTree {
         let input: &Input = input_storage.deref();
         let lookup: &EntityLookup = entity_lookup_storage.deref();
 
+        self.expire_research(&entities, &upgrade_storage, run_stats_storage.time_survived);
+        let mut undo_pressed = input.ctrl_held() && input.action_pressed(InputAction::Undo);
+        for button in (&mut button_storage).join() {
+            if button.name == "undo_research" && button.clicked(&input) {
+                undo_pressed = true;
+            }
+        }
+        if undo_pressed {
+            match self.undo_research(
+                &entities,
+                &node_storage,
+                researching_entities_storage.deref_mut(),
+                &mut transform_storage,
+                &mut upgrade_storage,
+            ) {
+                Some(cost) => {
+                    let wallet: &mut Wallet = wallet_storage.deref_mut();
+                    wallet.add_money(cost);
+                    run_stats_storage.spent_on_research -= cost;
+                    logic::update_text(
+                        localization_storage.format(
+                            "sidebar.wallet",
+                            &[("money", localization_storage.money(wallet.get_money()))],
+                        ),
+                        &mut text_storage,
+                        &wallet_ui_storage,
+                    );
+                }
+                None => notifications_storage
+                    .error(localization_storage.get("error.nothing_to_undo"), None),
+            }
+        }
+
+        // the undo button only shows while there is a research start to take back
+        let can_undo = !self.recent_research.is_empty();
+        let undo_entity = *lookup.get("undo_research").unwrap();
+        if let Some(transform) = transform_storage.get_mut(undo_entity) {
+            transform.visible = can_undo;
+        }
+        if let Some(button) = button_storage.get_mut(undo_entity) {
+            button.set_disabled(!can_undo);
+        }
+
         let mut mouse_over_tech_tree_node_entity = None;
         let mut tooltip_position = [0.0, 0.0];
 
@@ -154,8 +269,12 @@ impl<'a> System<'a> for TechTree {
         for (entity, _, transform) in
             (&*entities, &tech_tree_node_storage, &transform_storage).join()
         {
-            let absolute_pos =
-                Node::get_absolute_pos(root_entity, &entity, &transform_storage, &node_storage);
+            let (absolute_pos, absolute_scale) = Node::get_absolute_transform(
+                root_entity,
+                &entity,
+                &transform_storage,
+                &node_storage,
+            );
             let abs_transform = Transform::visible(
                 absolute_pos.x,
                 absolute_pos.y,
@@ -163,8 +282,8 @@ impl<'a> System<'a> for TechTree {
                 transform.size.x,
                 transform.size.y,
                 transform.rotation,
-                transform.scale.x,
-                transform.scale.y,
+                absolute_scale.x,
+                absolute_scale.y,
             );
             if abs_transform.contains(&input.mouse_pos.0, &input.mouse_pos.1) {
                 mouse_over_tech_tree_node_entity = Some(entity.clone());
@@ -203,7 +322,7 @@ impl<'a> System<'a> for TechTree {
 
                 let tooltip_size = (180, 180);
 
-                let mut tooltip_entity = create_tooltip::create(
+                let tooltip_entity = create_tooltip::create(
                     &entities,
                     &mut color_storage,
                     &mut node_storage,
@@ -216,19 +335,16 @@ impl<'a> System<'a> for TechTree {
                     container_h as i32,
                     tooltip_size.0,
                     tooltip_size.1,
-                    tech_tree_node_ui.text.clone(),
+                    // translations replace the description from tech_tree.json
+                    localization_storage.get_or(
+                        &format!("tech.{:?}", upgrade.buff),
+                        &tech_tree_node_ui.text,
+                    ),
                     None,
                 );
                 self.current_tooltip = Some(tooltip_entity.clone());
                 self.current_tech_tree_node_entity = Some(mouse_over_tech_tree_node_entity.clone());
 
-                let mut text_storage_type = TextStorage {
-                    entities: &entities,
-                    color_storage: &mut color_storage,
-                    text_storage: &mut text_storage,
-                    transform_storage: &mut transform_storage,
-                };
-
                 {
                     let text_y = (tooltip_size.1 - 30) as f32;
                     let tooltip_node = node_storage.get_mut(tooltip_entity).unwrap();
@@ -244,7 +360,8 @@ impl<'a> System<'a> for TechTree {
                         let text = create_text::create(
                             &entities,
                             &lazy,
-                            format!("lvl {}", level),
+                            localization_storage
+                                .format("tech_tree.level", &[("level", level.to_string())]),
                             20.0,
                             80.0,
                             text_y,
@@ -261,7 +378,7 @@ impl<'a> System<'a> for TechTree {
                         let text = create_text::create(
                             &entities,
                             &lazy,
-                            "Researched".to_string(),
+                            localization_storage.get("tech_tree.researched"),
                             20.0,
                             5.0,
                             text_y,
@@ -276,7 +393,7 @@ impl<'a> System<'a> for TechTree {
                         let text = create_text::create(
                             &entities,
                             &lazy,
-                            format!("${}", upgrade.cost),
+                            localization_storage.money(upgrade.cost),
                             20.0,
                             5.0,
                             text_y,
@@ -313,10 +430,11 @@ impl<'a> System<'a> for TechTree {
                     .unwrap();
                 if upgrade.status == Status::Researchable {
                     if wallet.spend(upgrade.cost) {
+                        run_stats_storage.spent_on_research += upgrade.cost;
                         if upgrade.buff == Buff::ResourceTrading {
                             tutorial::next_step(
                                 &entities,
-                                &mut actions_storage,
+                                &mut ui_commands_storage,
                                 &mut tutorial_step_storage,
                                 &tutorial_ui_storage,
                                 &node_storage,
@@ -325,9 +443,6 @@ impl<'a> System<'a> for TechTree {
                             );
                         }
                         upgrade.start_learning();
-                        *color_storage
-                            .get_mut(mouse_over_tech_tree_node_entity)
-                            .unwrap() = Color(get_color_from_status(&upgrade.status));
                         let researching_entities = researching_entities_storage.deref_mut();
                         let sprite = (*sprite_storage
                             .get(mouse_over_tech_tree_node_entity)
@@ -347,15 +462,24 @@ impl<'a> System<'a> for TechTree {
                             researching_entities.entities.len(),
                         );
                         researching_entities.entities.push(progress_entity);
+                        self.recent_research.push(ResearchRecord {
+                            time: run_stats_storage.time_survived,
+                            upgrade_entity: mouse_over_tech_tree_node_entity,
+                            progress_entity,
+                            cost: upgrade.cost,
+                        });
                         logic::update_text(
-                            format!("Wallet: ${}", wallet.get_money()),
+                            localization_storage.format(
+                                "sidebar.wallet",
+                                &[("money", localization_storage.money(wallet.get_money()))],
+                            ),
                             &mut text_storage,
                             &wallet_ui_storage,
                         );
                     } else {
-                        actions_storage.dispatch(
-                            "display_error".to_string(),
-                            "Not enough money to upgrade".to_string(),
+                        notifications_storage.error(
+                            localization_storage.get("error.upgrade_money"),
+                            Some(NotificationTarget::TechNode(upgrade.buff)),
                         );
                     }
                 }
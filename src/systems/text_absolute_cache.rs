@@ -21,8 +21,8 @@ impl<'a> System<'a> for TextAbsoluteCache {
         let root_entity = lookup.get("root").unwrap();
 
         for (entity, _) in (&*entities, &text_strorage).join() {
-            let absolute_pos = if transform_storage.get(entity).unwrap().dirty_pos {
-                Some(Node::get_absolute_pos(
+            let absolute_transform = if transform_storage.get(entity).unwrap().dirty_pos {
+                Some(Node::get_absolute_transform(
                     root_entity,
                     &entity,
                     &transform_storage,
@@ -32,11 +32,11 @@ impl<'a> System<'a> for TextAbsoluteCache {
                 None
             };
 
-            if let Some(absolute_pos) = absolute_pos {
+            if let Some((absolute_pos, absolute_scale)) = absolute_transform {
                 transform_storage
                     .get_mut(entity)
                     .unwrap()
-                    .set_absolute_pos(absolute_pos);
+                    .set_absolute_pos(absolute_pos, absolute_scale);
             }
         }
     }
@@ -1,19 +1,22 @@
-use components::{ui::TutorialUI, Actions, Button, Color, EffectedByPollutionTiles, EntityLookup,
-                 Gatherer, Input, Node, Rect, ResearchedBuffs, SelectedTile, Sprite, Text, Tile,
-                 Transform, TutorialStep};
+use components::{ui::TutorialUI, Button, EntityLookup, Gatherer, Input, Node, ResearchedBuffs,
+                 SelectedTile, Tile, TileCursor, Transform, TutorialStep, UiCommands};
 use entities::{create_build_ui, recursive_delete, tutorial};
+use key_bindings::InputAction;
 use specs::{Entities, Entity, Join, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
 use std::ops::Deref;
 use systems::logic;
 
 pub struct TileSelection {
     build_ui_entity: Option<Entity>,
+    // where the tile cursor was last frame, to tell when it moves
+    cursor_pos: Option<(f32, f32)>,
 }
 
 impl TileSelection {
     pub fn new() -> TileSelection {
         TileSelection {
             build_ui_entity: None,
+            cursor_pos: None,
         }
     }
 }
@@ -22,46 +25,38 @@ impl<'a> System<'a> for TileSelection {
     type SystemData = (
         Entities<'a>,
         Read<'a, LazyUpdate>,
-        Write<'a, Actions>,
         WriteStorage<'a, Button>,
-        WriteStorage<'a, Color>,
-        WriteStorage<'a, EffectedByPollutionTiles>,
         Read<'a, EntityLookup>,
         ReadStorage<'a, Gatherer>,
         Read<'a, Input>,
         WriteStorage<'a, Node>,
-        WriteStorage<'a, Rect>,
         Read<'a, ResearchedBuffs>,
         ReadStorage<'a, SelectedTile>,
-        WriteStorage<'a, Sprite>,
-        WriteStorage<'a, Text>,
         ReadStorage<'a, Tile>,
+        ReadStorage<'a, TileCursor>,
         WriteStorage<'a, Transform>,
         Write<'a, TutorialStep>,
         ReadStorage<'a, TutorialUI>,
+        Write<'a, UiCommands>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
             lazy,
-            mut actions_storage,
             mut button_storage,
-            mut color_storage,
-            mut effected_by_pollution_tiles_storage,
             entity_lookup_storage,
             gatherer_storage,
             input_storage,
             mut node_storage,
-            mut rect_storage,
             researched_buffs,
             selected_tile_storage,
-            mut sprite_storage,
-            mut text_storage,
             tile_storage,
+            tile_cursor_storage,
             mut transform_storage,
             mut tutorial_step_storage,
             tutorial_ui_storage,
+            mut ui_commands_storage,
         ) = data;
 
         let input: &Input = input_storage.deref();
@@ -71,6 +66,7 @@ impl<'a> System<'a> for TileSelection {
         let researched_buffs: &ResearchedBuffs = researched_buffs.deref();
 
         let mut tile_type_selected = None;
+        let mut opened = false;
 
         for (tile, button, transform) in
             (&tile_storage, &mut button_storage, &transform_storage).join()
@@ -83,6 +79,44 @@ impl<'a> System<'a> for TileSelection {
             }
         }
 
+        let mut cursor_pos = None;
+        for (_, transform) in (&tile_cursor_storage, &transform_storage).join() {
+            if transform.visible {
+                cursor_pos = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
+
+        let mut selected_pos = None;
+        for (_, transform) in (&selected_tile_storage, &transform_storage).join() {
+            if transform.visible {
+                selected_pos = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
+
+        // the select key opens the build ui under the cursor, and while it is open it follows the cursor
+        let mut selected_with_cursor = false;
+        if let Some((cursor_x, cursor_y)) = cursor_pos {
+            let cursor_moved = self.cursor_pos.is_some() && self.cursor_pos != cursor_pos;
+            let select_pressed = input.action_pressed(InputAction::SelectTile);
+            if !clicked && selected_pos != cursor_pos
+                && (select_pressed || (cursor_moved && selected_pos.is_some()))
+            {
+                selected_with_cursor = true;
+                for (tile, button, transform) in
+                    (&tile_storage, &button_storage, &transform_storage).join()
+                {
+                    if !button.disabled && transform.get_pos().x == cursor_x
+                        && transform.get_pos().y == cursor_y
+                    {
+                        tile_mouse_x = cursor_x;
+                        tile_mouse_y = cursor_y;
+                        clicked = true;
+                        tile_type_selected = Some(tile.tile_type.clone());
+                    }
+                }
+            }
+        }
+
         if clicked {
             let mut tile_already_taken = false;
             // check if tile already selected
@@ -94,11 +128,19 @@ impl<'a> System<'a> for TileSelection {
             }
 
             if !tile_already_taken {
+                opened = true;
                 for (_, transform) in (&selected_tile_storage, &mut transform_storage).join() {
                     transform.visible = true;
                     transform.set_pos2(tile_mouse_x, tile_mouse_y);
                 }
 
+                // clicking a tile brings the cursor along, so keyboard input carries on from there
+                for (_, transform) in (&tile_cursor_storage, &mut transform_storage).join() {
+                    if transform.visible {
+                        transform.set_pos2(tile_mouse_x, tile_mouse_y);
+                    }
+                }
+
                 // if build UI showing, clean it up, as tile type may be different
                 if let Some(build_ui_entity) = self.build_ui_entity {
                     recursive_delete(&entities, &node_storage, &build_ui_entity);
@@ -125,7 +167,7 @@ impl<'a> System<'a> for TileSelection {
 
                 let changed = tutorial::next_step(
                     &entities,
-                    &mut actions_storage,
+                    &mut ui_commands_storage,
                     &mut tutorial_step_storage,
                     &tutorial_ui_storage,
                     &node_storage,
@@ -138,7 +180,7 @@ impl<'a> System<'a> for TileSelection {
                     // Passing array of possible current states not super comparable :(
                     tutorial::next_step(
                         &entities,
-                        &mut actions_storage,
+                        &mut ui_commands_storage,
                         &mut tutorial_step_storage,
                         &tutorial_ui_storage,
                         &node_storage,
@@ -147,7 +189,16 @@ impl<'a> System<'a> for TileSelection {
                     );
                 }
             }
-        } else {
+        }
+
+        if selected_with_cursor && !opened {
+            // the cursor moved off anything buildable, so the build ui closes rather than staying behind
+            for (_, transform) in (&selected_tile_storage, &mut transform_storage).join() {
+                transform.visible = false;
+            }
+        }
+
+        if !opened {
             for (_, transform) in (&selected_tile_storage, &transform_storage).join() {
                 // if selected tile as hidden, clear out build entity
                 if !transform.visible {
@@ -158,5 +209,12 @@ impl<'a> System<'a> for TileSelection {
                 }
             }
         }
+
+        self.cursor_pos = None;
+        for (_, transform) in (&tile_cursor_storage, &transform_storage).join() {
+            if transform.visible {
+                self.cursor_pos = Some((transform.get_pos().x, transform.get_pos().y));
+            }
+        }
     }
 }
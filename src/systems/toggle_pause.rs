@@ -1,7 +1,7 @@
-use components::{Actions, Button, CurrentState, EntityLookup, Input, InternalState, Node,
-                 StateChange};
+use components::{Anchor, Button, EntityLookup, Input, InternalState, Node, StateChange, StateId,
+                 Transition, UiCommand, UiCommands};
 use entities::create_colored_rect;
-use glutin::VirtualKeyCode;
+use key_bindings::InputAction;
 use specs::{Entities, Join, LazyUpdate, Read, System, Write, WriteStorage};
 use std::ops::{Deref, DerefMut};
 use systems::logic;
@@ -12,28 +12,26 @@ impl<'a> System<'a> for TogglePause {
     type SystemData = (
         Entities<'a>,
         Read<'a, LazyUpdate>,
-        Write<'a, Actions>,
         WriteStorage<'a, Button>,
-        Read<'a, CurrentState>,
         Write<'a, EntityLookup>,
         Read<'a, Input>,
         Read<'a, InternalState>,
         WriteStorage<'a, Node>,
         Write<'a, StateChange>,
+        Write<'a, UiCommands>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
             lazy,
-            mut actions_storage,
             mut button_storage,
-            current_state_storage,
             mut entity_lookup_storage,
             input,
             internal_state_storage,
             mut node_storage,
             mut state_change_storage,
+            mut ui_commands_storage,
         ) = data;
 
         let input: &Input = input.deref();
@@ -46,15 +44,21 @@ impl<'a> System<'a> for TogglePause {
                     transition_to_pause = true;
                 }
             }
+            if input.action_pressed(InputAction::Pause) {
+                transition_to_pause = true;
+            }
         }
 
-        let actions = actions_storage.deref_mut();
+        let resume_clicked = ui_commands_storage
+            .take(|command| match *command {
+                UiCommand::ResumeGame => Some(()),
+                _ => None,
+            })
+            .is_some();
 
         if *internal_state == InternalState::Pause
-            && (*input.pressed_keys.get(&VirtualKeyCode::Escape).unwrap()
-                || actions.action_fired("resume_game"))
+            && (input.action_pressed(InputAction::Pause) || resume_clicked)
         {
-            actions.remove("resume_game".to_string());
             let lookup = entity_lookup_storage.deref_mut();
             {
                 let entity = lookup.get("pause_black").unwrap();
@@ -62,7 +66,7 @@ impl<'a> System<'a> for TogglePause {
             }
             lookup.entities.remove("pause_black");
             let state_change: &mut StateChange = state_change_storage.deref_mut();
-            state_change.set(current_state_storage.0.clone(), "resume".to_string());
+            state_change.set(Transition::Pop);
         }
 
         if transition_to_pause {
@@ -76,12 +80,13 @@ impl<'a> System<'a> for TogglePause {
                 &entities,
                 &lazy,
             );
+            lazy.insert(entity, Anchor::fill());
             let lookup: &mut EntityLookup = entity_lookup_storage.deref_mut();
             lookup.entities.insert("pause_black".to_string(), entity);
             let root_node = logic::get_root(&lookup, &mut node_storage);
             root_node.add(entity);
             let state_change: &mut StateChange = state_change_storage.deref_mut();
-            state_change.set(current_state_storage.0.clone(), "pause".to_string());
+            state_change.set(Transition::Push(StateId::Pause));
         }
     }
 }
@@ -1,8 +1,10 @@
-use components::{ui::TutorialUI, upgrade::Buff, Actions, Button, EntityLookup, Input, Node,
-                 ResearchedBuffs, StateChange, Tile, Transform, TutorialStep, Wallet};
+use components::{ui::TutorialUI, upgrade::{Buff, Upgrade}, Anchor, Button, EntityLookup, Input,
+                 InternalState, MapBlocker, MapBlockers, Node, ResearchedBuffs, StateChange,
+                 StateId, Transform, Transition, TutorialStep, UiCommand, UiCommands, Viewport,
+                 Wallet, MAP_SIZE, SIDEBAR_WIDTH};
 use entities::{create_colored_rect, tutorial};
+use key_bindings::InputAction;
 use specs::{Entities, Join, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
-use state::play_state::PlayState;
 use std::ops::{Deref, DerefMut};
 use systems::logic;
 
@@ -17,23 +19,25 @@ impl ToggleTechTree {
         &mut self,
         lookup: &mut EntityLookup,
         input: &Input,
+        key_pressed: bool,
         entities: &Entities,
         lazy: &Read<LazyUpdate>,
-        actions_storage: &mut Write<Actions>,
+        ui_commands_storage: &mut Write<UiCommands>,
         button_storage: &mut WriteStorage<Button>,
         node_storage: &mut WriteStorage<Node>,
         transform_storage: &mut WriteStorage<Transform>,
         tutorial_step_storage: &mut Write<TutorialStep>,
         tutorial_ui_storage: &ReadStorage<TutorialUI>,
         state_change_res: &mut Write<StateChange>,
-        tile_storage: &ReadStorage<Tile>,
+        map_blockers: &mut MapBlockers,
+        viewport: &Viewport,
     ) {
         let mut was_clicked = false;
         {
             let button = button_storage
                 .get_mut(*lookup.get("show_button_entity").unwrap())
                 .unwrap();
-            if button.clicked(&input) {
+            if button.clicked(&input) || key_pressed {
                 {
                     let transform = transform_storage
                         .get_mut(*lookup.get("tech_tree_container").unwrap())
@@ -49,18 +53,21 @@ impl ToggleTechTree {
                 }
 
                 let state_change: &mut StateChange = state_change_res.deref_mut();
-                state_change.set(PlayState::get_name(), "tech_tree_pause".to_string());
+                state_change.set(Transition::Push(StateId::TechTree));
 
                 let rect = create_colored_rect::create(
                     0.0,
                     0.0,
                     8.0,
-                    640,
-                    640,
+                    MAP_SIZE as u16,
+                    MAP_SIZE as u16,
                     [0.0, 0.0, 0.0, 0.8],
                     entities,
                     lazy,
                 );
+                // covers the map and the space around it, up to the tech tree
+                let tech_tree_width = SIDEBAR_WIDTH * viewport.ui_scale;
+                lazy.insert(rect, Anchor::fill().margin(0.0, 0.0, tech_tree_width, 0.0));
                 lookup.entities.insert("pause_black".to_string(), rect);
                 let node = logic::get_root(&lookup, node_storage);
                 node.add(rect);
@@ -72,16 +79,14 @@ impl ToggleTechTree {
         if was_clicked {
             tutorial::next_step(
                 entities,
-                actions_storage,
+                ui_commands_storage,
                 tutorial_step_storage,
                 tutorial_ui_storage,
                 node_storage,
                 TutorialStep::ShowUpgrades,
                 TutorialStep::Upgrade,
             );
-            for (_, button) in (tile_storage, button_storage).join() {
-                button.set_disabled(true);
-            }
+            map_blockers.set(MapBlocker::TechTree, true);
         }
     }
 
@@ -89,22 +94,23 @@ impl ToggleTechTree {
         &mut self,
         lookup: &mut EntityLookup,
         input: &Input,
+        key_pressed: bool,
         entities: &Entities,
-        actions_storage: &mut Write<Actions>,
+        ui_commands_storage: &mut Write<UiCommands>,
         button_storage: &mut WriteStorage<Button>,
         node_storage: &WriteStorage<Node>,
         transform_storage: &mut WriteStorage<Transform>,
         tutorial_step_storage: &mut Write<TutorialStep>,
         tutorial_ui_storage: &ReadStorage<TutorialUI>,
         state_change_res: &mut Write<StateChange>,
-        tile_storage: &ReadStorage<Tile>,
+        map_blockers: &mut MapBlockers,
     ) {
         let mut was_clicked = false;
         {
             let button = button_storage
                 .get_mut(*lookup.get("resume_from_upgrades").unwrap())
                 .unwrap();
-            if button.clicked(&input) {
+            if button.clicked(&input) || key_pressed {
                 {
                     let transform = transform_storage
                         .get_mut(*lookup.get("tech_tree_container").unwrap())
@@ -120,10 +126,13 @@ impl ToggleTechTree {
                 }
 
                 let state_change: &mut StateChange = state_change_res.deref_mut();
-                state_change.set(PlayState::get_name(), "resume".to_string());
+                state_change.set(Transition::Pop);
                 let overlay_entity = *lookup.get("pause_black").unwrap();
                 entities.delete(overlay_entity).unwrap();
                 lookup.entities.remove("pause_black");
+                if let Some(highlight_entity) = lookup.entities.remove("tech_node_highlight") {
+                    entities.delete(highlight_entity).unwrap();
+                }
                 was_clicked = true;
             }
         }
@@ -131,35 +140,73 @@ impl ToggleTechTree {
         if was_clicked {
             tutorial::next_step(
                 entities,
-                actions_storage,
+                ui_commands_storage,
                 tutorial_step_storage,
                 tutorial_ui_storage,
                 node_storage,
                 TutorialStep::Resume,
                 TutorialStep::Objective(20.0),
             );
-            for (_, button) in (tile_storage, button_storage).join() {
-                button.set_disabled(false);
-            }
+            map_blockers.set(MapBlocker::TechTree, false);
         }
     }
+
+    // frames the node a notification pointed at, until the tech tree is closed
+    fn highlight_tech_node(
+        &mut self,
+        lookup: &mut EntityLookup,
+        buff: Buff,
+        entities: &Entities,
+        lazy: &Read<LazyUpdate>,
+        node_storage: &mut WriteStorage<Node>,
+        transform_storage: &WriteStorage<Transform>,
+        upgrade_storage: &ReadStorage<Upgrade>,
+    ) {
+        let upgrade_entity = (&**entities, upgrade_storage)
+            .join()
+            .find(|&(_, upgrade)| upgrade.buff == buff)
+            .map(|(entity, _)| entity);
+        let transform = match upgrade_entity.and_then(|entity| transform_storage.get(entity)) {
+            Some(transform) => transform,
+            None => return,
+        };
+
+        let rect = create_colored_rect::create(
+            transform.get_pos().x - 4.0,
+            transform.get_pos().y - 4.0,
+            0.9,
+            transform.size.x + 8,
+            transform.size.y + 8,
+            [1.0, 1.0, 0.0, 1.0],
+            entities,
+            lazy,
+        );
+        node_storage
+            .get_mut(*lookup.get("tech_tree_container").unwrap())
+            .unwrap()
+            .add(rect);
+        lookup.entities.insert("tech_node_highlight".to_string(), rect);
+    }
 }
 
 impl<'a> System<'a> for ToggleTechTree {
     type SystemData = (
         Entities<'a>,
         Read<'a, LazyUpdate>,
-        Write<'a, Actions>,
         WriteStorage<'a, Button>,
         Write<'a, EntityLookup>,
         Read<'a, Input>,
+        Read<'a, InternalState>,
+        Write<'a, MapBlockers>,
         WriteStorage<'a, Node>,
         Read<'a, ResearchedBuffs>,
         Write<'a, StateChange>,
-        ReadStorage<'a, Tile>,
         WriteStorage<'a, Transform>,
         Write<'a, TutorialStep>,
         ReadStorage<'a, TutorialUI>,
+        Write<'a, UiCommands>,
+        ReadStorage<'a, Upgrade>,
+        Read<'a, Viewport>,
         Read<'a, Wallet>,
     );
 
@@ -167,17 +214,20 @@ impl<'a> System<'a> for ToggleTechTree {
         let (
             entities,
             lazy,
-            mut actions_storage,
             mut button_storage,
             mut lookup,
             input,
+            internal_state_storage,
+            mut map_blockers_storage,
             mut node_storage,
             researched_buffs_storage,
             mut state_change_res,
-            tile_storage,
             mut transform_storage,
             mut tutorial_step_storage,
             tutorial_ui_storage,
+            mut ui_commands_storage,
+            upgrade_storage,
+            viewport_storage,
             wallet_storage,
         ) = data;
 
@@ -189,7 +239,7 @@ impl<'a> System<'a> for ToggleTechTree {
             if wallet_storage.get_money() >= 50 {
                 tutorial::next_step(
                     &entities,
-                    &mut actions_storage,
+                    &mut ui_commands_storage,
                     &mut tutorial_step_storage,
                     &tutorial_ui_storage,
                     &node_storage,
@@ -200,7 +250,7 @@ impl<'a> System<'a> for ToggleTechTree {
         } else if *tutorial_step_storage.deref() == TutorialStep::ResourcesSold {
             tutorial::next_step(
                 &entities,
-                &mut actions_storage,
+                &mut ui_commands_storage,
                 &mut tutorial_step_storage,
                 &tutorial_ui_storage,
                 &node_storage,
@@ -211,32 +261,58 @@ impl<'a> System<'a> for ToggleTechTree {
 
         let mut lookup: &mut EntityLookup = lookup.deref_mut();
         let input: &Input = input.deref();
+        // the same key opens and closes the tech tree, so it only acts on the state it is in
+        let internal_state: &InternalState = internal_state_storage.deref();
+        let toggle_pressed = input.action_pressed(InputAction::OpenTechTree);
+        // clicking a research notification opens the tree at its node
+        let shown_node = if *internal_state == InternalState::Game {
+            ui_commands_storage.take(|command| match *command {
+                UiCommand::ShowTechNode(buff) => Some(buff),
+                _ => None,
+            })
+        } else {
+            None
+        };
         self.check_show_tech_tree(
             &mut lookup,
             &input,
+            (toggle_pressed || shown_node.is_some()) && *internal_state == InternalState::Game,
             &entities,
             &lazy,
-            &mut actions_storage,
+            &mut ui_commands_storage,
             &mut button_storage,
             &mut node_storage,
             &mut transform_storage,
             &mut tutorial_step_storage,
             &tutorial_ui_storage,
             &mut state_change_res,
-            &tile_storage,
+            &mut map_blockers_storage,
+            &viewport_storage,
         );
+        if let Some(buff) = shown_node {
+            self.highlight_tech_node(
+                &mut lookup,
+                buff,
+                &entities,
+                &lazy,
+                &mut node_storage,
+                &transform_storage,
+                &upgrade_storage,
+            );
+        }
         self.check_resume_from_tech_tree(
             &mut lookup,
             &input,
+            toggle_pressed && *internal_state == InternalState::TechTree,
             &entities,
-            &mut actions_storage,
+            &mut ui_commands_storage,
             &mut button_storage,
             &node_storage,
             &mut transform_storage,
             &mut tutorial_step_storage,
             &tutorial_ui_storage,
             &mut state_change_res,
-            &tile_storage,
+            &mut map_blockers_storage,
         );
     }
 }
@@ -0,0 +1,101 @@
+use specs::{Read, System, Write};
+
+use achievements::{Achievement, Achievements, TYCOON_MONEY};
+use components::{upgrade::Buff, CityPowerState, EventChannel, EventReader, GameEvent,
+                 GathererType, Notifications, ResearchedBuffs, RunStats, Wallet};
+use localization::Localization;
+
+pub struct TrackAchievements {
+    game_events: EventReader,
+}
+
+impl TrackAchievements {
+    pub fn new() -> Self {
+        TrackAchievements {
+            game_events: EventReader::new(),
+        }
+    }
+
+    fn is_achieved(
+        &self,
+        achievement: &Achievement,
+        city_power_state: &CityPowerState,
+        researched_buffs: &ResearchedBuffs,
+        run_stats: &RunStats,
+        wallet: &Wallet,
+    ) -> bool {
+        match *achievement {
+            Achievement::CleanGrid => {
+                city_power_state.current_city_count >= 10
+                    && run_stats.get_built_for_type(&GathererType::Coal) == 0
+            }
+            Achievement::SolarPioneer => [
+                Buff::Solar,
+                Buff::PurchaseSolarCellCompany,
+                Buff::ImprovePanelTech,
+                Buff::SellPanelsToConsumers,
+            ].iter()
+                .all(|buff| researched_buffs.0.contains_key(buff)),
+            Achievement::TaxFree => {
+                city_power_state.current_city_count >= 5 && run_stats.total_tax == 0
+            }
+            Achievement::Hydrologist => run_stats.get_built_for_type(&GathererType::Hydro) >= 5,
+            Achievement::Tycoon => wallet.get_money() >= TYCOON_MONEY,
+        }
+    }
+}
+
+impl<'a> System<'a> for TrackAchievements {
+    type SystemData = (
+        Write<'a, Achievements>,
+        Read<'a, CityPowerState>,
+        Read<'a, EventChannel<GameEvent>>,
+        Read<'a, Localization>,
+        Write<'a, Notifications>,
+        Read<'a, ResearchedBuffs>,
+        Read<'a, RunStats>,
+        Read<'a, Wallet>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut achievements_storage,
+            city_power_state_storage,
+            game_events_storage,
+            localization_storage,
+            mut notifications_storage,
+            researched_buffs_storage,
+            run_stats_storage,
+            wallet_storage,
+        ) = data;
+
+        // every achievement follows from something happening in the game, so there is nothing
+        // new to check on a quiet frame
+        if game_events_storage.read(&mut self.game_events).is_empty() {
+            return;
+        }
+
+        for achievement in Achievement::all() {
+            if achievements_storage.is_unlocked(&achievement) {
+                continue;
+            }
+
+            if self.is_achieved(
+                &achievement,
+                &city_power_state_storage,
+                &researched_buffs_storage,
+                &run_stats_storage,
+                &wallet_storage,
+            ) && achievements_storage.unlock(achievement)
+            {
+                notifications_storage.info(
+                    localization_storage.format(
+                        "achievement.unlocked",
+                        &[("title", achievement.get_title(&localization_storage))],
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+}
@@ -1,22 +1,28 @@
-use components::{ui::TutorialUI, Actions, Color, DeltaTime, EntityLookup, Node, Pulse, Rect,
-                 Shape, Text, Tile, TileNodes, TileType, Transform, TutorialStep};
+use components::{ui::TutorialUI, Anchor, Color, DeltaTime, EntityLookup, EventChannel,
+                 EventReader, GameEvent, Node, Pulse, Rect, Shape, Text, Tile, TileNodes, TileType,
+                 Transform, TutorialStep, UiCommand, UiCommands, Wallet, SIDEBAR_WIDTH};
 use entities::{recursive_delete, tutorial};
-use renderer;
+use localization::Localization;
 use settings::Settings;
 use specs::{Entities, Join, Read, System, Write, WriteStorage};
 use std::ops::{Deref, DerefMut};
 
-struct StepCreationDetails<'a> {
+struct StepCreationDetails {
+    // lookup name of the node the highlight is placed in
+    parent: &'static str,
+    anchor: Option<Anchor>,
     x: f32,
     y: f32,
     w: f32,
     h: f32,
-    message: &'a str,
+    message: String,
 }
 
-impl<'a> StepCreationDetails<'a> {
-    fn new(x: f32, y: f32, w: f32, h: f32, message: &'a str) -> Self {
+impl StepCreationDetails {
+    fn new(x: f32, y: f32, w: f32, h: f32, message: String) -> Self {
         StepCreationDetails {
+            parent: "root",
+            anchor: None,
             x,
             y,
             w,
@@ -24,15 +30,45 @@ impl<'a> StepCreationDetails<'a> {
             message,
         }
     }
+
+    fn in_node(mut self, parent: &'static str) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    // highlights an entity of the parent node, following its anchor
+    fn around(
+        parent: &'static str,
+        entity_name: &str,
+        anchor_storage: &WriteStorage<Anchor>,
+        lookup: &EntityLookup,
+        transform_storage: &WriteStorage<Transform>,
+        message: String,
+    ) -> Self {
+        let entity = *lookup.get(entity_name).unwrap();
+        let transform = transform_storage.get(entity).unwrap();
+        let pos = transform.get_pos();
+        let mut details = StepCreationDetails::new(
+            pos.x,
+            pos.y,
+            transform.size.x as f32,
+            transform.size.y as f32,
+            message,
+        ).in_node(parent);
+        details.anchor = anchor_storage.get(entity).cloned();
+        details
+    }
 }
 
 pub struct Tutorial {
+    game_events: EventReader,
     hide_last_step_time: f32,
 }
 
 impl Tutorial {
     pub fn new() -> Self {
         Tutorial {
+            game_events: EventReader::new(),
             hide_last_step_time: 0.0,
         }
     }
@@ -41,10 +77,12 @@ impl Tutorial {
 impl<'a> System<'a> for Tutorial {
     type SystemData = (
         Entities<'a>,
-        Write<'a, Actions>,
+        WriteStorage<'a, Anchor>,
         WriteStorage<'a, Color>,
         Read<'a, DeltaTime>,
         Read<'a, EntityLookup>,
+        Read<'a, EventChannel<GameEvent>>,
+        Read<'a, Localization>,
         WriteStorage<'a, Node>,
         WriteStorage<'a, Pulse>,
         WriteStorage<'a, Rect>,
@@ -55,15 +93,19 @@ impl<'a> System<'a> for Tutorial {
         WriteStorage<'a, Transform>,
         Write<'a, TutorialStep>,
         WriteStorage<'a, TutorialUI>,
+        Write<'a, UiCommands>,
+        Read<'a, Wallet>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            mut actions_storage,
+            mut anchor_storage,
             mut color_storage,
             delta_time_storage,
             entity_lookup_storage,
+            game_events_storage,
+            localization_storage,
             mut node_storage,
             mut pulse_storage,
             mut rect_storage,
@@ -74,133 +116,176 @@ impl<'a> System<'a> for Tutorial {
             mut transform_storage,
             mut tutorial_step_storage,
             mut tutorial_ui_storage,
+            mut ui_commands_storage,
+            wallet_storage,
         ) = data;
 
         // TOOD: return out from this function if settings has tutorial completed as true
 
-        let actions = actions_storage.deref_mut();
+        let mut energy_sold = false;
+        for event in game_events_storage.read(&mut self.game_events) {
+            match *event {
+                // building clears the steps that ask for it
+                GameEvent::GathererBuilt { .. } => {
+                    for step in &[TutorialStep::BuildCoal(0.0, 0.0), TutorialStep::ResourcesSold] {
+                        tutorial::clear_ui(
+                            &entities,
+                            &tutorial_step_storage,
+                            &tutorial_ui_storage,
+                            &node_storage,
+                            *step,
+                        );
+                    }
+                }
+                GameEvent::EnergySold { .. } => energy_sold = true,
+                _ => {}
+            }
+        }
+
+        if energy_sold && wallet_storage.get_money() > 10
+            && *tutorial_step_storage == TutorialStep::CoalGathered
+        {
+            tutorial::clear_ui(
+                &entities,
+                &tutorial_step_storage,
+                &tutorial_ui_storage,
+                &node_storage,
+                TutorialStep::CoalGathered,
+            );
+            *tutorial_step_storage = TutorialStep::ResourcesSold;
+            ui_commands_storage.push(UiCommand::ShowTutorialStep(TutorialStep::ResourcesSold));
+        }
+
+        let shown_step = ui_commands_storage.take(|command| match *command {
+            UiCommand::ShowTutorialStep(step) => Some(step),
+            _ => None,
+        });
 
         let mut details = None;
 
-        if actions.action_fired(&TutorialStep::SelectTile.as_string()) {
-            actions.remove(TutorialStep::SelectTile.as_string());
-            let tile_nodes = tile_nodes_storage.deref();
-
-            let mut target_cell = (0.0, 0.0);
-
-            for x in 0i32..10i32 {
-                for y in 0i32..10i32 {
-                    if let Some((tile_type, _)) = tile_nodes.nodes.get(&(x, y)) {
-                        if *tile_type == TileType::Open {
-                            let mut safe_space = true;
-                            'check_neighbours: for x2 in -1..2 {
-                                for y2 in -1..2 {
-                                    if x2 == 0 && y2 == 0 {
-                                        continue;
-                                    }
-                                    if let Some((tile_type, _)) =
-                                        tile_nodes.nodes.get(&(x2 + x, y2 + y))
-                                    {
-                                        if *tile_type != TileType::Open {
-                                            safe_space = false;
-                                            break 'check_neighbours;
+        match shown_step {
+            Some(TutorialStep::SelectTile) => {
+                let tile_nodes = tile_nodes_storage.deref();
+
+                let mut target_cell = (0.0, 0.0);
+
+                for x in 0i32..10i32 {
+                    for y in 0i32..10i32 {
+                        if let Some((tile_type, _)) = tile_nodes.nodes.get(&(x, y)) {
+                            if *tile_type == TileType::Open {
+                                let mut safe_space = true;
+                                'check_neighbours: for x2 in -1..2 {
+                                    for y2 in -1..2 {
+                                        if x2 == 0 && y2 == 0 {
+                                            continue;
+                                        }
+                                        if let Some((tile_type, _)) =
+                                            tile_nodes.nodes.get(&(x2 + x, y2 + y))
+                                        {
+                                            if *tile_type != TileType::Open {
+                                                safe_space = false;
+                                                break 'check_neighbours;
+                                            }
                                         }
                                     }
                                 }
-                            }
 
-                            if safe_space {
-                                target_cell.0 = x as f32;
-                                target_cell.1 = y as f32;
+                                if safe_space {
+                                    target_cell.0 = x as f32;
+                                    target_cell.1 = y as f32;
+                                }
                             }
                         }
                     }
                 }
-            }
 
-            let size = Tile::get_size();
-            target_cell.0 *= size;
-            target_cell.1 *= size;
-
-            details = Some(StepCreationDetails::new(
-                target_cell.0,
-                target_cell.1,
-                size,
-                size,
-                "To start collecting resources, click the glowing tile",
-            ));
-        } else if actions.action_fired(&TutorialStep::BuildCoal(0.0, 0.0).as_string()) {
-            actions.remove(TutorialStep::BuildCoal(0.0, 0.0).as_string());
-            let tutorial_step = tutorial_step_storage.deref();
-            match *tutorial_step {
-                TutorialStep::BuildCoal(x, y) => {
-                    details = Some(StepCreationDetails::new(
-                        x + 10.0,
-                        y + 10.0,
-                        64.0,
-                        64.0,
-                        "Click the icon here to build a coal mine operation",
-                    ));
-                }
-                _ => {}
+                let size = Tile::get_size();
+                target_cell.0 *= size;
+                target_cell.1 *= size;
+
+                details = Some(StepCreationDetails::new(
+                    target_cell.0,
+                    target_cell.1,
+                    size,
+                    size,
+                    localization_storage.get("tutorial.select_tile"),
+                ));
             }
-        } else if actions.action_fired(&TutorialStep::CoalGathered.as_string()) {
-            actions.remove(TutorialStep::CoalGathered.as_string());
-            details = Some(StepCreationDetails::new(
-                670.0,
-                235.0,
-                100.0,
-                30.0,
-                "After building the coal mine, you are now collecting coal as a resource.\nThis is then sold to add power to the city",
-            ));
-        } else if actions.action_fired(&TutorialStep::ResourcesSold.as_string()) {
-            actions.remove(TutorialStep::ResourcesSold.as_string());
-            details = Some(StepCreationDetails::new(
-                670.0,
-                32.0,
-                280.0,
-                360.0,
-                "When you sell, the resources go to the power grid filling up the city's power. Your money also goes up.\n\nUse money to keep building. Be wary of building next to a tile occupied by a city or by nature."
-            ));
-        } else if actions.action_fired(&TutorialStep::ShowUpgrades.as_string()) {
-            actions.remove(TutorialStep::ShowUpgrades.as_string());
-            details = Some(StepCreationDetails::new(
-                752.0,
-                576.0,
-                96.0,
-                32.0,
-                "Click the button to the bottom right to view available upgrades. Don't worry, this button pauses the game."
-            ));
-        } else if actions.action_fired(&TutorialStep::Upgrade.as_string()) {
-            actions.remove(TutorialStep::Upgrade.as_string());
-            let dimensions = renderer::get_dimensions();
-            let width = dimensions[0] - 640.0;
-            details = Some(StepCreationDetails::new(
-                640.0 + width * 0.75 - 16.0,
-                96.0,
-                32.0,
-                32.0,
-                "Research this upgrade to collect more resources when one mine, or other resource collection facilities of the same type are adjacent.\nSome upgrades like this one can upgraded to infinite levels."
-            ));
-        } else if actions.action_fired(&TutorialStep::Resume.as_string()) {
-            actions.remove(TutorialStep::Resume.as_string());
-            details = Some(StepCreationDetails::new(
-                752.0,
-                576.0,
-                96.0,
-                32.0,
-                "Resume the game to continue playing, and let the upgrade research.",
-            ));
-        } else if actions.action_fired(&TutorialStep::Objective(0.0).as_string()) {
-            actions.remove(TutorialStep::Objective(0.0).as_string());
-            self.hide_last_step_time = 10.0;
-            details = Some(StepCreationDetails::new(
-                670.0,
-                32.0,
-                280.0,
-                85.0,
-                "When the power # becomes positive, it means you are producing enough to supply this city. Click \"power additional city\" to increase the difficulty. Produce the most power for the most cities that you can.\n\nBest of luck!"
-            ));
+            Some(TutorialStep::BuildCoal(x, y)) => {
+                details = Some(StepCreationDetails::new(
+                    x + 10.0,
+                    y + 10.0,
+                    64.0,
+                    64.0,
+                    localization_storage.get("tutorial.build_coal"),
+                ));
+            }
+            Some(TutorialStep::CoalGathered) => {
+                details = Some(
+                    StepCreationDetails::new(
+                        30.0,
+                        235.0,
+                        100.0,
+                        30.0,
+                        localization_storage.get("tutorial.coal_gathered"),
+                    ).in_node("side_bar_container"),
+                );
+            }
+            Some(TutorialStep::ResourcesSold) => {
+                details = Some(
+                    StepCreationDetails::new(
+                        30.0,
+                        32.0,
+                        280.0,
+                        360.0,
+                        localization_storage.get("tutorial.resources_sold"),
+                    ).in_node("side_bar_container"),
+                );
+            }
+            Some(TutorialStep::ShowUpgrades) => {
+                details = Some(StepCreationDetails::around(
+                    "side_bar_container",
+                    "show_button_entity",
+                    &anchor_storage,
+                    &entity_lookup_storage,
+                    &transform_storage,
+                    localization_storage.get("tutorial.show_upgrades"),
+                ));
+            }
+            Some(TutorialStep::Upgrade) => {
+                details = Some(
+                    StepCreationDetails::new(
+                        SIDEBAR_WIDTH * 0.75 - 16.0,
+                        96.0,
+                        32.0,
+                        32.0,
+                        localization_storage.get("tutorial.upgrade"),
+                    ).in_node("tech_tree_container"),
+                );
+            }
+            Some(TutorialStep::Resume) => {
+                details = Some(StepCreationDetails::around(
+                    "tech_tree_container",
+                    "resume_from_upgrades",
+                    &anchor_storage,
+                    &entity_lookup_storage,
+                    &transform_storage,
+                    localization_storage.get("tutorial.resume"),
+                ));
+            }
+            Some(TutorialStep::Objective(_)) => {
+                self.hide_last_step_time = 10.0;
+                details = Some(
+                    StepCreationDetails::new(
+                        30.0,
+                        32.0,
+                        280.0,
+                        85.0,
+                        localization_storage.get("tutorial.objective"),
+                    ).in_node("side_bar_container"),
+                );
+            }
+            _ => {}
         }
 
         let tutorial_step = tutorial_step_storage.deref_mut();
@@ -224,6 +309,7 @@ impl<'a> System<'a> for Tutorial {
         if let Some(details) = details {
             tutorial::create_step(
                 &entities,
+                &mut anchor_storage,
                 &mut color_storage,
                 &entity_lookup_storage,
                 &mut node_storage,
@@ -233,11 +319,13 @@ impl<'a> System<'a> for Tutorial {
                 &mut text_storage,
                 &mut tutorial_ui_storage,
                 &mut transform_storage,
+                details.parent,
+                details.anchor,
                 details.x,
                 details.y,
                 details.w,
                 details.h,
-                details.message,
+                &details.message,
             );
         }
     }
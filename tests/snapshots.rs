@@ -0,0 +1,127 @@
+extern crate image;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Once;
+
+use image::RgbaImage;
+
+// how far a channel may drift before the pixel counts as changed, font rasterizing and
+// filtering differ a little between platforms
+const CHANNEL_TOLERANCE: u8 = 8;
+// the share of pixels that may change before the snapshot fails
+const PIXEL_TOLERANCE: f64 = 0.005;
+
+static COPY_RESOURCES: Once = Once::new();
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let target = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &target);
+        } else {
+            fs::copy(&path, &target).unwrap();
+        }
+    }
+}
+
+/**
+ * Renders the first frame of the game in snapshot mode and compares it to
+ * tests/snapshots/<name>.png. A missing reference is written from the frame and the test is
+ * skipped, so it gets looked at before being committed. UPDATE_SNAPSHOTS=1 rewrites the references
+ * after an intended change. A failing frame is kept in target/snapshot_diffs to compare by eye
+ */
+fn check_snapshot(name: &str, args: &[&str]) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // the game reads its resources from next to the binary, as scripts/copy_resources.sh sets up
+    COPY_RESOURCES.call_once(|| {
+        let exe_dir = Path::new(env!("CARGO_BIN_EXE_ld39")).parent().unwrap();
+        copy_dir(&root.join("resources"), &exe_dir.join("resources"));
+    });
+    let out_dir = env::temp_dir().join(format!("energy_grid_test_{}", name));
+    fs::remove_dir_all(&out_dir).ok();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ld39"))
+        .current_dir(root)
+        .arg("--snapshot")
+        .arg(&out_dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "Snapshot {} failed: {}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let frame_path = out_dir.join("frame_0001.png");
+    let frame = image::open(&frame_path).unwrap().to_rgba();
+    let reference_path = root.join("tests/snapshots").join(format!("{}.png", name));
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok() || !reference_path.exists() {
+        if !reference_path.exists() {
+            println!(
+                "Skipped {}, wrote the missing reference {} to check and commit",
+                name,
+                reference_path.display()
+            );
+        }
+        fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        frame.save(&reference_path).unwrap();
+        fs::remove_dir_all(&out_dir).ok();
+        return;
+    }
+
+    let reference = image::open(&reference_path).unwrap().to_rgba();
+    let result = compare(&reference, &frame);
+    if let Err(ref err) = result {
+        let diff_path: PathBuf = root.join("target/snapshot_diffs").join(format!("{}.png", name));
+        fs::create_dir_all(diff_path.parent().unwrap()).unwrap();
+        frame.save(&diff_path).unwrap();
+        fs::remove_dir_all(&out_dir).ok();
+        panic!("Snapshot {} differs: {}, the frame is at {}", name, err, diff_path.display());
+    }
+    fs::remove_dir_all(&out_dir).ok();
+}
+
+fn compare(reference: &RgbaImage, frame: &RgbaImage) -> Result<(), String> {
+    if reference.dimensions() != frame.dimensions() {
+        return Err(format!(
+            "the size is {:?} instead of {:?}",
+            frame.dimensions(),
+            reference.dimensions()
+        ));
+    }
+
+    let changed = reference
+        .pixels()
+        .zip(frame.pixels())
+        .filter(|&(a, b)| {
+            a.data
+                .iter()
+                .zip(b.data.iter())
+                .any(|(a, b)| (*a as i16 - *b as i16).abs() > CHANNEL_TOLERANCE as i16)
+        })
+        .count();
+    let total = (reference.width() * reference.height()) as f64;
+    if changed as f64 / total > PIXEL_TOLERANCE {
+        return Err(format!("{} of {} pixels changed", changed, total));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn menu() {
+    check_snapshot("menu", &["--state", "menu", "--seed", "1"]);
+}
+
+#[test]
+fn play_start() {
+    check_snapshot("play_start", &["--state", "play", "--seed", "1"]);
+}